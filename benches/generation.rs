@@ -0,0 +1,127 @@
+//! Measures the phases of generating the docs of a large synthetic library, so changes that
+//! are meant to make generation faster can be compared against the previous version.
+//!
+//! Run with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uiua_doc_gen::analysis::required_permissions;
+use uiua_doc_gen::changes::{build_api_snapshot, build_time};
+use uiua_doc_gen::extractor::{extract_uiua_definitions, ExtractOptions, FileContent};
+use uiua_doc_gen::formatter::format_source_code;
+use uiua_doc_gen::generator::{generate_documentation_site, GeneratorOptions};
+use uiua_doc_gen::metadata::ProjectMetadata;
+use uiua_doc_gen::registry::build_registry_package;
+use uiua_doc_gen::summarizer::summarize_content;
+
+/// Each group has a constant, three functions, a module and a data definition.
+const GROUPS: usize = 60;
+
+/// Identifiers can't contain digits, so groups are told apart by subscripts.
+fn subscript(number: usize) -> String {
+    number
+        .to_string()
+        .chars()
+        .map(|digit| char::from_u32('₀' as u32 + digit.to_digit(10).unwrap()).unwrap())
+        .collect()
+}
+
+fn synthetic_library() -> String {
+    let mut source =
+        "# Experimental!\n\n# !doc\n# # Synthetic library\n#\n# A large library for measuring how long generating its docs takes.\n\n".to_owned();
+    for group in 0..GROUPS {
+        let n = subscript(group);
+        source.push_str(&format!(
+            "# The number {group}
+Value{n} ← {group}
+
+# Adds {group} to a number
+# ? x
+Add{n} ← +{group}
+
+# Joins the rotated first array with the reversed second
+# ? a b
+Join{n} ← ⊂⊃(↻1|⇌)
+
+# Keeps the rows of an array that are above the mean
+# ? rows
+AboveMean{n} ← ▽⊸(>÷⊃⧻/+.)
+
+# Helpers of group {group}
+┌─╴Group{n}
+  # Doubles a number
+  # ? x
+  Double ← ×2
+
+  # Squares a number
+  # ? x
+  Square ← ×.
+└─╴
+
+# A point in group {group}
+~Point{n} {{X Y}}
+
+"
+        ));
+    }
+    source
+}
+
+fn library_directory() -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("uiua-doc-gen-bench-{}", std::process::id()));
+    fs::create_dir_all(&directory).expect("Unable to create the library directory");
+    fs::write(directory.join("lib.ua"), synthetic_library()).expect("Unable to write the library");
+    directory
+}
+
+fn extract(directory: &Path) -> Vec<FileContent> {
+    extract_uiua_definitions(directory, &ExtractOptions::default()).expect("Unable to extract the synthetic library")
+}
+
+fn render(directory: &Path, files: &[FileContent]) {
+    let main_file = files.iter().find(|file| file.main).unwrap();
+    let metadata = ProjectMetadata {
+        name: "Synthetic".to_owned(),
+        title: None,
+        version: None,
+        description: None,
+        repository: None,
+        documentation: None,
+        library_file: None,
+        sections: Default::default(),
+        groups: Vec::new(),
+    };
+    let options = GeneratorOptions::default();
+    let api = build_api_snapshot(&main_file.items, &metadata, None, None, build_time(true));
+    let registry = build_registry_package(&main_file.items, &metadata);
+    let permissions = required_permissions(files);
+    let summary = summarize_content(main_file, main_file, &metadata, Vec::new(), None, permissions, &options.markdown_hooks);
+    generate_documentation_site(&directory.join("doc-site"), summary, files, &api, &registry, &options).expect("Unable to generate the docs");
+}
+
+fn generation(c: &mut Criterion) {
+    let directory = library_directory();
+    let files = extract(&directory);
+
+    let mut group = c.benchmark_group("generation");
+    group.sample_size(10);
+
+    group.bench_function("extraction", |b| b.iter(|| extract(&directory)));
+
+    group.bench_function("highlighting", |b| {
+        b.iter(|| {
+            for file in &files {
+                format_source_code(&file.source, None);
+            }
+        })
+    });
+
+    group.bench_function("rendering", |b| b.iter(|| render(&directory, &files)));
+
+    group.finish();
+    let _ = fs::remove_dir_all(directory);
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
@@ -1,8 +1,446 @@
+// The page is readable without scripts, the styles only hide the controls that need them once they run
+document.documentElement.classList.add('js');
+
+// The script is loaded in the head, so the chosen theme is applied before the page is shown. The generator
+// defines the default theme above
+if ((localStorage.getItem('theme') || defaultTheme) === 'light') {
+    document.documentElement.classList.add('light');
+}
+
 document.addEventListener('DOMContentLoaded', function() {
-    const menuButton = document.querySelector('.mobile-nav .hamburger');
+    document.querySelector('.theme-toggle').addEventListener('click', function() {
+        const light = document.documentElement.classList.toggle('light');
+        localStorage.setItem('theme', light ? 'light' : 'dark');
+    });
+
+    if (document.querySelector('.source-file')) {
+        highlightSourceLines();
+        window.addEventListener('hashchange', highlightSourceLines);
+    }
+
+    // Only the front page has the search index, the search box of the other pages takes the query there
+    if (!document.getElementById('search-index')) {
+        return;
+    }
+
+    const menuButton = document.querySelector('.site-header .hamburger');
     const mobileNav = document.querySelector('.sidebar');
 
     menuButton.addEventListener('click', function() {
         mobileNav.classList.toggle('open');
     });
-});
\ No newline at end of file
+
+    // Panels are replaced with their new versions when the site is regenerated by `serve`, so the controls in them
+    // are handled for the whole document
+    document.addEventListener('click', function(event) {
+        const button = event.target.closest('.copy-button');
+        if (!button) {
+            return;
+        }
+        navigator.clipboard.writeText(button.dataset.copy).then(function() {
+            button.textContent = 'Copied';
+            setTimeout(function() {
+                button.textContent = 'Copy';
+            }, 1500);
+        });
+    });
+
+    // The styles collapse the groups once the script runs, without it every list is shown
+    document.querySelectorAll('.minimap-toggle').forEach(function(button) {
+        button.addEventListener('click', function() {
+            const group = button.closest('.minimap-group');
+            const expanded = group.classList.toggle('expanded');
+            const container = group.querySelector('.minimap-items');
+            if (expanded && !container.dataset.virtualized) {
+                container.dataset.virtualized = 'true';
+                virtualizeMinimapItems(container);
+            }
+            button.textContent = expanded ? '▾' : '▸';
+            button.setAttribute('aria-expanded', expanded);
+        });
+    });
+
+    // Panels loaded from fragments come with their own explorers, so the toggle is caught for the whole document
+    document.addEventListener('toggle', function(event) {
+        const target = event.target;
+        if (!target.classList || !target.open) {
+            return;
+        }
+        if (target.classList.contains('stack-explorer') && !target.dataset.rendered) {
+            target.dataset.rendered = 'true';
+            renderStackExplorer(target);
+        } else if (target.classList.contains('lazy-panel') && !target.dataset.loaded) {
+            target.dataset.loaded = 'true';
+            loadPanelFragment(target);
+        }
+    }, true);
+
+    const searchInput = document.querySelector('.search-input');
+    const searchResults = document.querySelector('.search-results');
+    const search = function(query) {
+        loadSearchIndex().then(function(searchIndex) {
+            // A large index can arrive after the query has changed again
+            if (searchInput.value === query) {
+                renderSearchResults(searchResults, query, searchIndex);
+            }
+        }).catch(function() {
+            const error = document.createElement('li');
+            error.className = 'search-empty';
+            error.textContent = 'Unable to load the search index. Pages opened from the file system can\'t load it, serve the site over HTTP instead.';
+            searchResults.replaceChildren(error);
+        });
+    };
+
+    searchInput.addEventListener('focus', loadSearchIndex);
+    searchInput.addEventListener('input', function() {
+        search(searchInput.value);
+    });
+
+    searchInput.closest('form').addEventListener('submit', function(event) {
+        event.preventDefault();
+        const firstResult = searchResults.querySelector('a');
+        if (firstResult) {
+            firstResult.click();
+        }
+    });
+
+    // The results are shown over the page, so they're closed once one is picked
+    searchResults.addEventListener('click', function(event) {
+        if (event.target.closest('a')) {
+            searchResults.replaceChildren();
+        }
+    });
+
+    const query = new URLSearchParams(window.location.search).get('search');
+    if (query) {
+        searchInput.value = query;
+        search(query);
+        searchInput.focus();
+    }
+
+    document.addEventListener('click', function(event) {
+        const link = event.target.closest('.permalink');
+        if (!link || !navigator.clipboard) {
+            return;
+        }
+
+        event.preventDefault();
+        const url = new URL(link.getAttribute('href'), window.location.href).href;
+        history.replaceState(null, '', url);
+        // Share pages carry the item's title and summary, for the previews of social platforms
+        const share = link.dataset.share ? new URL(link.dataset.share, window.location.href).href : url;
+        navigator.clipboard.writeText(share).then(function() {
+            link.classList.add('copied');
+            setTimeout(function() {
+                link.classList.remove('copied');
+            }, 1500);
+        });
+    });
+});
+
+// Splits a query into a qualified name (`Geo.Area`, `Geo~Area`), an arity filter (`|2`, `|2.1`), a glyph (`≡`)
+// and a badge (`badge:wip`)
+function parseSearchQuery(query) {
+    const parsed = { path: [], name: null, inputs: null, outputs: null, glyph: null, badge: null };
+
+    query.trim().split(/\s+/).filter(Boolean).forEach(function(term) {
+        const arity = term.match(/^\|(\d+)(?:\.(\d+))?$/);
+        if (arity) {
+            parsed.inputs = parseInt(arity[1]);
+            parsed.outputs = arity[2] === undefined ? null : parseInt(arity[2]);
+            return;
+        }
+
+        const badge = term.match(/^badge:(.+)$/i);
+        if (badge) {
+            parsed.badge = badge[1].toLowerCase();
+            return;
+        }
+
+        if ([...term].length === 1 && !/[\w|]/.test(term)) {
+            parsed.glyph = term;
+            return;
+        }
+
+        const parts = term.toLowerCase().split(/[.~]/);
+        parsed.name = parts.pop();
+        parsed.path = parts.filter(Boolean);
+    });
+
+    return parsed;
+}
+
+// Returns how well an entry matches the query (lower is better), or null if it doesn't match
+function matchSearchEntry(entry, query) {
+    if (query.inputs !== null && entry.inputs !== query.inputs) {
+        return null;
+    }
+    if (query.outputs !== null && entry.outputs !== query.outputs) {
+        return null;
+    }
+    if (query.glyph !== null && !entry.glyphs.includes(query.glyph)) {
+        return null;
+    }
+    if (query.badge !== null && !entry.badges.some(function(badge) { return badge.toLowerCase() === query.badge; })) {
+        return null;
+    }
+
+    // Module parts are matched against the end of the path, so `Geo.Area` also finds `Shapes~Geo~Area`
+    if (query.path.length > entry.path.length) {
+        return null;
+    }
+    const path = entry.path.slice(entry.path.length - query.path.length);
+    if (!query.path.every(function(part, index) { return path[index].toLowerCase().startsWith(part); })) {
+        return null;
+    }
+
+    const name = entry.name.toLowerCase();
+    const wanted = query.name || '';
+    if (name === wanted) {
+        return 0;
+    } else if (name.startsWith(wanted)) {
+        return 1;
+    } else if (name.includes(wanted)) {
+        return 2;
+    } else if (wanted.length >= 3 && entry.primitives.some(function(primitive) { return primitive.startsWith(wanted); })) {
+        // Typing a primitive's name finds the bindings using its glyph
+        return 3;
+    }
+    return null;
+}
+
+function renderSearchResults(list, text, index) {
+    const query = parseSearchQuery(text);
+    list.replaceChildren();
+    if (query.name === null && query.inputs === null && query.glyph === null && query.badge === null) {
+        return;
+    }
+
+    const results = index
+        .map(function(entry) { return { entry: entry, score: matchSearchEntry(entry, query) }; })
+        .filter(function(result) { return result.score !== null; })
+        .sort(function(a, b) {
+            return a.score - b.score
+                || a.entry.path.length - b.entry.path.length
+                || a.entry.name.localeCompare(b.entry.name);
+        })
+        .slice(0, 20);
+
+    if (results.length === 0) {
+        const empty = document.createElement('li');
+        empty.className = 'search-empty';
+        empty.textContent = 'No results';
+        list.appendChild(empty);
+        return;
+    }
+
+    results.forEach(function(result) {
+        const entry = result.entry;
+        const link = document.createElement('a');
+        link.href = entry.url || '#' + entry.anchor;
+
+        const name = document.createElement('span');
+        name.className = 'mono';
+        name.textContent = entry.qualified_name;
+        link.appendChild(name);
+
+        const kind = document.createElement('span');
+        kind.className = 'badge';
+        kind.textContent = entry.inputs === null ? entry.kind : '|' + entry.inputs + '.' + entry.outputs;
+        link.appendChild(document.createTextNode(' '));
+        link.appendChild(kind);
+        entry.badges.forEach(function(label) {
+            const badge = document.createElement('span');
+            badge.className = 'badge custom-badge';
+            badge.textContent = label;
+            link.appendChild(document.createTextNode(' '));
+            link.appendChild(badge);
+        });
+
+        const item = document.createElement('li');
+        item.appendChild(link);
+        if (entry.summary) {
+            const summary = document.createElement('div');
+            summary.className = 'search-summary';
+            summary.textContent = entry.summary;
+            item.appendChild(summary);
+        }
+        list.appendChild(item);
+    });
+}
+
+// Highlights the lines referenced by a `#L12` or `#L12-L20` link on a source page
+function highlightSourceLines() {
+    document.querySelectorAll('.source-file .highlighted').forEach(function(line) {
+        line.classList.remove('highlighted');
+    });
+
+    const range = window.location.hash.match(/^#L(\d+)(?:-L(\d+))?$/);
+    if (!range) {
+        return;
+    }
+
+    const start = parseInt(range[1]);
+    const end = range[2] === undefined ? start : parseInt(range[2]);
+    for (let number = start; number <= end; number++) {
+        const line = document.getElementById('L' + number);
+        if (line) {
+            line.classList.add('highlighted');
+        }
+    }
+
+    const first = document.getElementById('L' + start);
+    if (first) {
+        first.scrollIntoView({ block: 'center' });
+    }
+}
+
+const MINIMAP_ROW_HEIGHT = 24;
+const MINIMAP_VISIBLE_ROWS = 12;
+
+// Short lists are left as they are, long ones only keep the rows in view in the document
+function virtualizeMinimapItems(container) {
+    const links = Array.from(container.querySelectorAll('.minimap-item'));
+    if (links.length <= MINIMAP_VISIBLE_ROWS * 2) {
+        return;
+    }
+
+    container.classList.add('virtualized');
+    container.style.height = (MINIMAP_ROW_HEIGHT * MINIMAP_VISIBLE_ROWS) + 'px';
+
+    const spacer = document.createElement('div');
+    spacer.className = 'minimap-spacer';
+    spacer.style.height = (MINIMAP_ROW_HEIGHT * links.length) + 'px';
+    container.replaceChildren(spacer);
+
+    let scheduled = false;
+    function renderVisibleRows() {
+        scheduled = false;
+        const first = Math.floor(container.scrollTop / MINIMAP_ROW_HEIGHT);
+        const last = Math.min(links.length, first + MINIMAP_VISIBLE_ROWS + 1);
+        const rows = [];
+        for (let index = first; index < last; index++) {
+            const link = links[index];
+            link.style.top = (index * MINIMAP_ROW_HEIGHT) + 'px';
+            rows.push(link);
+        }
+        spacer.replaceChildren(...rows);
+    }
+
+    container.addEventListener('scroll', function() {
+        if (!scheduled) {
+            scheduled = true;
+            requestAnimationFrame(renderVisibleRows);
+        }
+    });
+    renderVisibleRows();
+}
+
+let searchIndex = null;
+
+// Small indexes are part of the page, large ones are a file of their own that's fetched the first time it's needed
+function loadSearchIndex() {
+    if (!searchIndex) {
+        const element = document.getElementById('search-index');
+        searchIndex = element.dataset.src
+            ? fetch(element.dataset.src).then(function(response) {
+                if (!response.ok) {
+                    throw new Error(response.statusText);
+                }
+                return response.json();
+            })
+            : Promise.resolve(JSON.parse(element.textContent));
+        searchIndex.catch(function() {
+            searchIndex = null;
+        });
+    }
+    return searchIndex;
+}
+
+function loadPanelFragment(panel) {
+    const body = panel.querySelector('.lazy-panel-body');
+    body.textContent = 'Loading...';
+
+    fetch(panel.dataset.fragment)
+        .then(function(response) {
+            if (!response.ok) {
+                throw new Error(response.statusText);
+            }
+            return response.text();
+        })
+        .then(function(html) {
+            body.innerHTML = html;
+        })
+        .catch(function() {
+            delete panel.dataset.loaded;
+            body.textContent = 'Unable to load the details. Pages opened from the file system can\'t load them, serve the site over HTTP instead.';
+        });
+}
+
+// Arguments are written in the order of the named inputs, and the first one ends up on top of the stack
+function renderStackExplorer(explorer) {
+    const name = explorer.dataset.name;
+    const inputs = JSON.parse(explorer.dataset.inputs);
+    const outputs = JSON.parse(explorer.dataset.outputs);
+    const values = inputs.map(function(_, index) {
+        return String(index + 1);
+    });
+
+    const fields = document.createElement('div');
+    fields.className = 'stack-explorer-fields';
+    const call = document.createElement('code');
+    call.className = 'stack-explorer-call';
+    const stacks = document.createElement('div');
+    stacks.className = 'stack-explorer-stacks';
+    const before = document.createElement('ol');
+    const after = document.createElement('ol');
+    stacks.append(createStackColumn('Stack before the call', before), createStackColumn('Stack after the call', after));
+
+    function update() {
+        call.textContent = [name].concat(values).join(' ');
+        before.replaceChildren(...inputs.map(function(input, index) {
+            return createStackSlot(input, values[index]);
+        }));
+        after.replaceChildren(...outputs.map(function(output) {
+            return createStackSlot(output, '');
+        }));
+    }
+
+    inputs.forEach(function(input, index) {
+        const label = document.createElement('label');
+        label.textContent = input + ' ';
+        const field = document.createElement('input');
+        field.className = 'mono';
+        field.value = values[index];
+        field.size = 8;
+        field.addEventListener('input', function() {
+            values[index] = field.value.trim() || '_';
+            update();
+        });
+        label.appendChild(field);
+        fields.appendChild(label);
+    });
+
+    const note = document.createElement('p');
+    note.textContent = 'Code runs from right to left, so the last argument is pushed first and the first one ends up on top of the stack.';
+
+    update();
+    explorer.querySelector('.stack-explorer-body').replaceChildren(fields, call, note, stacks);
+}
+
+function createStackColumn(title, list) {
+    const column = document.createElement('div');
+    column.className = 'stack-explorer-column';
+    const heading = document.createElement('div');
+    heading.className = 'stack-explorer-heading';
+    heading.textContent = title + ' (top first)';
+    column.append(heading, list);
+    return column;
+}
+
+function createStackSlot(name, value) {
+    const slot = document.createElement('li');
+    slot.className = 'stack-explorer-slot mono';
+    slot.textContent = value ? name + ' = ' + value : name;
+    return slot;
+}
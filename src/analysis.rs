@@ -0,0 +1,799 @@
+use crate::directives::{malformed_directive, parse_directive};
+use crate::examples::{ExampleNotebook, NotebookCell};
+use crate::extractor::{
+    qualified_name, BindingDefinition, BindingType, DataDefinition, Documented, FileContent, ItemContent, ItemReference, ModuleDefinition,
+    SourceLocation,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uiua::ast::{Modifier, Word};
+use uiua::{NativeSys, Primitive, Purity, Sp, SpanKind, Spans, SysOp, SysOpClass};
+
+/// A simplified token of Uiua source code, used to analyze what a piece of code refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodeToken {
+    Primitive(Primitive),
+    Number(f64),
+    /// A possibly module-qualified name, like `Module~Name`.
+    Path(Vec<String>),
+    Other,
+}
+
+pub fn tokenize_code(code: &str) -> Vec<CodeToken> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut continues_path = false;
+
+    for span in Spans::with_backend(code, NativeSys).spans {
+        let text: String = chars[span.span.start.char_pos as usize..span.span.end.char_pos as usize].iter().collect();
+        let token = match span.value {
+            SpanKind::Whitespace | SpanKind::Comment => continue,
+            SpanKind::Delimiter if text == "~" && matches!(tokens.last(), Some(CodeToken::Path(_))) => {
+                continues_path = true;
+                continue;
+            }
+            SpanKind::Ident { .. } => {
+                if let (true, Some(CodeToken::Path(path))) = (continues_path, tokens.last_mut()) {
+                    path.push(text);
+                    continues_path = false;
+                    continue;
+                }
+                CodeToken::Path(vec![text])
+            }
+            SpanKind::Primitive(prim, None) => CodeToken::Primitive(prim),
+            SpanKind::Number => text.replace('¯', "-").parse().map(CodeToken::Number).unwrap_or(CodeToken::Other),
+            _ => CodeToken::Other,
+        };
+        continues_path = false;
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// The distinct primitives a piece of code uses, in order of first appearance.
+pub fn used_primitives(code: &str) -> Vec<Primitive> {
+    let mut primitives = Vec::new();
+    for token in tokenize_code(code) {
+        if let CodeToken::Primitive(primitive) = token {
+            if !primitives.contains(&primitive) {
+                primitives.push(primitive);
+            }
+        }
+    }
+    primitives
+}
+
+/// Size and complexity measures of a binding's body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodeMetrics {
+    pub tokens: usize,
+    pub primitives: usize,
+    /// How deeply modifiers are nested in the operands of other modifiers.
+    pub modifier_depth: usize,
+    /// Names of the metrics that are unusually high compared to the rest of the library.
+    pub outliers: Vec<String>,
+}
+
+impl CodeMetrics {
+    /// Every metric with its name.
+    pub fn values(&self) -> [(&'static str, usize); 3] {
+        [
+            ("tokens", self.tokens),
+            ("primitives", self.primitives),
+            ("modifier depth", self.modifier_depth),
+        ]
+    }
+}
+
+/// The smallest value of each metric worth flagging, so libraries of tiny bindings don't flag slightly larger ones.
+const OUTLIER_MINIMUMS: [usize; 3] = [20, 10, 3];
+
+pub fn code_metrics(words: &[Sp<Word>]) -> CodeMetrics {
+    let mut metrics = CodeMetrics::default();
+    metrics.modifier_depth = measure_words(words, &mut metrics);
+    metrics
+}
+
+/// Counts the tokens and primitives of the words into the metrics, and returns their modifier depth.
+fn measure_words(words: &[Sp<Word>], metrics: &mut CodeMetrics) -> usize {
+    words.iter().map(|word| measure_word(&word.value, metrics)).max().unwrap_or(0)
+}
+
+fn measure_lines(lines: &[Vec<Sp<Word>>], metrics: &mut CodeMetrics) -> usize {
+    lines.iter().map(|line| measure_words(line, metrics)).max().unwrap_or(0)
+}
+
+fn measure_word(word: &Word, metrics: &mut CodeMetrics) -> usize {
+    match word {
+        Word::Modified(modified) => {
+            metrics.tokens += 1;
+            let modifier_depth = match &modified.modifier.value {
+                Modifier::Primitive(_) => {
+                    metrics.primitives += 1;
+                    0
+                }
+                Modifier::Ref(_) => 0,
+                Modifier::Macro(inline_macro) => measure_lines(&inline_macro.func.value.lines, metrics),
+            };
+            1 + modifier_depth.max(measure_words(&modified.operands, metrics))
+        }
+        Word::Primitive(_) => {
+            metrics.tokens += 1;
+            metrics.primitives += 1;
+            0
+        }
+        Word::Strand(words) => measure_words(words, metrics),
+        Word::Array(array) => measure_lines(&array.lines, metrics),
+        Word::Func(func) => measure_lines(&func.lines, metrics),
+        Word::Pack(pack) => pack
+            .branches
+            .iter()
+            .map(|branch| measure_lines(&branch.value.lines, metrics))
+            .max()
+            .unwrap_or(0),
+        Word::Subscripted(subscripted) => measure_word(&subscripted.word.value, metrics),
+        Word::InlineMacro(inline_macro) => measure_lines(&inline_macro.func.value.lines, metrics),
+        Word::SemanticComment(_) | Word::OutputComment { .. } => 0,
+        word if word.is_code() => {
+            metrics.tokens += 1;
+            0
+        }
+        _ => 0,
+    }
+}
+
+/// Marks the metrics of documented bindings that lie far above the rest of the library,
+/// using Tukey's rule (above the third quartile by more than 1.5 times the interquartile range).
+pub fn flag_metric_outliers(items: &mut [ItemContent]) {
+    let mut bindings = Vec::new();
+    collect_documented_bindings(items, &mut bindings);
+    // Quartiles of a handful of bindings say nothing about the library
+    if bindings.len() < 4 {
+        return;
+    }
+
+    for (index, minimum) in OUTLIER_MINIMUMS.into_iter().enumerate() {
+        let mut values = bindings.iter().map(|binding| binding.metrics.values()[index].1).collect::<Vec<_>>();
+        values.sort();
+        let first_quartile = values[values.len() / 4] as f64;
+        let third_quartile = values[values.len() * 3 / 4] as f64;
+        let fence = third_quartile + 1.5 * (third_quartile - first_quartile);
+
+        for binding in bindings.iter_mut() {
+            let (name, value) = binding.metrics.values()[index];
+            if value >= minimum && value as f64 > fence {
+                binding.metrics.outliers.push(name.to_owned());
+            }
+        }
+    }
+}
+
+fn collect_documented_bindings<'a>(items: &'a mut [ItemContent], bindings: &mut Vec<&'a mut BindingDefinition>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.is_documented() => bindings.push(binding),
+            ItemContent::Module(module) => collect_documented_bindings(&mut module.items, bindings),
+            _ => {}
+        }
+    }
+}
+
+/// Lines describing the bindings with outlying metrics, as candidates for refactoring or extra documentation.
+pub fn metrics_report(items: &[ItemContent]) -> Vec<String> {
+    let mut lines = Vec::new();
+    collect_metrics_report(items, None, &mut lines);
+    lines
+}
+
+fn collect_metrics_report(items: &[ItemContent], parent_module: Option<&str>, lines: &mut Vec<String>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if !binding.metrics.outliers.is_empty() => {
+                let metrics = &binding.metrics;
+                let name = match parent_module {
+                    Some(module) => format!("{}~{}", module, binding.name),
+                    None => binding.name.clone(),
+                };
+                let undocumented = if binding.comment().is_none() { ", undocumented" } else { "" };
+                lines.push(format!(
+                    "{}: {} tokens, {} primitives, modifier depth {} (high {}{})",
+                    name,
+                    metrics.tokens,
+                    metrics.primitives,
+                    metrics.modifier_depth,
+                    metrics.outliers.join(", "),
+                    undocumented
+                ));
+            }
+            ItemContent::Module(module) => collect_metrics_report(&module.items, Some(&module.name), lines),
+            _ => {}
+        }
+    }
+}
+
+/// Access to the host system that a library needs, found from the system functions in its code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Permission {
+    /// What the library does with it, like "reads files".
+    pub description: String,
+    /// The system functions that need it, like `&fras`.
+    pub functions: Vec<String>,
+}
+
+/// The permissions the code of the library's files needs, including private bindings and code that
+/// runs when the library is imported.
+pub fn required_permissions(files: &[FileContent]) -> Vec<Permission> {
+    let mut operations = Vec::new();
+    for file in files {
+        collect_system_operations(&file.items, &mut operations);
+    }
+
+    let mut permissions: Vec<Permission> = Vec::new();
+    for operation in operations {
+        let Some(description) = permission_description(operation) else {
+            continue;
+        };
+        let name = operation.name().to_owned();
+        match permissions.iter_mut().find(|permission| permission.description == description) {
+            Some(permission) if permission.functions.contains(&name) => {}
+            Some(permission) => permission.functions.push(name),
+            None => permissions.push(Permission {
+                description: description.to_owned(),
+                functions: vec![name],
+            }),
+        }
+    }
+    permissions
+}
+
+fn collect_system_operations(items: &[ItemContent], operations: &mut Vec<SysOp>) {
+    for item in items {
+        let primitives = match item {
+            ItemContent::Binding(binding) => binding.primitives.clone(),
+            ItemContent::Words { code } => used_primitives(code),
+            ItemContent::Module(module) => {
+                collect_system_operations(&module.items, operations);
+                continue;
+            }
+            _ => continue,
+        };
+        operations.extend(primitives.into_iter().filter_map(|primitive| match primitive {
+            Primitive::Sys(operation) => Some(operation),
+            _ => None,
+        }));
+    }
+}
+
+fn permission_description(operation: SysOp) -> Option<&'static str> {
+    match (operation, operation.class()) {
+        (SysOp::ChangeDirectory, _) => Some("changes the working directory"),
+        (_, SysOpClass::Filesystem) if operation.purity() == Purity::Mutating => Some("writes files"),
+        (_, SysOpClass::Filesystem) => Some("reads files"),
+        (_, SysOpClass::Tcp) => Some("opens network connections"),
+        (_, SysOpClass::Command) => Some("runs commands"),
+        (_, SysOpClass::Env) => Some("reads the environment"),
+        (_, SysOpClass::StdIO) => Some("uses the terminal"),
+        (_, SysOpClass::Media) => Some("shows images and plays audio"),
+        (_, SysOpClass::Ffi) => Some("calls native code"),
+        (SysOp::Clip, _) => Some("reads the clipboard"),
+        (SysOp::WebcamCapture, _) => Some("uses the webcam"),
+        (SysOp::Exit, _) => Some("exits the program"),
+        // Streams are opened by the functions above, and the rest doesn't reach outside the program
+        _ => None,
+    }
+}
+
+/// Whether a documented binding is used by a test scope or an example notebook.
+#[derive(Debug, Clone)]
+pub struct BindingCoverage {
+    /// The name, qualified by the modules it's in, like `Module~Name`.
+    pub name: String,
+    pub tested: bool,
+    pub exampled: bool,
+}
+
+/// Checks which of the documented bindings in `items` the test scopes of the library's files and the
+/// examples refer to, by name or through a module.
+pub fn binding_coverage(items: &[ItemContent], files: &[FileContent], examples: &[ExampleNotebook]) -> Vec<BindingCoverage> {
+    let mut test_code = Vec::new();
+    for file in files {
+        collect_test_code(&file.items, &mut test_code);
+    }
+    let example_code = examples
+        .iter()
+        .flat_map(|example| &example.cells)
+        .filter_map(|cell| match cell {
+            NotebookCell::Code { code, .. } => Some(code.as_str()),
+            NotebookCell::Prose(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    let paths = |code: &[&str]| {
+        code.iter()
+            .flat_map(|code| tokenize_code(code))
+            .filter_map(|token| match token {
+                CodeToken::Path(path) => Some(path),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let tested = paths(&test_code);
+    let exampled = paths(&example_code);
+
+    let mut bindings = Vec::new();
+    collect_coverage_bindings(items, &mut bindings);
+    bindings
+        .into_iter()
+        .map(|binding| {
+            let path = binding
+                .module_path
+                .iter()
+                .cloned()
+                .chain(std::iter::once(binding.name.clone()))
+                .collect::<Vec<_>>();
+            // Examples import the library, so their paths can start with the name they gave it
+            let refers = |paths: &[Vec<String>]| paths.iter().any(|candidate| candidate.ends_with(&path));
+            BindingCoverage {
+                name: path.join("~"),
+                tested: refers(&tested),
+                exampled: refers(&exampled),
+            }
+        })
+        .collect()
+}
+
+fn collect_test_code<'a>(items: &'a [ItemContent], code: &mut Vec<&'a str>) {
+    for item in items {
+        match item {
+            ItemContent::Test { code: test } => code.push(test),
+            ItemContent::Module(module) => collect_test_code(&module.items, code),
+            _ => {}
+        }
+    }
+}
+
+/// A documented binding without a doc comment, or a function or modifier without a signature comment.
+#[derive(Debug, Clone)]
+pub struct UndocumentedBinding {
+    /// The name, qualified by the modules it's in, like `Module~Name`.
+    pub name: String,
+    pub location: SourceLocation,
+    pub missing_comment: bool,
+    pub missing_signature: bool,
+}
+
+impl UndocumentedBinding {
+    pub fn message(&self) -> String {
+        let missing = match (self.missing_comment, self.missing_signature) {
+            (true, true) => "no comment or signature comment",
+            (true, false) => "no comment",
+            _ => "no signature comment",
+        };
+        format!("{}:{} {}: {}", self.location.file, self.location.start_line, self.name, missing)
+    }
+}
+
+/// Finds the documented bindings in `items` that are missing a doc comment, or, for functions and modifiers, a
+/// signature comment like `# Sum ? Xs`.
+pub fn find_undocumented_bindings(items: &[ItemContent]) -> Vec<UndocumentedBinding> {
+    let mut bindings = Vec::new();
+    collect_coverage_bindings(items, &mut bindings);
+    bindings
+        .into_iter()
+        .filter_map(|binding| {
+            let missing_comment = binding.comment.is_none();
+            let missing_signature = match &binding.kind {
+                BindingType::Const(_) => false,
+                BindingType::Function(function) => function.named_signature.is_none(),
+                BindingType::IndexMacro(index_macro) => index_macro.named_signature.is_none(),
+                BindingType::CodeMacro(code_macro) => code_macro.named_signature.is_none(),
+            };
+            (missing_comment || missing_signature).then(|| UndocumentedBinding {
+                name: qualified_name(&binding.module_path, &binding.name),
+                location: binding.location.clone(),
+                missing_comment,
+                missing_signature,
+            })
+        })
+        .collect()
+}
+
+fn collect_coverage_bindings<'a>(items: &'a [ItemContent], bindings: &mut Vec<&'a BindingDefinition>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.is_documented() => bindings.push(binding),
+            ItemContent::Module(module) => collect_coverage_bindings(&module.items, bindings),
+            _ => {}
+        }
+    }
+}
+
+/// A definition that reuses a name already defined in the same scope, so only the later one can be used.
+#[derive(Debug, Clone)]
+pub struct ShadowedName {
+    /// The name, qualified by the modules it's in, like `Module~Name`.
+    pub name: String,
+    pub file: String,
+    /// The line of the later definition, when it's known.
+    pub line: Option<usize>,
+    /// What the name referred to before, like "the public binding on line 4".
+    pub shadowed: String,
+}
+
+impl ShadowedName {
+    pub fn message(&self) -> String {
+        let location = match self.line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.clone(),
+        };
+        format!(
+            "`{}` in {} shadows {}, so the docs only describe one of them",
+            self.name, location, self.shadowed
+        )
+    }
+}
+
+/// Finds public bindings defined again later in the same scope, and items that shadow names brought in by imports.
+pub fn find_shadowed_names(files: &[FileContent]) -> Vec<ShadowedName> {
+    let mut shadowed = Vec::new();
+    for file in files {
+        collect_shadowed_names(&file.relative_path, &[], &file.items, &mut shadowed);
+    }
+    shadowed
+}
+
+fn collect_shadowed_names(file: &str, path: &[String], items: &[ItemContent], shadowed: &mut Vec<ShadowedName>) {
+    // What every name in the scope refers to, for the names whose shadowing is worth a warning
+    let mut defined = HashMap::<&str, String>::new();
+    for item in items {
+        let (name, line, definition) = match item {
+            ItemContent::Binding(binding) => (
+                binding.name.as_str(),
+                Some(binding.location.start_line),
+                binding
+                    .public
+                    .then(|| format!("the public binding on line {}", binding.location.start_line)),
+            ),
+            ItemContent::Module(module) => {
+                let module_path = path.iter().cloned().chain(std::iter::once(module.name.clone())).collect::<Vec<_>>();
+                collect_shadowed_names(file, &module_path, &module.items, shadowed);
+                (module.name.as_str(), None, Some("a module defined before it".to_owned()))
+            }
+            ItemContent::Data(DataDefinition { name: Some(name), .. }) => (name.as_str(), None, Some("a data definition before it".to_owned())),
+            ItemContent::Variant(variant) => (variant.name.as_str(), None, Some("a variant defined before it".to_owned())),
+            ItemContent::Import(import) => {
+                if let Some(name) = &import.name {
+                    defined.insert(name, format!("the import of \"{}\"", import.path));
+                }
+                for imported in &import.items {
+                    defined.insert(imported, format!("`{}` imported from \"{}\"", imported, import.path));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        if let Some(previous) = defined.get(name) {
+            shadowed.push(ShadowedName {
+                name: path.iter().map(String::as_str).chain(std::iter::once(name)).collect::<Vec<_>>().join("~"),
+                file: file.to_owned(),
+                line,
+                shadowed: previous.clone(),
+            });
+        }
+        match definition {
+            Some(definition) => defined.insert(name, definition),
+            None => defined.remove(name),
+        };
+    }
+}
+
+/// A comment line that looks like a `!doc` directive or an annotation, but that the generator can't use, like a
+/// misspelled `!docs hide` or a `!doc hide` that isn't directly above a binding.
+#[derive(Debug, Clone)]
+pub struct DirectiveProblem {
+    pub file: String,
+    /// The line of the comment, or of the item it belongs to, when it's known.
+    pub line: Option<usize>,
+    /// The item the comment belongs to, qualified by the modules it's in, like `Module~Name`.
+    pub name: Option<String>,
+    pub problem: String,
+}
+
+impl DirectiveProblem {
+    pub fn message(&self) -> String {
+        let location = match self.line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.clone(),
+        };
+        match &self.name {
+            Some(name) => format!("{} {}: {}", location, name, self.problem),
+            None => format!("{}: {}", location, self.problem),
+        }
+    }
+}
+
+/// Finds the directives and annotations that would otherwise be ignored without a word: misspelled ones, ones with
+/// values that don't fit, and ones that aren't attached to a binding. Only the `!doc` blocks at the top level of
+/// `landing` end up on the front page.
+pub fn find_directive_problems(files: &[FileContent], landing: &FileContent) -> Vec<DirectiveProblem> {
+    let mut problems = Vec::new();
+    for file in files {
+        // Chunks of words are found in the source in order, to know their lines
+        let mut cursor = 0;
+        let landing = (file.relative_path != landing.relative_path).then_some(landing.relative_path.as_str());
+        collect_directive_problems(file, &file.items, landing, &mut cursor, &mut problems);
+    }
+    problems
+}
+
+/// `landing` is the path of the landing file when the items aren't at its top level, where `!doc` blocks are shown.
+fn collect_directive_problems(
+    file: &FileContent,
+    items: &[ItemContent],
+    landing: Option<&str>,
+    cursor: &mut usize,
+    problems: &mut Vec<DirectiveProblem>,
+) {
+    let problem = |line: Option<usize>, name: Option<String>, problem: String| DirectiveProblem {
+        file: file.relative_path.clone(),
+        line,
+        name,
+        problem,
+    };
+
+    for item in items {
+        let (line, name, comment) = match item {
+            ItemContent::Binding(binding) => {
+                let name = qualified_name(&binding.module_path, &binding.name);
+                let line = Some(binding.location.start_line);
+                for message in binding.directives.iter().filter_map(|directive| directive.problem()) {
+                    problems.push(problem(line, Some(name.clone()), message));
+                }
+                // Directive lines were taken out of the comment, so what's left isn't read as one
+                for comment in binding.comment.iter().flat_map(|comment| comment.lines()).map(str::trim) {
+                    let message = match comment {
+                        "!doc" => Some("`!doc` without a directive, like `!doc hide`".to_owned()),
+                        comment => malformed_directive(comment),
+                    };
+                    if let Some(message) = message {
+                        problems.push(problem(line, Some(name.clone()), message));
+                    }
+                }
+                continue;
+            }
+            ItemContent::Module(module) => (
+                Some(module.location.start_line),
+                qualified_name(&module.module_path, &module.name),
+                module.comment.as_deref(),
+            ),
+            ItemContent::Data(data) => (
+                None,
+                match &data.name {
+                    Some(name) => qualified_name(&data.module_path, name),
+                    None => data.module_path.join("~"),
+                },
+                data.comment.as_deref(),
+            ),
+            ItemContent::Variant(variant) => (None, qualified_name(&variant.module_path, &variant.name), variant.comment.as_deref()),
+            ItemContent::Words { code } => {
+                let start = file.source[*cursor..].find(code.as_str()).map(|offset| *cursor + offset);
+                if let Some(start) = start {
+                    *cursor = start + code.len();
+                }
+                let first_line = start.map(|start| file.source[..start].matches('\n').count() + 1);
+                for (index, line) in code.lines().enumerate() {
+                    let Some(comment) = line.trim_start().strip_prefix('#').map(str::trim) else {
+                        continue;
+                    };
+                    let is_doc_block = index == 0 && (comment == "!doc" || comment.starts_with("!doc "));
+                    let message = match (malformed_directive(comment), parse_directive(comment)) {
+                        (Some(message), _) => Some(message),
+                        (None, directive) if is_doc_block => match (landing, directive) {
+                            (Some(landing), _) => Some(format!(
+                                "the `!doc` block isn't directly above a binding, and only the ones at the top level of {} are shown on the front page",
+                                landing
+                            )),
+                            (None, Some(directive)) if directive.is_known() => Some(format!(
+                                "`{}` isn't directly above a binding, so it's shown on the front page as text",
+                                comment
+                            )),
+                            _ => None,
+                        },
+                        (None, Some(_)) => Some(format!("`{}` isn't directly above a binding, so it does nothing", comment)),
+                        (None, None) => None,
+                    };
+                    if let Some(message) = message {
+                        problems.push(problem(first_line.map(|line| line + index), None, message));
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        // Only bindings have directives, so in the comments of anything else they're left in the text
+        for comment in comment.iter().flat_map(|comment| comment.lines()).map(str::trim) {
+            let message = match (malformed_directive(comment), parse_directive(comment)) {
+                (Some(message), _) => Some(message),
+                (None, Some(_)) => Some(format!("`{}` does nothing here, directives only apply to bindings", comment)),
+                (None, None) => None,
+            };
+            if let Some(message) = message {
+                problems.push(problem(line, (!name.is_empty()).then(|| name.clone()), message));
+            }
+        }
+        if let ItemContent::Module(module) = item {
+            collect_directive_problems(file, &module.items, landing.or(Some(&file.relative_path)), cursor, problems);
+        }
+    }
+}
+
+#[derive(Default)]
+struct VariantUsage {
+    produced_by: Vec<ItemReference>,
+    consumed_by: Vec<ItemReference>,
+}
+
+/// Records on every module which of the modules next to it its bindings refer to.
+pub fn link_module_dependencies(items: &mut [ItemContent]) {
+    let names = items
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Module(module) => Some(module.name.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    for item in items {
+        if let ItemContent::Module(module) = item {
+            if names.len() > 1 {
+                let mut dependencies = Vec::new();
+                collect_module_references(&module.items, &names, &mut dependencies);
+                dependencies.retain(|name| *name != module.name);
+                module.dependencies = dependencies;
+            }
+            link_module_dependencies(&mut module.items);
+        }
+    }
+}
+
+fn collect_module_references(items: &[ItemContent], names: &[String], references: &mut Vec<String>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) => {
+                for token in tokenize_code(&binding.code) {
+                    // Only qualified names, as a binding of the module can share a name with another module
+                    if let CodeToken::Path(path) = token {
+                        if path.len() > 1 && names.contains(&path[0]) && !references.contains(&path[0]) {
+                            references.push(path[0].clone());
+                        }
+                    }
+                }
+            }
+            ItemContent::Module(module) => collect_module_references(&module.items, names, references),
+            _ => {}
+        }
+    }
+}
+
+/// The indices of the modules ordered so that every module comes after the modules it depends on.
+/// Otherwise the modules keep their order, and modules that depend on each other are kept in it.
+pub fn dependency_order(modules: &[&ModuleDefinition]) -> Vec<usize> {
+    let mut order = Vec::with_capacity(modules.len());
+    let mut placed = vec![false; modules.len()];
+
+    while order.len() < modules.len() {
+        let is_ready = |index: &usize| {
+            !placed[*index]
+                && modules[*index].dependencies.iter().all(|dependency| {
+                    modules
+                        .iter()
+                        .enumerate()
+                        .all(|(other, module)| placed[other] || module.name != *dependency)
+                })
+        };
+        let next = (0..modules.len())
+            .find(is_ready)
+            .or_else(|| (0..modules.len()).find(|index| !placed[*index]))
+            .unwrap();
+        placed[next] = true;
+        order.push(next);
+    }
+
+    order
+}
+
+/// Records on every variant which public functions construct it and which ones take it apart.
+pub fn link_variants(items: &mut [ItemContent]) {
+    let mut usages = HashMap::new();
+    collect_variant_names(items, &mut usages);
+    if usages.is_empty() {
+        return;
+    }
+
+    collect_variant_usages(items, &mut usages);
+    assign_variant_usages(items, &mut usages);
+}
+
+/// The variants, by their names qualified by the modules they're in, so variants with the same name in different
+/// modules are told apart.
+fn collect_variant_names(items: &[ItemContent], usages: &mut HashMap<String, VariantUsage>) {
+    for item in items {
+        match item {
+            ItemContent::Variant(variant) => {
+                usages.insert(qualified_name(&variant.module_path, &variant.name), VariantUsage::default());
+            }
+            ItemContent::Module(module) => collect_variant_names(&module.items, usages),
+            _ => {}
+        }
+    }
+}
+
+/// The variant a path in the code of a binding refers to, and the index of the path's segment that names it. Like
+/// the names of Uiua, the path is looked up in the binding's module first, then in the modules around it.
+fn referenced_variant(module_path: &[String], path: &[String], usages: &HashMap<String, VariantUsage>) -> Option<(String, usize)> {
+    (0..=module_path.len()).rev().find_map(|depth| {
+        (0..path.len()).find_map(|position| {
+            let name = qualified_name(&[&module_path[..depth], &path[..position]].concat(), &path[position]);
+            usages.contains_key(&name).then_some((name, position))
+        })
+    })
+}
+
+fn collect_variant_usages(items: &[ItemContent], usages: &mut HashMap<String, VariantUsage>) {
+    for item in items {
+        let binding = match item {
+            ItemContent::Binding(binding) if binding.is_documented() && matches!(binding.kind, BindingType::Function(_)) => binding,
+            ItemContent::Module(module) => {
+                collect_variant_usages(&module.items, usages);
+                continue;
+            }
+            _ => continue,
+        };
+
+        let reference = ItemReference {
+            module_path: binding.module_path.clone(),
+            name: binding.name.clone(),
+        };
+
+        let tokens = tokenize_code(&binding.code);
+        for (index, token) in tokens.iter().enumerate() {
+            let CodeToken::Path(path) = token else {
+                continue;
+            };
+
+            let Some((variant, position)) = referenced_variant(&binding.module_path, path, usages) else {
+                continue;
+            };
+
+            let un = index > 0 && tokens[index - 1] == CodeToken::Primitive(Primitive::Un);
+            let consumes = match path.get(position + 1) {
+                Some(member) => member != "New",
+                None => un,
+            };
+
+            let usage = usages.get_mut(&variant).unwrap();
+            let references = if consumes { &mut usage.consumed_by } else { &mut usage.produced_by };
+            if !references.contains(&reference) {
+                references.push(reference.clone());
+            }
+        }
+    }
+}
+
+fn assign_variant_usages(items: &mut [ItemContent], usages: &mut HashMap<String, VariantUsage>) {
+    for item in items {
+        match item {
+            ItemContent::Variant(variant) => {
+                if let Some(usage) = usages.get(&qualified_name(&variant.module_path, &variant.name)) {
+                    variant.produced_by = usage.produced_by.clone();
+                    variant.consumed_by = usage.consumed_by.clone();
+                }
+            }
+            ItemContent::Module(module) => assign_variant_usages(&mut module.items, usages),
+            _ => {}
+        }
+    }
+}
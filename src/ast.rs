@@ -0,0 +1,275 @@
+use crate::extractor::{read_library_sources, ExtractError};
+use serde::Serialize;
+use std::fs::canonicalize;
+use std::path::Path;
+use uiua::ast::{DataField, Item, ModuleKind, Word};
+use uiua::{parse, CodeSpan, InputSrc, Inputs, Sp};
+
+/// The parsed items of a file of the library, as `extract --ast` prints them.
+#[derive(Debug, Clone, Serialize)]
+pub struct AstFile {
+    /// The path of the file, relative to the library's directory.
+    pub file: String,
+    pub items: Vec<AstItem>,
+}
+
+/// A top-level item, tagged with its `kind`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AstItem {
+    /// Code outside of bindings, including comments, one list of words per line.
+    Words { lines: Vec<Vec<AstWord>> },
+    Binding {
+        name: String,
+        span: AstSpan,
+        public: bool,
+        code_macro: bool,
+        signature: Option<String>,
+        words: Vec<AstWord>,
+    },
+    Import {
+        name: Option<String>,
+        span: AstSpan,
+        path: String,
+        items: Vec<String>,
+    },
+    /// A scoped module, without a name for test scopes.
+    Module {
+        name: Option<String>,
+        span: AstSpan,
+        items: Vec<AstItem>,
+    },
+    Data {
+        name: Option<String>,
+        span: AstSpan,
+        variant: bool,
+        boxed: Option<bool>,
+        fields: Vec<AstField>,
+        words: Option<Vec<AstWord>>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AstField {
+    pub name: String,
+    pub span: AstSpan,
+    pub comments: Vec<String>,
+    pub validator: Option<Vec<AstWord>>,
+    pub default: Option<Vec<AstWord>>,
+}
+
+/// A word of code, with the words nested in it for functions, arrays, strands and modifiers.
+#[derive(Debug, Clone, Serialize)]
+pub struct AstWord {
+    pub kind: &'static str,
+    pub span: AstSpan,
+    /// The source code of the word.
+    pub text: String,
+    /// What the word stands for, like the name of a primitive or the text of a comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<Vec<AstWord>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AstSpan {
+    pub start: AstLocation,
+    pub end: AstLocation,
+}
+
+/// A location in a file, with lines and columns counted from 1.
+#[derive(Debug, Clone, Serialize)]
+pub struct AstLocation {
+    pub line: u16,
+    pub column: u16,
+    pub byte: u32,
+}
+
+/// Parses the files of a library, following the imports from its entry file, like `lib.ua`, without compiling it.
+pub fn parse_library_ast(path: &Path, library_file: &Path) -> Result<Vec<AstFile>, ExtractError> {
+    let lib_path = path.join(library_file);
+    if !lib_path.exists() || !lib_path.is_file() {
+        return Err(ExtractError::LibraryNotFound(lib_path));
+    }
+
+    let root = canonicalize(path)?;
+    let mut inputs = read_library_sources(&lib_path)?;
+    let mut files: Vec<_> = inputs.files.iter().map(|file| (file.key().clone(), file.value().clone())).collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|(file_path, source)| {
+            let (items, _, _) = parse(&source, InputSrc::File(file_path.clone().into()), &mut inputs);
+            let full_file_path = canonicalize(&file_path)?;
+            let file = full_file_path
+                .strip_prefix(&root)
+                .unwrap_or(&full_file_path)
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Ok(AstFile {
+                file,
+                items: items.iter().map(|item| convert_item(item, &inputs)).collect(),
+            })
+        })
+        .collect()
+}
+
+pub fn ast_json(files: &[AstFile]) -> String {
+    serde_json::to_string_pretty(files).expect("Unable to serialize the syntax tree")
+}
+
+fn convert_item(item: &Item, inputs: &Inputs) -> AstItem {
+    match item {
+        Item::Words(lines) => AstItem::Words {
+            lines: lines.iter().map(|line| convert_words(line, inputs)).collect(),
+        },
+        Item::Binding(binding) => AstItem::Binding {
+            name: binding.name.value.to_string(),
+            span: convert_span(&binding.span()),
+            public: binding.public,
+            code_macro: binding.code_macro,
+            signature: binding.signature.as_ref().map(|signature| signature.value.to_string()),
+            words: convert_words(&binding.words, inputs),
+        },
+        Item::Import(import) => AstItem::Import {
+            name: import.name.as_ref().map(|name| name.value.to_string()),
+            span: convert_span(&import.span()),
+            path: import.path.value.clone(),
+            items: import.items().map(|item| item.value.to_string()).collect(),
+        },
+        Item::Module(module) => AstItem::Module {
+            name: match &module.value.kind {
+                ModuleKind::Named(name) => Some(name.value.to_string()),
+                ModuleKind::Test => None,
+            },
+            span: convert_span(&module.span),
+            items: module.value.items.iter().map(|item| convert_item(item, inputs)).collect(),
+        },
+        Item::Data(data) => AstItem::Data {
+            name: data.name.as_ref().map(|name| name.value.to_string()),
+            span: convert_span(&data.span()),
+            variant: data.variant,
+            boxed: data.fields.as_ref().map(|fields| fields.boxed),
+            fields: data
+                .fields
+                .iter()
+                .flat_map(|fields| &fields.fields)
+                .map(|field| convert_field(field, inputs))
+                .collect(),
+            words: data.func.as_ref().map(|words| convert_words(words, inputs)),
+        },
+    }
+}
+
+fn convert_field(field: &DataField, inputs: &Inputs) -> AstField {
+    AstField {
+        name: field.name.value.to_string(),
+        span: convert_span(&field.span()),
+        comments: field
+            .comments
+            .iter()
+            .flat_map(|comments| &comments.lines)
+            .map(|line| line.value.to_string())
+            .collect(),
+        validator: field.validator.as_ref().map(|validator| convert_words(&validator.words, inputs)),
+        default: field.init.as_ref().map(|init| convert_words(&init.words, inputs)),
+    }
+}
+
+fn convert_words(words: &[Sp<Word>], inputs: &Inputs) -> Vec<AstWord> {
+    words.iter().map(|word| convert_word(word, inputs)).collect()
+}
+
+fn convert_lines(lines: &[Vec<Sp<Word>>], inputs: &Inputs) -> Vec<Vec<AstWord>> {
+    lines.iter().map(|line| convert_words(line, inputs)).collect()
+}
+
+fn convert_word(word: &Sp<Word>, inputs: &Inputs) -> AstWord {
+    let (kind, value, lines) = match &word.value {
+        Word::Number(Ok(number)) => ("number", Some(number.to_string()), Vec::new()),
+        Word::Number(Err(number)) => ("number", Some(number.clone()), Vec::new()),
+        Word::Char(char) => ("char", Some(char.clone()), Vec::new()),
+        Word::String(string) => ("string", Some(string.clone()), Vec::new()),
+        Word::MultilineString(lines) => (
+            "multiline_string",
+            Some(lines.iter().map(|line| line.value.as_str()).collect::<Vec<_>>().join("\n")),
+            Vec::new(),
+        ),
+        // The holes of format strings are written as `_`
+        Word::FormatString(parts) => ("format_string", Some(parts.join("_")), Vec::new()),
+        Word::MultilineFormatString(lines) => (
+            "multiline_format_string",
+            Some(lines.iter().map(|line| line.value.join("_")).collect::<Vec<_>>().join("\n")),
+            Vec::new(),
+        ),
+        Word::Label(label) => ("label", Some(label.clone()), Vec::new()),
+        Word::Ref(reference) => ("ref", Some(reference.to_string()), Vec::new()),
+        Word::IncompleteRef { path, .. } => (
+            "incomplete_ref",
+            Some(path.iter().map(|component| format!("{}~", component.module.value)).collect()),
+            Vec::new(),
+        ),
+        Word::Strand(words) => ("strand", None, vec![convert_words(words, inputs)]),
+        Word::Array(array) => (
+            if array.boxes { "boxed_array" } else { "array" },
+            None,
+            convert_lines(&array.lines, inputs),
+        ),
+        Word::Func(func) => ("function", None, convert_lines(&func.lines, inputs)),
+        Word::Pack(pack) => {
+            let branches = pack
+                .branches
+                .iter()
+                .map(|branch| convert_word(&branch.clone().map(Word::Func), inputs))
+                .collect();
+            ("pack", None, vec![branches])
+        }
+        Word::Primitive(primitive) => ("primitive", Some(primitive.name().to_owned()), Vec::new()),
+        Word::Modified(modified) => (
+            "modified",
+            Some(modified.modifier.value.to_string()),
+            vec![convert_words(&modified.operands, inputs)],
+        ),
+        Word::Placeholder(index) => ("placeholder", Some(index.to_string()), Vec::new()),
+        Word::Comment(comment) => ("comment", Some(comment.clone()), Vec::new()),
+        Word::Spaces => ("spaces", None, Vec::new()),
+        Word::BreakLine => ("break_line", None, Vec::new()),
+        Word::FlipLine => ("flip_line", None, Vec::new()),
+        Word::SemanticComment(comment) => ("semantic_comment", Some(comment.to_string()), Vec::new()),
+        Word::OutputComment { i, n } => ("output_comment", Some(format!("{}/{}", i, n)), Vec::new()),
+        Word::Subscripted(subscripted) => (
+            "subscripted",
+            Some(subscripted.n.value.to_string()),
+            vec![vec![convert_word(&subscripted.word, inputs)]],
+        ),
+        Word::InlineMacro(inline_macro) => (
+            "inline_macro",
+            Some(inline_macro.ident.value.to_string()),
+            convert_lines(&inline_macro.func.value.lines, inputs),
+        ),
+    };
+
+    AstWord {
+        kind,
+        span: convert_span(&word.span),
+        text: word.span.as_str(inputs, str::to_owned),
+        value,
+        lines,
+    }
+}
+
+fn convert_span(span: &CodeSpan) -> AstSpan {
+    let location = |loc: &uiua::Loc| AstLocation {
+        line: loc.line,
+        column: loc.col,
+        byte: loc.byte_pos,
+    };
+    AstSpan {
+        start: location(&span.start),
+        end: location(&span.end),
+    }
+}
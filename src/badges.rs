@@ -0,0 +1,66 @@
+use crate::extractor::{BindingDefinition, Documented, ItemContent};
+use serde::Serialize;
+
+/// A badge in the format of shields.io's endpoint badges, so READMEs can show
+/// `https://img.shields.io/endpoint?url=<site>/badges/coverage.json`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Badge {
+    pub schema_version: u8,
+    pub label: String,
+    pub message: String,
+    pub color: &'static str,
+}
+
+impl Badge {
+    fn new(label: &str, message: String, color: &'static str) -> Self {
+        Self {
+            schema_version: 1,
+            label: label.to_owned(),
+            message,
+            color,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Unable to serialize a badge")
+    }
+}
+
+/// The badges of a library, with the file names they are published under.
+pub fn build_badges(items: &[ItemContent], version: Option<&str>) -> Vec<(&'static str, Badge)> {
+    let mut bindings = Vec::new();
+    collect_bindings(items, &mut bindings);
+
+    let commented = bindings.iter().filter(|binding| binding.comment().is_some()).count();
+    let coverage = match bindings.len() {
+        0 => 100,
+        total => commented * 100 / total,
+    };
+    let coverage_color = match coverage {
+        90.. => "brightgreen",
+        75.. => "green",
+        50.. => "yellow",
+        _ => "red",
+    };
+
+    let mut badges = vec![
+        ("coverage.json", Badge::new("docs", format!("{}%", coverage), coverage_color)),
+        ("bindings.json", Badge::new("bindings", bindings.len().to_string(), "blue")),
+        ("uiua.json", Badge::new("uiua", uiua::VERSION.to_owned(), "blueviolet")),
+    ];
+    if let Some(version) = version {
+        badges.push(("version.json", Badge::new("version", version.to_owned(), "orange")));
+    }
+    badges
+}
+
+fn collect_bindings<'a>(items: &'a [ItemContent], bindings: &mut Vec<&'a BindingDefinition>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.is_documented() => bindings.push(binding),
+            ItemContent::Module(module) => collect_bindings(&module.items, bindings),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::extractor::{BindingDefinition, ItemContent};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use uiua::{Compiler, Uiua};
+
+/// How many timed runs the median of a benchmark is taken from, after one warm-up run.
+const BENCHMARK_RUNS: usize = 15;
+
+/// The timing of an `# @bench` expression, measured while generating the documentation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Benchmark {
+    pub expression: String,
+    pub median: Duration,
+    pub runs: usize,
+}
+
+/// Times the `@bench` expressions of all bindings with the embedded interpreter.
+///
+/// The expressions run at the top level of the library, after the library itself has run.
+pub fn run_benchmarks(items: &mut [ItemContent], compiler: &Compiler) {
+    let mut bindings = Vec::new();
+    collect_benchmarked(items, &mut bindings);
+    if bindings.is_empty() {
+        return;
+    }
+
+    let mut compiler = compiler.clone();
+    let mut env = Uiua::with_backend(compiler.backend()).with_execution_limit(Duration::from_secs(10));
+    // Bindings computed at runtime only have values once the library has run
+    if let Err(err) = env.run_compiler(&mut compiler) {
+        eprintln!("Warning: unable to run the library for benchmarks: {}", err);
+        return;
+    }
+    env.take_stack();
+
+    for binding in bindings {
+        let Some(expression) = binding.directives.get("@bench") else {
+            eprintln!("Warning: `@bench` on `{}` has no expression to time", binding.name);
+            continue;
+        };
+
+        match measure(&compiler, &mut env, expression) {
+            Ok(median) => {
+                binding.benchmark = Some(Benchmark {
+                    expression: expression.to_owned(),
+                    median,
+                    runs: BENCHMARK_RUNS,
+                })
+            }
+            Err(err) => eprintln!("Warning: benchmark of `{}` failed: {}", binding.name, err),
+        }
+    }
+}
+
+fn collect_benchmarked<'a>(items: &'a mut [ItemContent], bindings: &mut Vec<&'a mut BindingDefinition>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.directives.has("@bench") => bindings.push(binding),
+            ItemContent::Module(module) => collect_benchmarked(&mut module.items, bindings),
+            _ => {}
+        }
+    }
+}
+
+fn measure(compiler: &Compiler, env: &mut Uiua, expression: &str) -> Result<Duration, String> {
+    let mut compiler = compiler.clone();
+    compiler.assembly_mut().root.clear();
+    if let Err(err) = compiler.load_str(expression) {
+        return Err(err.to_string());
+    }
+    let asm = compiler.finish();
+
+    let mut times = Vec::with_capacity(BENCHMARK_RUNS);
+    for run in 0..=BENCHMARK_RUNS {
+        let asm = asm.clone();
+        let start = Instant::now();
+        let result = env.run_asm(asm);
+        let elapsed = start.elapsed();
+        env.take_stack();
+
+        result.map_err(|err| err.to_string())?;
+        if run > 0 {
+            times.push(elapsed);
+        }
+    }
+
+    times.sort();
+    Ok(times[times.len() / 2])
+}
+
+/// Formats a duration with a unit that keeps the number short, like `1.25 ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    match nanos {
+        n if n < 1e3 => format!("{:.0} ns", n),
+        n if n < 1e6 => format!("{:.2} µs", n / 1e3),
+        n if n < 1e9 => format!("{:.2} ms", n / 1e6),
+        n => format!("{:.2} s", n / 1e9),
+    }
+}
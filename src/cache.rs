@@ -0,0 +1,112 @@
+use crate::analysis::used_primitives;
+use crate::extractor::FileContent;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uiua::{Inputs, Primitive, SysOpClass};
+
+/// The directory where extracted items are kept between runs, in the user's cache directory so nothing is written
+/// next to the library's sources.
+fn cache_directory() -> PathBuf {
+    let user_cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| cfg!(windows).then(|| env::var_os("LOCALAPPDATA")).flatten().map(PathBuf::from))
+        .or_else(|| {
+            env::var_os("HOME").map(|home| match cfg!(target_os = "macos") {
+                true => Path::new(&home).join("Library").join("Caches"),
+                false => Path::new(&home).join(".cache"),
+            })
+        })
+        .unwrap_or_else(env::temp_dir);
+    user_cache.join("uiua-doc-gen")
+}
+
+/// The cache file of the library in `root`, named after its canonical path so every library has one of its own.
+fn cache_file(root: &Path) -> PathBuf {
+    let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    cache_directory().join(format!("{:016x}.json", stable_hash(root.to_string_lossy().as_bytes())))
+}
+
+/// The items extracted from a library, along with what they were extracted from. The cache
+/// is only used while every source file is unchanged and the library is extracted the same way.
+#[derive(Serialize, Deserialize)]
+struct ExtractionCache<Files> {
+    generator_version: String,
+    settings: String,
+    sources: Vec<CachedSource>,
+    files: Files,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSource {
+    path: PathBuf,
+    hash: u64,
+}
+
+/// The 64-bit FNV-1a hash of the content, which is the same on every platform and with every version of Rust, unlike
+/// the hashers of the standard library, so it can be kept between runs.
+pub(crate) fn stable_hash(content: &[u8]) -> u64 {
+    content
+        .iter()
+        .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn hash_source(source: &str) -> u64 {
+    stable_hash(source.as_bytes())
+}
+
+/// Whether the code uses the file system, like `&fras` for a constant read from a data file. Only the sources are
+/// compared with the cache, so the items of a library that reads other files could be out of date.
+fn uses_files(source: &str) -> bool {
+    used_primitives(source)
+        .into_iter()
+        .any(|primitive| matches!(primitive, Primitive::Sys(operation) if matches!(operation.class(), SysOpClass::Filesystem)))
+}
+
+/// The cached items of the library, if none of its sources changed since they were extracted.
+pub(crate) fn read_cached_extraction(root: &Path, settings: &str) -> Option<Vec<FileContent>> {
+    let cache: ExtractionCache<Vec<FileContent>> = serde_json::from_str(&fs::read_to_string(cache_file(root)).ok()?).ok()?;
+    if cache.generator_version != env!("CARGO_PKG_VERSION") || cache.settings != settings {
+        return None;
+    }
+
+    // Source paths are relative to the library's directory, like the compiler's
+    let unchanged = cache
+        .sources
+        .iter()
+        .all(|source| fs::read_to_string(root.join(&source.path)).is_ok_and(|content| hash_source(&content) == source.hash));
+    unchanged.then_some(cache.files)
+}
+
+/// Saves the items extracted from the sources for the next run. Failing to do so only makes the next run slower.
+/// Libraries that use files aren't saved, since the cache can't tell when the files they read change.
+pub(crate) fn write_cached_extraction(root: &Path, settings: &str, inputs: &Inputs, files: &[FileContent]) {
+    if inputs.files.iter().any(|file| uses_files(file.value())) {
+        return;
+    }
+    let sources = inputs
+        .files
+        .iter()
+        .map(|file| CachedSource {
+            path: file.key().clone(),
+            hash: hash_source(file.value()),
+        })
+        .collect();
+    let cache = ExtractionCache {
+        generator_version: env!("CARGO_PKG_VERSION").to_owned(),
+        settings: settings.to_owned(),
+        sources,
+        files,
+    };
+
+    let path = cache_file(root);
+    match serde_json::to_string(&cache) {
+        Ok(json) => {
+            if let Err(err) = fs::create_dir_all(cache_directory()).and_then(|_| fs::write(&path, json)) {
+                eprintln!("Warning: unable to write the cache {}: {}", path.display(), err);
+            }
+        }
+        Err(err) => eprintln!("Warning: unable to cache the extracted items: {}", err),
+    }
+}
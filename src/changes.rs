@@ -0,0 +1,236 @@
+use crate::extractor::{BindingType, ItemContent};
+use crate::metadata::ProjectMetadata;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// The public API of a library at one release, published as `api.json` with the site.
+///
+/// Passing the snapshot of a previous release to the next generation lets the changes
+/// between them be listed in an Atom feed. Earlier releases are carried over in `history`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSnapshot {
+    pub release: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub bindings: Vec<ApiBinding>,
+    pub history: Vec<ApiRelease>,
+    /// Every release published so far, newest first, starting with this one.
+    #[serde(default)]
+    pub releases: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiBinding {
+    /// The name, qualified by the modules it's in, like `Module~Name`.
+    pub name: String,
+    pub kind: String,
+    pub signature: Option<String>,
+}
+
+/// The bindings that were added, removed or changed in a release, newest releases first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiRelease {
+    pub release: String,
+    /// When the release was generated, in RFC 3339 format.
+    pub updated: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("Unable to read the previous API snapshot: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid previous API snapshot: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub fn read_api_snapshot(path: &Path) -> Result<ApiSnapshot, SnapshotError> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+impl ApiSnapshot {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Unable to serialize the API snapshot")
+    }
+}
+
+/// Takes a snapshot of the documented bindings, recording how they changed since the previous snapshot.
+///
+/// `time` is when the snapshot is taken, in seconds since the Unix epoch, see [`build_time`].
+pub fn build_api_snapshot(
+    items: &[ItemContent],
+    metadata: &ProjectMetadata,
+    release: Option<String>,
+    previous: Option<&ApiSnapshot>,
+    time: u64,
+) -> ApiSnapshot {
+    let updated = rfc3339(time);
+    let release = release.unwrap_or_else(|| updated[..10].to_owned());
+
+    let mut bindings = Vec::new();
+    collect_api_bindings(items, "", &mut bindings);
+
+    let history = match previous {
+        Some(previous) => {
+            let find = |bindings: &[ApiBinding], name: &str| bindings.iter().find(|binding| binding.name == name).cloned();
+            let names = |bindings: &[ApiBinding], filter: &dyn Fn(&ApiBinding) -> bool| {
+                bindings
+                    .iter()
+                    .filter(|binding| filter(binding))
+                    .map(|binding| binding.name.clone())
+                    .collect()
+            };
+
+            let entry = ApiRelease {
+                release: release.clone(),
+                updated,
+                added: names(&bindings, &|binding| find(&previous.bindings, &binding.name).is_none()),
+                removed: names(&previous.bindings, &|binding| find(&bindings, &binding.name).is_none()),
+                changed: names(&bindings, &|binding| {
+                    find(&previous.bindings, &binding.name).is_some_and(|old| old != *binding)
+                }),
+            };
+            std::iter::once(entry).chain(previous.history.iter().cloned()).collect()
+        }
+        None => Vec::new(),
+    };
+
+    // Snapshots from before the list of releases only know the releases in their history
+    let mut releases = vec![release.clone()];
+    if let Some(previous) = previous {
+        let earlier = match previous.releases.is_empty() {
+            true => std::iter::once(&previous.release)
+                .chain(previous.history.iter().map(|entry| &entry.release))
+                .cloned()
+                .collect(),
+            false => previous.releases.clone(),
+        };
+        for earlier in earlier {
+            if !releases.contains(&earlier) {
+                releases.push(earlier);
+            }
+        }
+    }
+
+    ApiSnapshot {
+        release,
+        version: metadata.version.clone(),
+        description: metadata.description.clone(),
+        bindings,
+        history,
+        releases,
+    }
+}
+
+fn collect_api_bindings(items: &[ItemContent], prefix: &str, bindings: &mut Vec<ApiBinding>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.is_documented() => bindings.push(ApiBinding {
+                name: format!("{}{}", prefix, binding.name),
+                kind: binding.kind.name().to_owned(),
+                signature: match &binding.kind {
+                    BindingType::Function(function) => function.signature.as_ref().map(ToString::to_string),
+                    _ => None,
+                },
+            }),
+            ItemContent::Module(module) => collect_api_bindings(&module.items, &format!("{}{}~", prefix, module.name), bindings),
+            _ => {}
+        }
+    }
+}
+
+/// An Atom feed with an entry for every release in the snapshot's history.
+pub fn api_changes_feed(title: &str, snapshot: &ApiSnapshot) -> String {
+    // Identifiers have to be valid URIs, so anything unusual in the names is replaced
+    let uri_part = |text: &str| text.replace(|c: char| !c.is_ascii_alphanumeric() && !"-._".contains(c), "-");
+    let id = |release: &str| format!("urn:uiua-doc-gen:{}:{}", uri_part(title), uri_part(release));
+    let list = |label: &str, names: &[String]| match names {
+        [] => String::new(),
+        names => format!("{}: {}\n", label, names.join(", ")),
+    };
+
+    let entries = snapshot
+        .history
+        .iter()
+        .map(|release| {
+            let mut summary = list("Added", &release.added) + &list("Removed", &release.removed) + &list("Changed", &release.changed);
+            if summary.is_empty() {
+                summary = "No changes to the public API.".to_owned();
+            }
+            format!(
+                "  <entry>\n    <id>{}</id>\n    <title>{}</title>\n    <updated>{}</updated>\n    <link href=\"index.html\"/>\n    <summary>{}</summary>\n  </entry>\n",
+                id(&release.release),
+                escape_xml(&release.release),
+                release.updated,
+                escape_xml(summary.trim_end())
+            )
+        })
+        .collect::<String>();
+
+    let updated = snapshot
+        .history
+        .first()
+        .map(|release| release.updated.clone())
+        .unwrap_or_else(|| rfc3339(build_time(false)));
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <id>{}</id>\n  <title>{} API changes</title>\n  <updated>{}</updated>\n  <author><name>{}</name></author>\n{}</feed>\n",
+        id("changes"),
+        escape_xml(title),
+        updated,
+        escape_xml(title),
+        entries
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The time a build is dated with, in seconds since the Unix epoch: `SOURCE_DATE_EPOCH` when it's set, like tools for
+/// reproducible builds expect, the epoch itself for other deterministic builds, and the current time otherwise.
+pub fn build_time(deterministic: bool) -> u64 {
+    let source_date = env::var("SOURCE_DATE_EPOCH").ok().and_then(|seconds| seconds.trim().parse().ok());
+    match (source_date, deterministic) {
+        (Some(seconds), _) => seconds,
+        (None, true) => 0,
+        (None, false) => SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0),
+    }
+}
+
+/// A time in UTC, like `2024-11-05T14:30:00Z`.
+fn rfc3339(seconds: u64) -> String {
+    let (days, time) = (seconds / 86400, seconds % 86400);
+
+    // Converts days since the epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
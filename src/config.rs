@@ -0,0 +1,132 @@
+use crate::fonts::FontStrategy;
+use crate::generator::Theme;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The file in the library's directory with the options of the generator, so they don't have to be passed on every run.
+pub const CONFIG_FILE: &str = "uiua-doc.toml";
+
+/// The `uiua-doc.toml` written by `init`, with every option commented out so the defaults apply until it's edited.
+pub const CONFIG_TEMPLATE: &str = r#"# Options of uiua-doc-gen for the docs of this library. The command line options take precedence over them.
+
+# The library's entry file, relative to this directory
+# lib = "lib.ua"
+
+# The title of the docs, instead of the library's name
+# title = "My Library"
+
+# The directory the docs are written to, relative to this directory
+# output = "doc-site"
+
+# The URL the docs are published at
+# base_url = "https://user.github.io/my-library"
+
+# The URL of the docs of each release, to link the earlier releases from the header
+# version_url = "https://user.github.io/my-library/{version}/"
+
+# Modules left out of the docs, along with the ones excluded in uiua.toml
+# exclude_modules = ["Internal"]
+
+# Files and modules left out of the docs, written like the lines of a .gitignore
+# exclude = ["internal/*.ua", "Geo.Internal*"]
+
+[theme]
+# The theme pages are shown in until the reader picks one: "dark" or "light"
+# default = "dark"
+
+# Where the site loads the font for code from: "subset", "embed", "cdn" or "system"
+# font = "subset"
+
+# Bindings whose names start with the prefix are listed together, after a shared description
+# [[groups]]
+# prefix = "Sort"
+# title = "Sorting"
+# description = "All sorts are stable."
+
+# The libraries documented together by `uiua-doc-gen workspace` when this directory has several
+# [workspace]
+# members = ["core", "extras"]
+
+# Items and modules that were renamed or moved, from their old names to the new ones, so links to their pages keep working
+# [moved]
+# "Geo~Area" = "Geo~Shapes~Area"
+"#;
+
+/// The contents of `uiua-doc.toml`. Unlike `uiua.toml`, it describes the docs rather than the project, and the
+/// command line options take precedence over it.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneratorConfig {
+    /// The library's entry file, relative to its directory, when it isn't `lib.ua`.
+    pub lib: Option<PathBuf>,
+    /// The title of the docs, instead of the library's name.
+    pub title: Option<String>,
+    /// The directory the docs are written to, relative to the library's directory.
+    pub output: Option<PathBuf>,
+    /// The URL the docs are published at.
+    pub base_url: Option<String>,
+    /// The URL of the docs of a release, with `{version}` where the release goes.
+    pub version_url: Option<String>,
+    /// Modules left out of the docs, along with the ones excluded in `uiua.toml`.
+    pub exclude_modules: Vec<String>,
+    /// Patterns of the files and modules left out of the docs, like `internal/*.ua`.
+    pub exclude: Vec<String>,
+    pub theme: ThemeConfig,
+    pub groups: Vec<GroupConfig>,
+    pub workspace: WorkspaceConfig,
+    /// Items and modules that were renamed or moved, from their old names qualified by their modules, like `Geo~Area`,
+    /// to their new ones.
+    pub moved: BTreeMap<String, String>,
+}
+
+/// The `[theme]` table of `uiua-doc.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// The theme pages are shown in until the reader picks one.
+    pub default: Option<Theme>,
+    pub font: Option<FontStrategy>,
+}
+
+/// The `[workspace]` table of `uiua-doc.toml`, for a directory with several libraries documented into one site.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// The directories of the libraries, relative to the workspace's directory.
+    pub members: Vec<PathBuf>,
+}
+
+/// A `[[groups]]` table of `uiua-doc.toml`, listing a family of bindings like `Sort`, `SortBy` and `SortDesc` in a
+/// group of their own instead of with the other bindings of their kind.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupConfig {
+    /// The start of the names of the bindings in the group.
+    pub prefix: String,
+    /// The title of the group, instead of the prefix.
+    pub title: Option<String>,
+    /// Markdown shown above the group's panels, for what its bindings have in common.
+    pub description: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigFileError {
+    #[error("Unable to read {CONFIG_FILE}: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid {CONFIG_FILE}: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+/// Reads the generator's config file of the library, if it has one.
+pub fn read_config_file(root: &Path) -> Result<Option<GeneratorConfig>, ConfigFileError> {
+    let path = root.join(CONFIG_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    Ok(Some(toml::from_str(&fs::read_to_string(path)?)?))
+}
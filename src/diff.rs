@@ -0,0 +1,308 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How many lines of a diff are printed for one file, so a page that changed throughout doesn't flood the log.
+const MAX_FILE_LINES: usize = 100;
+
+/// How many lines of diffs are printed in total.
+const MAX_TOTAL_LINES: usize = 500;
+
+/// Longer lines are cut off, since minified files and the search index can be a single line.
+const MAX_LINE_WIDTH: usize = 240;
+
+/// Files that differ in more lines than this are shown as replaced as a whole, which is quicker to find.
+const MAX_EDIT_DISTANCE: usize = 4000;
+
+/// The lines of unchanged text shown around every change.
+const CONTEXT_LINES: usize = 3;
+
+/// A file that's different in a freshly generated copy of the docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    /// Only the fresh docs have the file.
+    Added(PathBuf),
+    /// Only the old docs have the file.
+    Removed(PathBuf),
+    /// The file's content changed.
+    Changed(PathBuf),
+}
+
+impl FileChange {
+    pub fn path(&self) -> &Path {
+        match self {
+            FileChange::Added(path) | FileChange::Removed(path) | FileChange::Changed(path) => path,
+        }
+    }
+}
+
+/// The files that differ between two directories, by their paths relative to them.
+pub fn compare_directories(old: &Path, new: &Path) -> io::Result<Vec<FileChange>> {
+    let old_files = list_files(old)?;
+    let new_files = list_files(new)?;
+
+    let mut changes = Vec::new();
+    for file in &old_files {
+        match new_files.binary_search(file) {
+            Ok(_) if fs::read(old.join(file))? != fs::read(new.join(file))? => changes.push(FileChange::Changed(file.clone())),
+            Ok(_) => {}
+            Err(_) => changes.push(FileChange::Removed(file.clone())),
+        }
+    }
+    for file in &new_files {
+        if old_files.binary_search(file).is_err() {
+            changes.push(FileChange::Added(file.clone()));
+        }
+    }
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+    Ok(changes)
+}
+
+/// Every file in the directory, hidden ones included, sorted by their paths relative to it.
+fn list_files(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            match path.is_dir() {
+                true => directories.push(path),
+                false => files.push(path.strip_prefix(root).unwrap_or(&path).to_path_buf()),
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Writes diffs of the changed files like `diff -u`, coloured for terminals and CI logs when `color` is set.
+///
+/// The diffs are cut short after a number of lines, both for every file and in total. Pages are split into a line
+/// per tag and JSON is pretty printed first, so that their diffs show what changed rather than one long line.
+pub fn print_diffs(old: &Path, new: &Path, changes: &[FileChange], color: bool) {
+    let paint = |code: &str, text: &str| match color {
+        true => format!("\x1b[{}m{}\x1b[0m", code, text),
+        false => text.to_owned(),
+    };
+
+    let mut printed = 0;
+    for change in changes {
+        let path = change.path();
+        let label = path.display().to_string().replace('\\', "/");
+        match change {
+            FileChange::Added(_) => println!("{}", paint("1;32", &format!("Added {}", label))),
+            FileChange::Removed(_) => println!("{}", paint("1;31", &format!("Removed {}", label))),
+            FileChange::Changed(_) => println!("{}", paint("1", &format!("Changed {}", label))),
+        }
+        let FileChange::Changed(_) = change else {
+            continue;
+        };
+        if printed >= MAX_TOTAL_LINES {
+            continue;
+        }
+
+        let (Some(old_text), Some(new_text)) = (comparable_text(&old.join(path)), comparable_text(&new.join(path))) else {
+            println!("  (binary file)");
+            continue;
+        };
+        let old_lines = old_text.lines().collect::<Vec<_>>();
+        let new_lines = new_text.lines().collect::<Vec<_>>();
+        let lines = unified_diff(&old_lines, &new_lines);
+        let limit = MAX_FILE_LINES.min(MAX_TOTAL_LINES - printed);
+
+        println!("{}", paint("31", &format!("--- old/{}", label)));
+        println!("{}", paint("32", &format!("+++ new/{}", label)));
+        for line in lines.iter().take(limit) {
+            let text = shorten(line);
+            match line.chars().next() {
+                Some('@') => println!("{}", paint("36", &text)),
+                Some('-') => println!("{}", paint("31", &text)),
+                Some('+') => println!("{}", paint("32", &text)),
+                _ => println!("{}", text),
+            }
+        }
+        if lines.len() > limit {
+            println!("{}", paint("2", &format!("... {} more lines of changes", lines.len() - limit)));
+        }
+        printed += lines.len().min(limit);
+        if printed >= MAX_TOTAL_LINES {
+            println!("{}", paint("2", "... the diffs of the other changed files are left out"));
+        }
+    }
+}
+
+fn shorten(line: &str) -> String {
+    match line.char_indices().nth(MAX_LINE_WIDTH) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_owned(),
+    }
+}
+
+/// The text of a file in a form that's readable in a diff, or `None` for files that aren't text.
+fn comparable_text(file: &Path) -> Option<String> {
+    let extension = file.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    let text = fs::read_to_string(file).ok()?;
+    match extension {
+        "html" | "xml" | "svg" => {
+            let text = text.replace('<', "\n<").replace('>', ">\n");
+            Some(text.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>().join("\n"))
+        }
+        "json" => Some(
+            serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|json| serde_json::to_string_pretty(&json).ok())
+                .unwrap_or(text),
+        ),
+        _ => Some(text),
+    }
+}
+
+/// The lines of a unified diff between two texts, with hunk headers like `@@ -1,4 +1,5 @@`.
+fn unified_diff(old: &[&str], new: &[&str]) -> Vec<String> {
+    let edits = diff_lines(old, new);
+
+    // Changes closer together than twice the context are in the same hunk
+    let mut lines = Vec::new();
+    let changed = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Equal(..)))
+        .map(|(index, _)| index);
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for index in changed {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + CONTEXT_LINES + 1).min(edits.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        let hunk = &edits[start..end];
+        let (old_start, new_start) = hunk[0].position();
+        let old_count = hunk.iter().filter(|edit| !matches!(edit, Edit::Insert(..))).count();
+        let new_count = hunk.iter().filter(|edit| !matches!(edit, Edit::Delete(..))).count();
+        lines.push(format!("@@ -{},{} +{},{} @@", old_start + 1, old_count, new_start + 1, new_count));
+        for edit in hunk {
+            lines.push(match *edit {
+                Edit::Equal(old_index, _) => format!(" {}", old[old_index]),
+                Edit::Delete(old_index, _) => format!("-{}", old[old_index]),
+                Edit::Insert(_, new_index) => format!("+{}", new[new_index]),
+            });
+        }
+    }
+    lines
+}
+
+/// One step of turning the old lines into the new ones, with the indices in the old and new lines it's at.
+#[derive(Debug, Clone, Copy)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize, usize),
+    Insert(usize, usize),
+}
+
+impl Edit {
+    fn position(self) -> (usize, usize) {
+        match self {
+            Edit::Equal(old, new) | Edit::Delete(old, new) | Edit::Insert(old, new) => (old, new),
+        }
+    }
+}
+
+/// The shortest edit from the old lines to the new ones, found with Myers' algorithm after the lines the texts
+/// start and end with are set aside.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(old, new)| old == new).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let (old_middle, new_middle) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut edits = (0..prefix).map(|index| Edit::Equal(index, index)).collect::<Vec<_>>();
+    let middle = shortest_edit(old_middle, new_middle).unwrap_or_else(|| {
+        let deleted = (0..old_middle.len()).map(|index| Edit::Delete(index, 0));
+        deleted
+            .chain((0..new_middle.len()).map(|index| Edit::Insert(old_middle.len(), index)))
+            .collect()
+    });
+    edits.extend(middle.into_iter().map(|edit| match edit {
+        Edit::Equal(old, new) => Edit::Equal(old + prefix, new + prefix),
+        Edit::Delete(old, new) => Edit::Delete(old + prefix, new + prefix),
+        Edit::Insert(old, new) => Edit::Insert(old + prefix, new + prefix),
+    }));
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    edits.extend((0..suffix).map(|index| Edit::Equal(old_end + index, new_end + index)));
+    edits
+}
+
+/// Myers' algorithm, or `None` when the texts differ in more than `MAX_EDIT_DISTANCE` lines.
+fn shortest_edit(old: &[&str], new: &[&str]) -> Option<Vec<Edit>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    // The furthest x reached on every diagonal k = x - y, for every number of edits d, indexed by k + d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    for d in 0..=(n + m).min(MAX_EDIT_DISTANCE as isize) {
+        let mut furthest = vec![0; 2 * d as usize + 1];
+        for k in (-d..=d).step_by(2) {
+            let mut x = match trace.last() {
+                None => 0,
+                Some(previous) => {
+                    let at = |k: isize| previous[(k + d - 1) as usize];
+                    match k == -d || (k != d && at(k - 1) < at(k + 1)) {
+                        true => at(k + 1),
+                        false => at(k - 1) + 1,
+                    }
+                }
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            furthest[(k + d) as usize] = x;
+            if x >= n && y >= m {
+                trace.push(furthest);
+                return Some(backtrack(&trace, n, m));
+            }
+        }
+        trace.push(furthest);
+    }
+    None
+}
+
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (1..trace.len() as isize).rev() {
+        let previous = &trace[d as usize - 1];
+        let at = |k: isize| previous[(k + d - 1) as usize];
+        let k = x - y;
+        let previous_k = match k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            true => k + 1,
+            false => k - 1,
+        };
+        let previous_x = at(previous_k);
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+        match previous_k == k + 1 {
+            true => edits.push(Edit::Insert(previous_x as usize, previous_y as usize)),
+            false => edits.push(Edit::Delete(previous_x as usize, previous_y as usize)),
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        edits.push(Edit::Equal(x as usize, y as usize));
+    }
+    edits.reverse();
+    edits
+}
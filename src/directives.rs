@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+/// A `!doc` directive written in an item's comment, like `# !doc merge` or `# !doc order: 10`,
+/// or an annotation like `# @bench Sort ⇡100`, which is stored with its `@`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Directive {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+impl Directive {
+    /// Whether the generator knows the directive or annotation, whatever its value.
+    pub fn is_known(&self) -> bool {
+        DIRECTIVES.contains(&self.name.as_str()) || ANNOTATIONS.contains(&self.name.as_str())
+    }
+
+    /// What's wrong with the directive, when it's one the generator doesn't know or its value doesn't fit.
+    pub fn problem(&self) -> Option<String> {
+        let value = self.value.as_deref();
+        match self.name.as_str() {
+            "hide" | "hide-code" | "merge" => value.map(|_| format!("`!doc {}` doesn't take a value", self.name)),
+            "badge" => value
+                .filter(|value| !value.is_empty())
+                .is_none()
+                .then(|| "`!doc badge` needs a label, like `!doc badge: WIP`".to_owned()),
+            "order" => value
+                .and_then(|value| value.parse::<i64>().ok())
+                .is_none()
+                .then(|| "`!doc order` needs a whole number, like `!doc order: 10`".to_owned()),
+            name if name.starts_with('@') => None,
+            name => Some(format!(
+                "unknown directive `!doc {}`, the directives are {}",
+                name,
+                DIRECTIVES.map(|name| format!("`{}`", name)).join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Directives(Vec<Directive>);
+
+impl Directives {
+    pub fn iter(&self) -> impl Iterator<Item = &Directive> {
+        self.0.iter()
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.0.iter().any(|directive| directive.name == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|directive| directive.name == name)
+            .and_then(|directive| directive.value.as_deref())
+    }
+
+    /// The values of every directive with the name, for directives that can be repeated.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.0
+            .iter()
+            .filter(move |directive| directive.name == name)
+            .filter_map(|directive| directive.value.as_deref())
+    }
+
+    /// The labels added with `!doc badge: Label` or `!doc badge: Label, color`.
+    pub fn badges(&self) -> Vec<CustomBadge> {
+        self.get_all("badge")
+            .filter_map(|value| {
+                let (label, color) = match value.rsplit_once(',') {
+                    Some((label, color)) => (label.trim(), Some(color.trim())),
+                    None => (value, None),
+                };
+                (!label.is_empty()).then(|| CustomBadge {
+                    label: label.to_owned(),
+                    color: color.filter(|color| is_css_color(color)).map(str::to_owned),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A label the author put on a binding, like "WIP", shown next to its kind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomBadge {
+    pub label: String,
+    /// A CSS color, like `orange` or `#f80`.
+    pub color: Option<String>,
+}
+
+/// Colors end up in a `style` attribute, so anything but names, hex codes and color functions is ignored.
+fn is_css_color(color: &str) -> bool {
+    !color.is_empty()
+        && color
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '#' | '(' | ')' | ',' | '.' | '%' | ' ' | '-'))
+}
+
+/// The directives bindings can have, like `!doc hide`.
+const DIRECTIVES: [&str; 5] = ["hide", "hide-code", "badge", "order", "merge"];
+
+/// Annotations that take the rest of their line as the value, like `@bench expression`.
+const ANNOTATIONS: [&str; 1] = ["@bench"];
+
+/// Splits directive lines out of a doc comment, returning the remaining text and the directives.
+pub fn extract_directives(comment: &str) -> (Option<String>, Directives) {
+    let mut directives = Vec::new();
+    let mut lines = Vec::new();
+
+    for line in comment.lines() {
+        match parse_directive(line.trim()) {
+            Some(directive) => directives.push(directive),
+            None => lines.push(line),
+        }
+    }
+
+    let text = lines.join("\n").trim().to_owned();
+    let text = if text.is_empty() { None } else { Some(text) };
+
+    (text, Directives(directives))
+}
+
+/// The directive or annotation on a line of a comment, without the `#`.
+pub fn parse_directive(line: &str) -> Option<Directive> {
+    match line.strip_prefix("!doc ") {
+        Some(directive) => {
+            let (name, value) = match directive.split_once(':') {
+                Some((name, value)) => (name, Some(value.trim().to_owned())),
+                None => (directive, None),
+            };
+            Some(Directive {
+                name: name.trim().to_owned(),
+                value,
+            })
+        }
+        None => parse_annotation(line),
+    }
+}
+
+/// What's wrong with a line of a comment that looks like a directive but isn't read as one, like `!docs hide` or
+/// `@bnech Sort ⇡100`.
+pub fn malformed_directive(line: &str) -> Option<String> {
+    if let Some(rest) = line.strip_prefix("!doc") {
+        return (!rest.is_empty() && !rest.starts_with(char::is_whitespace))
+            .then(|| format!("`{}` isn't a directive, they're written like `!doc hide`", line));
+    }
+
+    let word = line.split_whitespace().next()?;
+    let name = word.strip_prefix('@')?;
+    (!name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '-') && !ANNOTATIONS.contains(&word)).then(|| {
+        format!(
+            "unknown annotation `{}`, the annotations are {}",
+            word,
+            ANNOTATIONS.map(|name| format!("`{}`", name)).join(", ")
+        )
+    })
+}
+
+fn parse_annotation(line: &str) -> Option<Directive> {
+    let name = ANNOTATIONS.into_iter().find(|name| {
+        line.strip_prefix(name)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    })?;
+    let value = line[name.len()..].trim();
+
+    Some(Directive {
+        name: name.to_owned(),
+        value: (!value.is_empty()).then(|| value.to_owned()),
+    })
+}
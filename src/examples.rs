@@ -0,0 +1,507 @@
+use crate::extractor::ExtractOptions;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Cursor, Read};
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uiua::{Compiler, GitTarget, Handle, SysBackend, Uiua};
+
+/// The directory of the files examples can read, relative to the library's directory.
+pub const DATA_DIRECTORY: &str = "docs/data";
+
+/// Annotations in the comments of an example that declare what it reads, like `# @stdin 3 4` or `# @env NAME=World`.
+const INPUT_ANNOTATIONS: [&str; 2] = ["@stdin", "@env"];
+
+/// An example program from the library's `examples/` directory, split into notebook cells.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExampleNotebook {
+    pub name: String,
+    /// What the example shows, like "Parsing CSV", from its `# @title` line.
+    pub title: Option<String>,
+    pub cells: Vec<NotebookCell>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum NotebookCell {
+    /// Markdown written in the comments between code.
+    Prose(String),
+    Code {
+        code: String,
+        /// The values left on the stack by the cell, top first.
+        outputs: Vec<String>,
+        error: Option<String>,
+        /// The cell was marked with `# @example(error)`, so its error is part of the example rather than a mistake.
+        expect_error: bool,
+    },
+}
+
+impl ExampleNotebook {
+    pub fn page_path(&self) -> String {
+        format!("examples/{}.html", self.name)
+    }
+
+    /// What the example is listed as, its title or the name of its file.
+    pub fn heading(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The first paragraph of prose, used to describe the example in listings.
+    pub fn summary(&self) -> Option<&str> {
+        self.cells.iter().find_map(|cell| match cell {
+            NotebookCell::Prose(prose) => prose.lines().map(str::trim).find(|line| !line.is_empty() && !line.starts_with('#')),
+            _ => None,
+        })
+    }
+}
+
+/// Reads and runs every `.ua` file in the `examples/` directory of the library,
+/// except the ones ignored by git. They run with the same backend as the library, except that they can't change files.
+pub fn extract_examples(root: &Path, options: &ExtractOptions) -> io::Result<Vec<ExampleNotebook>> {
+    let root = fs::canonicalize(root)?;
+    let directory = root.join("examples");
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = unignored_files(&directory)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ua"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    // Examples import the library and read files relative to their own location, so they run in their directory
+    // and the generator goes back to the one it was in afterwards
+    let previous_directory = env::current_dir()?;
+    env::set_current_dir(&directory)?;
+
+    let data_directory = Some(root.join(DATA_DIRECTORY)).filter(|directory| directory.is_dir());
+    let notebooks = paths
+        .iter()
+        .filter_map(|path| {
+            let source = fs::read_to_string(path).ok()?;
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            let input = read_example_input(&name, &source);
+            let backend = Arc::new(ExampleSys {
+                inner: options.backend(),
+                data_directory: data_directory.clone(),
+                stdin: Mutex::new(Cursor::new(input.stdin)),
+                vars: input.vars,
+            });
+            let cells = run_cells(split_cells(&source), is_experimental(&source), backend);
+            for cell in &cells {
+                if let NotebookCell::Code {
+                    code,
+                    error: None,
+                    expect_error: true,
+                    ..
+                } = cell
+                {
+                    let first_line = code.lines().next().unwrap_or_default();
+                    eprintln!(
+                        "Warning: The cell `{}` of example {} is marked with @example(error), but it ran without one",
+                        first_line, name
+                    );
+                }
+            }
+            Some(ExampleNotebook {
+                name,
+                title: read_title(&source),
+                cells,
+            })
+        })
+        .collect();
+
+    env::set_current_dir(previous_directory)?;
+    Ok(notebooks)
+}
+
+/// Copies the data files of the examples next to their pages, so the prose of an example can link to them.
+pub fn copy_data_files(root: &Path, output_directory: &Path) -> io::Result<()> {
+    let data_directory = root.join(DATA_DIRECTORY);
+    if !data_directory.is_dir() {
+        return Ok(());
+    }
+
+    for entry in WalkBuilder::new(&data_directory).require_git(false).build().filter_map(Result::ok) {
+        let Ok(relative) = entry.path().strip_prefix(&data_directory) else {
+            continue;
+        };
+        let target = output_directory.join("examples").join("data").join(relative);
+        match entry.file_type() {
+            Some(file_type) if file_type.is_dir() => fs::create_dir_all(target)?,
+            Some(file_type) if file_type.is_file() => {
+                fs::copy(entry.path(), target)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// What an example reads from stdin and the environment, declared in its comments so examples of command line
+/// programs show the same output on every generation.
+#[derive(Debug, Default)]
+struct ExampleInput {
+    /// The lines of every `@stdin` annotation, each ending with a newline.
+    stdin: Vec<u8>,
+    vars: HashMap<String, String>,
+}
+
+fn read_example_input(name: &str, source: &str) -> ExampleInput {
+    let mut input = ExampleInput::default();
+    for line in source.lines() {
+        match input_annotation(line.trim()) {
+            Some(("@stdin", text)) => {
+                input.stdin.extend_from_slice(text.as_bytes());
+                input.stdin.push(b'\n');
+            }
+            Some((_, variable)) => match variable.split_once('=') {
+                Some((variable, value)) => {
+                    input.vars.insert(variable.trim().to_owned(), value.trim().to_owned());
+                }
+                None => eprintln!(
+                    "Warning: `@env {}` in example {} has no value, write it as `@env NAME=value`",
+                    variable, name
+                ),
+            },
+            None => {}
+        }
+    }
+    input
+}
+
+/// The text of the example's `# @title` line, if it has one.
+fn read_title(source: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let title = title_annotation(line.trim())?.trim();
+        (!title.is_empty()).then(|| title.to_owned())
+    })
+}
+
+fn title_annotation(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix('#')?.trim_start().strip_prefix("@title")?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(rest)
+}
+
+/// Splits a comment line like `# @stdin 3 4` into the annotation and the rest of the line.
+fn input_annotation(line: &str) -> Option<(&'static str, &str)> {
+    let comment = line.strip_prefix('#')?.trim_start();
+    INPUT_ANNOTATIONS.into_iter().find_map(|name| {
+        let rest = comment.strip_prefix(name)?;
+        match rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            // Only the space after the annotation is left out, so stdin can have indented lines
+            true => Some((name, rest.strip_prefix(' ').unwrap_or(rest))),
+            false => None,
+        }
+    })
+}
+
+/// The backend examples run with. Files can be read but not changed, `docs/data` shows up as a `data` directory next
+/// to the examples, stdin has the `@stdin` lines and nothing after them, the `@env` variables are set, and everything
+/// else is passed on to the library's backend. Audio, images, FFI, servers and the terminal aren't available.
+struct ExampleSys {
+    inner: Arc<dyn SysBackend>,
+    data_directory: Option<PathBuf>,
+    stdin: Mutex<Cursor<Vec<u8>>>,
+    vars: HashMap<String, String>,
+}
+
+impl ExampleSys {
+    /// Where a file is read from: paths in the `data` directory are in `docs/data`, and other paths are passed on.
+    /// Paths that start in the `data` directory can't leave it, like `data/../../lib.ua`.
+    fn resolve(&self, path: &Path) -> Result<PathBuf, String> {
+        let Some(data_directory) = &self.data_directory else {
+            return Ok(path.to_path_buf());
+        };
+        let Ok(file) = path.strip_prefix(".").unwrap_or(path).strip_prefix("data") else {
+            return Ok(path.to_path_buf());
+        };
+        let mut data_path = data_directory.clone();
+        for component in file.components() {
+            match component {
+                Component::Normal(name) => data_path.push(name),
+                Component::CurDir => {}
+                Component::ParentDir if data_path != *data_directory => {
+                    data_path.pop();
+                }
+                _ => return Err(format!("{} is outside of the data directory", path.display())),
+            }
+        }
+        Ok(data_path)
+    }
+
+    fn read_only<T>(&self, path: &Path) -> Result<T, String> {
+        Err(format!("Examples can't change files, like {}", path.display()))
+    }
+}
+
+impl SysBackend for ExampleSys {
+    fn any(&self) -> &dyn Any {
+        self
+    }
+
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn print_str_stdout(&self, s: &str) -> Result<(), String> {
+        self.inner.print_str_stdout(s)
+    }
+
+    fn print_str_stderr(&self, s: &str) -> Result<(), String> {
+        self.inner.print_str_stderr(s)
+    }
+
+    fn print_str_trace(&self, s: &str) {
+        self.inner.print_str_trace(s)
+    }
+
+    fn scan_line_stdin(&self) -> Result<Option<String>, String> {
+        let mut line = Vec::new();
+        match self.stdin.lock().unwrap().read_until(b'\n', &mut line) {
+            Ok(0) => Ok(None),
+            Ok(_) => {
+                let line = line.strip_suffix(b"\n").unwrap_or(&line);
+                String::from_utf8(line.to_vec()).map(Some).map_err(|err| err.to_string())
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    fn scan_stdin(&self, count: Option<usize>) -> Result<Vec<u8>, String> {
+        let mut stdin = self.stdin.lock().unwrap();
+        let mut bytes = Vec::new();
+        let result = match count {
+            Some(count) => stdin.by_ref().take(count as u64).read_to_end(&mut bytes),
+            None => stdin.read_to_end(&mut bytes),
+        };
+        result.map(|_| bytes).map_err(|err| err.to_string())
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        self.vars.get(name).cloned().or_else(|| self.inner.var(name))
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        self.resolve(Path::new(path))
+            .is_ok_and(|path| self.inner.file_exists(&path.to_string_lossy()))
+    }
+
+    fn list_dir(&self, path: &str) -> Result<Vec<String>, String> {
+        self.inner.list_dir(&self.resolve(Path::new(path))?.to_string_lossy())
+    }
+
+    fn is_file(&self, path: &str) -> Result<bool, String> {
+        self.inner.is_file(&self.resolve(Path::new(path))?.to_string_lossy())
+    }
+
+    fn delete(&self, path: &str) -> Result<(), String> {
+        self.read_only(Path::new(path))
+    }
+
+    fn trash(&self, path: &str) -> Result<(), String> {
+        self.read_only(Path::new(path))
+    }
+
+    fn read(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
+        self.inner.read(handle, count)
+    }
+
+    fn read_all(&self, handle: Handle) -> Result<Vec<u8>, String> {
+        self.inner.read_all(handle)
+    }
+
+    fn read_until(&self, handle: Handle, delim: &[u8]) -> Result<Vec<u8>, String> {
+        self.inner.read_until(handle, delim)
+    }
+
+    fn write(&self, handle: Handle, contents: &[u8]) -> Result<(), String> {
+        self.inner.write(handle, contents)
+    }
+
+    fn create_file(&self, path: &Path) -> Result<Handle, String> {
+        self.read_only(path)
+    }
+
+    fn open_file(&self, path: &Path, write: bool) -> Result<Handle, String> {
+        if write {
+            return self.read_only(path);
+        }
+        self.inner.open_file(&self.resolve(path)?, false)
+    }
+
+    fn make_dir(&self, path: &Path) -> Result<(), String> {
+        self.read_only(path)
+    }
+
+    fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
+        self.inner.file_read_all(&self.resolve(path)?)
+    }
+
+    fn file_write_all(&self, path: &Path, _contents: &[u8]) -> Result<(), String> {
+        self.read_only(path)
+    }
+
+    fn sleep(&self, seconds: f64) -> Result<(), String> {
+        self.inner.sleep(seconds)
+    }
+
+    fn allow_thread_spawning(&self) -> bool {
+        self.inner.allow_thread_spawning()
+    }
+
+    fn now(&self) -> f64 {
+        self.inner.now()
+    }
+
+    fn tcp_connect(&self, addr: &str) -> Result<Handle, String> {
+        self.inner.tcp_connect(addr)
+    }
+
+    fn tls_connect(&self, addr: &str) -> Result<Handle, String> {
+        self.inner.tls_connect(addr)
+    }
+
+    fn tcp_addr(&self, handle: Handle) -> Result<SocketAddr, String> {
+        self.inner.tcp_addr(handle)
+    }
+
+    fn close(&self, handle: Handle) -> Result<(), String> {
+        self.inner.close(handle)
+    }
+
+    fn run_command_capture(&self, command: &str, args: &[&str]) -> Result<(i32, String, String), String> {
+        self.inner.run_command_capture(command, args)
+    }
+
+    fn change_directory(&self, path: &str) -> Result<(), String> {
+        self.inner.change_directory(path)
+    }
+
+    fn https_get(&self, request: &str, handle: Handle) -> Result<String, String> {
+        self.inner.https_get(request, handle)
+    }
+
+    fn load_git_module(&self, url: &str, target: GitTarget) -> Result<PathBuf, String> {
+        self.inner.load_git_module(url, target)
+    }
+
+    fn timezone(&self) -> Result<f64, String> {
+        self.inner.timezone()
+    }
+}
+
+/// The files directly in a directory that aren't hidden or ignored by a `.gitignore`,
+/// even when the library isn't in a git repository.
+fn unignored_files(directory: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(directory)
+        .max_depth(Some(1))
+        .require_git(false)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|file_type| file_type.is_file()))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Whether the file enables experimental features, which has to be repeated for every cell.
+fn is_experimental(source: &str) -> bool {
+    source.lines().any(|line| line.trim() == "# Experimental!")
+}
+
+/// Whether a comment marks the code cell after it as expected to fail. `@error` is a shorter way to write it.
+fn is_error_marker(comment: &str) -> bool {
+    matches!(comment.trim(), "@example(error)" | "@error")
+}
+
+/// Comment lines become prose, other lines are grouped into code cells separated by blank lines. A
+/// `# @example(error)` line marks the code cell after it as one that's expected to fail.
+fn split_cells(source: &str) -> Vec<NotebookCell> {
+    let mut cells = Vec::new();
+    let mut after_blank_line = false;
+    let mut expect_error = false;
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            after_blank_line = true;
+            continue;
+        }
+
+        if trimmed == "# Experimental!" || input_annotation(trimmed).is_some() || title_annotation(trimmed).is_some() {
+            continue;
+        }
+
+        if trimmed.strip_prefix('#').is_some_and(is_error_marker) {
+            expect_error = true;
+            continue;
+        }
+
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            let comment = comment.strip_prefix(' ').unwrap_or(comment);
+            match cells.last_mut() {
+                Some(NotebookCell::Prose(prose)) => {
+                    prose.push_str(if after_blank_line { "\n\n" } else { "\n" });
+                    prose.push_str(comment);
+                }
+                _ => cells.push(NotebookCell::Prose(comment.to_owned())),
+            }
+        } else {
+            match cells.last_mut() {
+                Some(NotebookCell::Code { code, .. }) if !after_blank_line && !expect_error => {
+                    code.push('\n');
+                    code.push_str(line);
+                }
+                _ => cells.push(NotebookCell::Code {
+                    code: line.to_owned(),
+                    outputs: Vec::new(),
+                    error: None,
+                    expect_error: std::mem::take(&mut expect_error),
+                }),
+            }
+        }
+        after_blank_line = false;
+    }
+
+    cells
+}
+
+/// Runs the code cells one after another, like lines in a REPL, so bindings carry over between cells.
+fn run_cells(mut cells: Vec<NotebookCell>, experimental: bool, backend: Arc<dyn SysBackend>) -> Vec<NotebookCell> {
+    let mut compiler = Compiler::with_backend(backend.clone());
+    let mut env = Uiua::with_backend(backend).with_execution_limit(Duration::from_secs(5));
+
+    for cell in &mut cells {
+        let NotebookCell::Code { code, outputs, error, .. } = cell else {
+            continue;
+        };
+
+        let backup = compiler.clone();
+        let source = if experimental {
+            format!("# Experimental!\n{}", code)
+        } else {
+            code.clone()
+        };
+        let result = match compiler.load_str(&source) {
+            Ok(_) => env.run_compiler(&mut compiler),
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => *outputs = env.take_stack().iter().rev().map(|value| value.show()).collect(),
+            Err(err) => {
+                compiler = backup;
+                env.take_stack();
+                *error = Some(err.to_string());
+            }
+        }
+        compiler.assembly_mut().root.clear();
+    }
+
+    cells
+}
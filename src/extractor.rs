@@ -1,18 +1,32 @@
 extern crate uiua;
 
+use ignore::overrides::{Override, OverrideBuilder};
 use same_file::is_same_file;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::env;
 use std::fmt;
-use std::fs::canonicalize;
+use std::fs::{self, canonicalize};
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 use thiserror::Error;
 
+use crate::analysis::{
+    code_metrics, flag_metric_outliers, link_module_dependencies, link_variants, tokenize_code, used_primitives, CodeMetrics, CodeToken,
+};
+use crate::benchmark::{run_benchmarks, Benchmark};
+use crate::cache::{read_cached_extraction, write_cached_extraction};
+use crate::directives::{extract_directives, Directives};
+use crate::validator::explain_validator;
+
 use uiua::{
-    ast::{Item, ModuleKind, Word},
-    parse, Assembly, BindingInfo, BindingKind, CodeSpan, Compiler, DocCommentSig, InputSrc, NativeSys, ParseError, Signature, Sp, SysBackend,
+    ast::{Binding, DataDef, Item, ModuleKind, Word},
+    ident_modifier_args, parse, Assembly, BindingInfo, BindingKind, CodeSpan, Compiler, DocComment, DocCommentSig, InputSrc, Inputs, NativeSys,
+    ParseError, Primitive, Signature, Sp, Span, SysBackend, UiuaErrorKind,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignatureInfo {
     pub inputs: usize,
     pub outputs: usize,
@@ -41,31 +55,240 @@ impl Colored for SignatureInfo {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NamedSignature {
     pub inputs: Vec<String>,
     pub outputs: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+impl NamedSignature {
+    /// The signature as it's written in a doc comment, like `# Area ? Width Height`.
+    pub fn to_comment(&self) -> String {
+        std::iter::once("#")
+            .chain(self.outputs.iter().map(String::as_str))
+            .chain(std::iter::once("?"))
+            .chain(self.inputs.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Field {
     pub name: String,
     pub validator: Option<String>,
+    pub validator_explanation: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Definition {
     pub boxed: bool,
     pub fields: Vec<Field>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BindingDefinition {
     pub name: String,
+    /// The modules the binding is nested in, outermost first.
+    pub module_path: Vec<String>,
     pub code: String,
     pub public: bool,
+    /// Set on private bindings when the docs include private items, like internal docs for a team.
+    pub show_private: bool,
     pub comment: Option<String>,
+    pub directives: Directives,
     pub kind: BindingType,
+    /// Primitives used in the code, so bindings can be found by the glyphs they use.
+    pub primitives: Vec<Primitive>,
+    pub location: SourceLocation,
+    pub benchmark: Option<Benchmark>,
+    pub metrics: CodeMetrics,
+    /// Set when the binding failed to compile and is documented from its source alone.
+    pub unverified: Option<Unverified>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unverified {
+    /// The compiler's error, if it was raised in the binding itself rather than in something it uses.
+    pub error: Option<String>,
+}
+
+/// Where an item is defined, relative to the library's directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl SourceLocation {
+    fn of_span(file: &str, span: &CodeSpan) -> Self {
+        Self {
+            file: file.to_owned(),
+            start_line: span.start.line as usize,
+            end_line: span.end.line as usize,
+        }
+    }
+}
+
+/// A name qualified by the modules it's in, like `Outer~Inner~Name`.
+pub fn qualified_name(module_path: &[String], name: &str) -> String {
+    module_path
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(name))
+        .collect::<Vec<_>>()
+        .join("~")
+}
+
+/// Drops the code of the bindings marked with `!doc hide-code`, or of all bindings, for reference-only docs.
+///
+/// The lines of a hidden binding are blanked in the file's source as well, so its body doesn't show
+/// up on the source pages. With `all`, the sources are dropped entirely.
+pub fn hide_code(files: &mut [FileContent], all: bool) {
+    for file in files {
+        let mut hidden = Vec::new();
+        hide_binding_code(&mut file.items, all, &mut hidden);
+        if all {
+            file.source.clear();
+            continue;
+        }
+        blank_lines(&mut file.source, &hidden);
+    }
+}
+
+/// Drops the timings of `@bench` expressions, which differ on every run, for builds that have to be reproducible.
+pub fn drop_benchmarks(files: &mut [FileContent]) {
+    for file in files {
+        drop_binding_benchmarks(&mut file.items);
+    }
+}
+
+fn drop_binding_benchmarks(items: &mut [ItemContent]) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) => binding.benchmark = None,
+            ItemContent::Module(module) => drop_binding_benchmarks(&mut module.items),
+            _ => {}
+        }
+    }
+}
+
+/// Removes the modules named in `excluded`, like `Internal` or `Geo~Experimental`, from the docs, and blanks their
+/// lines on the source pages. Returns the names that don't match a module.
+pub fn exclude_modules(files: &mut [FileContent], excluded: &[String]) -> Vec<String> {
+    let excluded = excluded.iter().map(|name| name.replace('.', "~")).collect::<Vec<_>>();
+    let mut removed = Vec::new();
+    for file in files {
+        let mut locations = Vec::new();
+        remove_modules(
+            &mut file.items,
+            &|name| excluded.iter().any(|excluded| excluded == name),
+            &mut removed,
+            &mut locations,
+        );
+        blank_lines(&mut file.source, &locations);
+    }
+    excluded.into_iter().filter(|name| !removed.contains(name)).collect()
+}
+
+/// Removes the modules whose qualified names `is_excluded` accepts, collecting their names and locations.
+fn remove_modules(items: &mut Vec<ItemContent>, is_excluded: &dyn Fn(&str) -> bool, removed: &mut Vec<String>, locations: &mut Vec<SourceLocation>) {
+    items.retain(|item| {
+        let ItemContent::Module(module) = item else {
+            return true;
+        };
+        let name = qualified_name(&module.module_path, &module.name);
+        if !is_excluded(&name) {
+            return true;
+        }
+        removed.push(name);
+        locations.push(module.location.clone());
+        false
+    });
+
+    for item in items {
+        if let ItemContent::Module(module) = item {
+            // The modules a module depends on are next to it, so they have the same path
+            module
+                .dependencies
+                .retain(|dependency| !is_excluded(&qualified_name(&module.module_path, dependency)));
+            remove_modules(&mut module.items, is_excluded, removed, locations);
+        }
+    }
+}
+
+/// The `--exclude` patterns of the library, which are written like the lines of a `.gitignore`.
+fn exclude_patterns(root: &Path, patterns: &[String]) -> Result<Override, ExtractError> {
+    let mut builder = OverrideBuilder::new(root);
+    for pattern in patterns {
+        builder.add(pattern)?;
+    }
+    Ok(builder.build()?)
+}
+
+/// Whether an `--exclude` pattern matches the path of a file, relative to the library's directory, or the
+/// qualified name of a module, written with dots like `Geo.Internal`.
+fn is_excluded(patterns: &Override, path: &str) -> bool {
+    patterns.matched(path, false).is_whitelist()
+}
+
+/// Blanks the lines of the given locations in a file's source, keeping the line numbers of the rest.
+fn blank_lines(source: &mut String, locations: &[SourceLocation]) {
+    if locations.is_empty() {
+        return;
+    }
+
+    let mut lines = source.lines().map(str::to_owned).collect::<Vec<_>>();
+    for location in locations {
+        for line in lines.iter_mut().take(location.end_line).skip(location.start_line.saturating_sub(1)) {
+            line.clear();
+        }
+    }
+    *source = lines.join("\n");
+}
+
+fn hide_binding_code(items: &mut [ItemContent], all: bool, hidden: &mut Vec<SourceLocation>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if all || binding.directives.has("hide-code") => {
+                binding.code.clear();
+                hidden.push(binding.location.clone());
+            }
+            ItemContent::Module(module) => hide_binding_code(&mut module.items, all, hidden),
+            _ => {}
+        }
+    }
+}
+
+/// Documents the private bindings along with the public ones, which are then marked as private. Bindings hidden with
+/// `!doc hide` stay hidden.
+pub fn include_private(files: &mut [FileContent]) {
+    for file in files {
+        show_private_bindings(&mut file.items);
+    }
+}
+
+fn show_private_bindings(items: &mut [ItemContent]) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) => binding.show_private = !binding.public,
+            ItemContent::Module(module) => show_private_bindings(&mut module.items),
+            _ => {}
+        }
+    }
+}
+
+impl BindingDefinition {
+    /// Whether the binding shows up in the generated documentation.
+    pub fn is_documented(&self) -> bool {
+        (self.public || self.show_private) && !self.directives.has("hide")
+    }
+
+    /// The position given with `!doc order: 10`. Bindings with one come first in their section, lowest first.
+    pub fn order(&self) -> Option<i64> {
+        self.directives.get("order").and_then(|order| order.parse().ok())
+    }
 }
 
 impl Documented for BindingDefinition {
@@ -74,11 +297,18 @@ impl Documented for BindingDefinition {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleDefinition {
     pub name: String,
+    /// The modules this module is nested in, outermost first.
+    pub module_path: Vec<String>,
     pub comment: Option<String>,
+    /// Long-form documentation from a `ModuleName.md` file next to the library.
+    pub readme: Option<String>,
     pub items: Vec<ItemContent>,
+    /// The modules next to this one that its bindings refer to.
+    pub dependencies: Vec<String>,
+    pub location: SourceLocation,
 }
 
 impl Documented for ModuleDefinition {
@@ -90,7 +320,7 @@ impl Documented for ModuleDefinition {
 impl ModuleDefinition {
     pub fn has_public_items(&self) -> bool {
         self.items.iter().any(|item| match item {
-            ItemContent::Binding(binding) => binding.public,
+            ItemContent::Binding(binding) => binding.is_documented(),
             ItemContent::Module(module) => module.has_public_items(),
             ItemContent::Data(_) => true,
             ItemContent::Variant(_) => true,
@@ -99,11 +329,13 @@ impl ModuleDefinition {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataDefinition {
     pub name: Option<String>,
+    pub module_path: Vec<String>,
     pub comment: Option<String>,
     pub definition: Option<Definition>,
+    pub generated_functions: Vec<GeneratedFunction>,
 }
 
 impl Documented for DataDefinition {
@@ -112,11 +344,16 @@ impl Documented for DataDefinition {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VariantDefinition {
     pub name: String,
+    pub module_path: Vec<String>,
+    pub index: usize,
     pub comment: Option<String>,
     pub definition: Option<Definition>,
+    pub generated_functions: Vec<GeneratedFunction>,
+    pub produced_by: Vec<ItemReference>,
+    pub consumed_by: Vec<ItemReference>,
 }
 
 impl Documented for VariantDefinition {
@@ -125,16 +362,47 @@ impl Documented for VariantDefinition {
     }
 }
 
-#[derive(Debug, Clone)]
-#[allow(unused)]
+/// Points to another documented item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemReference {
+    /// The modules the item is nested in, outermost first.
+    pub module_path: Vec<String>,
+    pub name: String,
+}
+
+/// A function the compiler implicitly defines for a data definition, like its `New` constructor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedFunction {
+    pub name: String,
+    pub comment: Option<String>,
+    pub function: FunctionDefinition,
+}
+
+impl Documented for GeneratedFunction {
+    fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportDefinition {
-    path: String,
+    pub path: String,
+    /// The name the imported module is bound to, like `Geo` in `Geo ~ "geo.ua"`.
+    pub name: Option<String>,
+    /// The items brought into scope, like `Area` in `~ "geo.ua" ~ Area`.
+    pub items: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(unused)]
 pub enum ItemContent {
-    Words { code: String },
+    Words {
+        code: String,
+    },
+    /// A test scope, which isn't documented but shows which bindings are tested.
+    Test {
+        code: String,
+    },
 
     Binding(BindingDefinition),
     Module(ModuleDefinition),
@@ -143,18 +411,22 @@ pub enum ItemContent {
     Import(ImportDefinition),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConstantDefinition {
     pub value: Option<String>,
+    /// Set when the library was compiled but the value is only known once it's loaded, like a value
+    /// computed by a function or read from a file.
+    pub computed_at_load: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionDefinition {
-    pub signature: SignatureInfo,
+    /// Unknown for functions without a declared signature when the library isn't compiled.
+    pub signature: Option<SignatureInfo>,
     pub named_signature: Option<NamedSignature>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexMacroDefinition {
     pub arguments: usize,
     pub named_signature: Option<NamedSignature>,
@@ -170,12 +442,13 @@ impl Colored for IndexMacroDefinition {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeMacroDefinition {
+    pub arguments: usize,
     pub named_signature: Option<NamedSignature>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BindingType {
     Const(ConstantDefinition),
     Function(FunctionDefinition),
@@ -183,11 +456,25 @@ pub enum BindingType {
     CodeMacro(CodeMacroDefinition),
 }
 
-#[derive(Debug)]
+impl BindingType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BindingType::Const(_) => "constant",
+            BindingType::Function(_) => "function",
+            BindingType::IndexMacro(_) => "index macro",
+            BindingType::CodeMacro(_) => "code macro",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(unused)]
 pub struct FileContent {
     pub main: bool,
     pub file: String,
+    /// Path of the file relative to the library's directory, with `/` as the separator.
+    pub relative_path: String,
+    pub source: String,
     pub items: Vec<ItemContent>,
 }
 
@@ -197,6 +484,12 @@ pub trait Colored {
 
 pub trait Documented {
     fn comment(&self) -> Option<&str>;
+
+    /// The first non-empty line of the comment, used where only a one-line description fits.
+    fn summary(&self) -> Option<&str> {
+        self.comment()
+            .and_then(|comment| comment.lines().map(str::trim).find(|line| !line.is_empty()))
+    }
 }
 
 impl From<DocCommentSig> for NamedSignature {
@@ -205,7 +498,7 @@ impl From<DocCommentSig> for NamedSignature {
             .args
             .map(|inputs| inputs.iter().map(|output| output.name.to_string()).collect())
             .unwrap_or_default();
-        
+
         let outputs = doc
             .outputs
             .map(|outputs| outputs.iter().map(|output| output.name.to_string()).collect())
@@ -224,27 +517,146 @@ impl From<Signature> for SignatureInfo {
     }
 }
 
+/// What is known about the library while its items are extracted: the parsed source, and what
+/// the compiler inferred about its bindings, unless the library is documented from the source alone.
+struct Extraction<'a> {
+    inputs: &'a Inputs,
+    assembly: Option<&'a Assembly>,
+    /// The errors of a library that failed to compile. Its assembly only has the bindings that did compile.
+    compile_errors: &'a [CompileError],
+}
+
+impl Extraction<'_> {
+    fn code(&self, span: &CodeSpan) -> String {
+        span.as_str(self.inputs, |code| code.to_owned())
+    }
+
+    fn compile_failed(&self) -> bool {
+        !self.compile_errors.is_empty()
+    }
+
+    /// The first compile error raised within the span.
+    fn compile_error_in(&self, span: &CodeSpan) -> Option<String> {
+        self.compile_errors
+            .iter()
+            .find(|error| {
+                error.span.as_ref().is_some_and(|error_span| {
+                    error_span.src == span.src
+                        && span.byte_range().start <= error_span.byte_range().start
+                        && error_span.byte_range().end <= span.byte_range().end
+                })
+            })
+            .map(|error| error.message.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompileError {
+    span: Option<CodeSpan>,
+    message: String,
+}
+
+fn compile_errors(error: uiua::UiuaError) -> Vec<CompileError> {
+    error
+        .into_multi()
+        .into_iter()
+        .map(|error| match &error.kind {
+            UiuaErrorKind::Run { message, .. } => CompileError {
+                span: match &message.span {
+                    Span::Code(span) => Some(span.clone()),
+                    Span::Builtin => None,
+                },
+                message: message.value.clone(),
+            },
+            _ => CompileError {
+                span: None,
+                message: error.to_string(),
+            },
+        })
+        .collect()
+}
+
 // Rest of the helper functions remain the same
 fn get_binding_info(asm: &Assembly, span: &CodeSpan) -> Option<BindingInfo> {
     asm.bindings.iter().find(|binding| binding.span == *span).cloned()
 }
 
-fn get_words_as_code_2(words: &[Vec<Sp<Word>>], asm: &Assembly) -> String {
-    if words.first().unwrap().is_empty() {
-        return "".to_string();
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WordsLine {
+    Blank,
+    /// A comment line starting a `!doc` block, like `# !doc`.
+    DocComment,
+    Comment,
+    Code,
+}
+
+impl WordsLine {
+    fn of(line: &[Sp<Word>]) -> Self {
+        let mut words = line.iter().filter(|word| !matches!(word.value, Word::Spaces));
+        match (words.next(), words.next()) {
+            (None, _) => WordsLine::Blank,
+            (Some(word), None) => match &word.value {
+                Word::Comment(comment) if comment.starts_with(" !doc") => WordsLine::DocComment,
+                Word::Comment(_) => WordsLine::Comment,
+                _ => WordsLine::Code,
+            },
+            _ => WordsLine::Code,
+        }
     }
+}
 
-    if words.last().unwrap().is_empty() {
-        return "".to_string();
+/// Splits top-level words into chunks by their parsed lines. Blank lines end a chunk, and `!doc` blocks are kept
+/// apart from the code and comments around them.
+///
+/// With `attached`, the comment lines right before the next item are its doc comment, so they're left out.
+fn chunk_words(lines: &[Vec<Sp<Word>>], attached: bool, extraction: &Extraction) -> Vec<String> {
+    let mut lines = lines.iter().map(|line| (WordsLine::of(line), line)).collect::<Vec<_>>();
+    if attached {
+        while lines
+            .last()
+            .is_some_and(|(kind, _)| matches!(kind, WordsLine::DocComment | WordsLine::Comment))
+        {
+            lines.pop();
+        }
     }
 
-    let from = &words.first().unwrap().first().unwrap().span;
-    let to = &words.last().unwrap().last().unwrap().span;
-    let span = from.clone().merge(to.clone());
-    span.as_str(&asm.inputs, |code| code.to_owned())
+    let mut chunks = Vec::new();
+    let mut chunk: Vec<&Vec<Sp<Word>>> = Vec::new();
+    let mut in_doc_block = false;
+    for (kind, line) in lines {
+        let starts_chunk = match kind {
+            WordsLine::Blank | WordsLine::DocComment => true,
+            WordsLine::Comment => false,
+            WordsLine::Code => in_doc_block,
+        };
+        if starts_chunk && !chunk.is_empty() {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        match kind {
+            WordsLine::Blank => in_doc_block = false,
+            WordsLine::DocComment => in_doc_block = true,
+            WordsLine::Code => in_doc_block = false,
+            WordsLine::Comment => {}
+        }
+        if kind != WordsLine::Blank {
+            chunk.push(line);
+        }
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            let from = &chunk.first().unwrap().first().unwrap().span;
+            let to = &chunk.last().unwrap().last().unwrap().span;
+            extraction.code(&from.clone().merge(to.clone())).replace("\r\n", "\n")
+        })
+        .collect()
 }
 
-fn get_words_as_code(words: &[Sp<Word>], asm: &Assembly) -> String {
+fn get_words_as_code(words: &[Sp<Word>], extraction: &Extraction) -> String {
     if words.is_empty() {
         return "".to_string();
     }
@@ -252,35 +664,115 @@ fn get_words_as_code(words: &[Sp<Word>], asm: &Assembly) -> String {
     let from = &words.first().unwrap().span;
     let to = &words.last().unwrap().span;
     let span = from.clone().merge(to.clone());
-    span.as_str(&asm.inputs, |code| code.to_owned())
+    extraction.code(&span)
+}
+
+/// Finds the constructor and field getters the compiler generates for a data definition.
+fn get_generated_functions(data_def: &DataDef, asm: &Assembly) -> Vec<GeneratedFunction> {
+    let constructor = std::iter::once(("New".to_owned(), &data_def.init_span));
+    let getters = data_def
+        .fields
+        .iter()
+        .flat_map(|fields| &fields.fields)
+        .map(|field| (field.name.value.to_string(), &field.name.span));
+
+    constructor
+        .chain(getters)
+        .filter_map(|(name, span)| {
+            let info = asm
+                .bindings
+                .iter()
+                .find(|binding| binding.span == *span && matches!(binding.kind, BindingKind::Func(_)))?;
+            let BindingKind::Func(function) = &info.kind else {
+                return None;
+            };
+            let comment = info.meta.comment.as_ref();
+
+            Some(GeneratedFunction {
+                name,
+                // Signature lines the compiler couldn't parse (e.g. constructors without fields) are left in the text
+                comment: comment.map(|comment| {
+                    comment
+                        .text
+                        .lines()
+                        .filter(|line| !line.split_whitespace().any(|word| word == "?"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }),
+                function: FunctionDefinition {
+                    signature: Some(function.sig.into()),
+                    named_signature: comment.and_then(|comment| comment.sig.clone()).map(Into::into),
+                },
+            })
+        })
+        .collect()
 }
 
-fn handle_ast_items(items: Vec<Item>, asm: &Assembly) -> Vec<ItemContent> {
+fn handle_ast_items(items: Vec<Item>, extraction: &Extraction, file: &str, module_path: &[String]) -> Vec<ItemContent> {
     let mut results = Vec::new();
+    // Variants are tagged with their position among the variants of the enclosing scope
+    let mut variant_count = 0;
+    // The compiler drops comments of alias bindings, so they are picked up from the preceding words
+    let mut preceding_comment = None;
 
-    for item in items {
+    let mut items = items.into_iter().peekable();
+    while let Some(item) = items.next() {
+        let comment_before = preceding_comment.take();
         match item {
             Item::Words(words) => {
-                let code_str = get_words_as_code_2(&words, asm).replace("\r\n", "\n");
-                for chunk in code_str.split("\n\n") {
-                    results.push(ItemContent::Words { code: chunk.to_string() });
+                preceding_comment = trailing_comment(&words);
+                let attached = items.peek().is_some_and(|item| !matches!(item, Item::Words(_)));
+                for code in chunk_words(&words, attached, extraction) {
+                    results.push(ItemContent::Words { code });
                 }
             }
             Item::Binding(binding) => {
+                let Some(asm) = extraction.assembly else {
+                    let binding = uncompiled_binding(&binding, comment_before, extraction, file, module_path);
+                    results.push(ItemContent::Binding(binding));
+                    continue;
+                };
                 let info = match get_binding_info(asm, &binding.name.span) {
                     Some(info) => info,
-                    None => continue,
+                    None => {
+                        if let Some(alias) = get_alias(&binding, comment_before.clone(), &results, extraction, file, module_path) {
+                            results.push(ItemContent::Binding(alias));
+                        } else if extraction.compile_failed() {
+                            results.push(ItemContent::Binding(unverified_binding(
+                                &binding,
+                                comment_before,
+                                extraction,
+                                file,
+                                module_path,
+                            )));
+                        }
+                        continue;
+                    }
+                };
+                if let BindingKind::Error = info.kind {
+                    results.push(ItemContent::Binding(unverified_binding(
+                        &binding,
+                        comment_before,
+                        extraction,
+                        file,
+                        module_path,
+                    )));
+                    continue;
+                }
+                let code = extraction.code(&binding.span());
+                let (comment, directives) = match &info.meta.comment {
+                    Some(comment) => extract_directives(&comment.text),
+                    None => (None, Directives::default()),
                 };
-                let code = binding.span().as_str(&asm.inputs, |code| code.to_owned());
-                let comment = info.meta.comment.clone().map(|comment| comment.text.to_string());
                 let signature = info.meta.comment.and_then(|comment| comment.sig);
 
                 let kind = match info.kind {
                     BindingKind::Const(value) => BindingType::Const(ConstantDefinition {
+                        computed_at_load: value.is_none(),
                         value: value.map(|v| v.show()),
                     }),
                     BindingKind::Func(function) => BindingType::Function(FunctionDefinition {
-                        signature: function.sig.into(),
+                        signature: Some(function.sig.into()),
                         named_signature: signature.map(Into::into),
                     }),
                     BindingKind::IndexMacro(code_macro_args) => BindingType::IndexMacro(IndexMacroDefinition {
@@ -288,6 +780,7 @@ fn handle_ast_items(items: Vec<Item>, asm: &Assembly) -> Vec<ItemContent> {
                         named_signature: signature.map(Into::into),
                     }),
                     BindingKind::CodeMacro(_) => BindingType::CodeMacro(CodeMacroDefinition {
+                        arguments: ident_modifier_args(&binding.name.value),
                         named_signature: signature.map(Into::into),
                     }),
                     _ => continue,
@@ -295,63 +788,109 @@ fn handle_ast_items(items: Vec<Item>, asm: &Assembly) -> Vec<ItemContent> {
 
                 results.push(ItemContent::Binding(BindingDefinition {
                     name: binding.name.value.to_string(),
+                    module_path: module_path.to_vec(),
+                    primitives: used_primitives(&code),
+                    location: SourceLocation::of_span(file, &binding.span()),
+                    benchmark: None,
+                    metrics: code_metrics(&binding.words),
+                    unverified: None,
                     code,
                     public: info.public,
+                    show_private: false,
                     comment,
+                    directives,
                     kind,
                 }));
             }
             Item::Module(module) => {
                 if let ModuleKind::Test = module.value.kind {
+                    results.push(ItemContent::Test {
+                        code: extraction.code(&module.span),
+                    });
                     continue;
                 } else if let ModuleKind::Named(name) = module.value.kind {
-                    let info = match get_binding_info(asm, &name.span) {
-                        Some(info) => info,
-                        None => continue,
+                    let comment = match extraction.assembly {
+                        Some(asm) => match get_binding_info(asm, &name.span) {
+                            Some(info) => info.meta.comment.map(|comment| comment.text.to_string()),
+                            None if extraction.compile_failed() => {
+                                comment_above(name.span.start.line, comment_before).map(|comment| comment.text.to_string())
+                            }
+                            None => continue,
+                        },
+                        None => comment_above(name.span.start.line, comment_before).map(|comment| comment.text.to_string()),
                     };
 
-                    let comment = info.meta.comment.map(|comment| comment.text.to_string());
-                    let processed_items = handle_ast_items(module.value.items, asm);
+                    let inner_path = module_path
+                        .iter()
+                        .cloned()
+                        .chain(std::iter::once(name.value.to_string()))
+                        .collect::<Vec<_>>();
+                    let processed_items = handle_ast_items(module.value.items, extraction, file, &inner_path);
 
                     results.push(ItemContent::Module(ModuleDefinition {
                         name: name.value.to_string(),
+                        module_path: module_path.to_vec(),
                         comment,
+                        readme: None,
                         items: processed_items,
+                        dependencies: Vec::new(),
+                        location: SourceLocation::of_span(file, &module.span),
                     }));
                 }
             }
             Item::Data(data_def) => {
+                let generated_functions = match extraction.assembly {
+                    Some(asm) => get_generated_functions(&data_def, asm),
+                    None => Vec::new(),
+                };
                 let definition = data_def.fields.map(|def| Definition {
                     boxed: def.boxed,
                     fields: def
                         .fields
                         .iter()
-                        .map(|field| Field {
-                            name: field.name.value.to_string(),
-                            validator: field.validator.as_ref().map(|v| get_words_as_code(&v.words, asm)),
+                        .map(|field| {
+                            let validator = field.validator.as_ref().map(|v| get_words_as_code(&v.words, extraction));
+                            Field {
+                                name: field.name.value.to_string(),
+                                validator_explanation: validator.as_deref().and_then(explain_validator),
+                                validator,
+                            }
                         })
                         .collect(),
                 });
 
-                let info = match &data_def.name {
-                    Some(name) => match get_binding_info(asm, &name.span) {
-                        Some(info) => Some(info),
+                let comment = match (&data_def.name, extraction.assembly) {
+                    (Some(name), Some(asm)) if !extraction.compile_failed() => match get_binding_info(asm, &name.span) {
+                        Some(info) => info.meta.comment.map(|comment| comment.text.to_string()),
                         None => panic!("Data definition without binding info"),
                     },
-                    None => None,
+                    (Some(name), Some(asm)) => match get_binding_info(asm, &name.span) {
+                        Some(info) => info.meta.comment.map(|comment| comment.text.to_string()),
+                        None => comment_above(name.span.start.line, comment_before).map(|comment| comment.text.to_string()),
+                    },
+                    (Some(name), None) => comment_above(name.span.start.line, comment_before).map(|comment| comment.text.to_string()),
+                    (None, _) => None,
                 };
 
                 let item_content = if data_def.variant {
+                    variant_count += 1;
                     ItemContent::Variant(VariantDefinition {
+                        index: variant_count - 1,
                         name: data_def.name.map(|name| name.value.to_string()).unwrap(),
-                        comment: info.and_then(|info| info.meta.comment.map(|comment| comment.text.to_string())),
+                        module_path: module_path.to_vec(),
+                        comment,
                         definition,
+                        generated_functions,
+                        produced_by: Vec::new(),
+                        consumed_by: Vec::new(),
                     })
                 } else {
                     ItemContent::Data(DataDefinition {
                         name: data_def.name.map(|name| name.value.to_string()),
-                        comment: info.and_then(|info| info.meta.comment.map(|comment| comment.text.to_string())),
+                        module_path: module_path.to_vec(),
+                        comment,
                         definition,
+                        generated_functions,
                     })
                 };
 
@@ -360,14 +899,253 @@ fn handle_ast_items(items: Vec<Item>, asm: &Assembly) -> Vec<ItemContent> {
             Item::Import(import) => {
                 results.push(ItemContent::Import(ImportDefinition {
                     path: import.path.value.to_string(),
+                    name: import.name.as_ref().map(|name| name.value.to_string()),
+                    items: import.items().map(|item| item.value.to_string()).collect(),
                 }));
             }
         }
     }
 
+    merge_wrappers(&mut results);
     results
 }
 
+/// The comment lines at the end of a block of words, along with the line the block ends on.
+fn trailing_comment(lines: &[Vec<Sp<Word>>]) -> Option<(String, u16)> {
+    let mut comment_lines = Vec::new();
+    let mut last_line = None;
+
+    for line in lines.iter().rev() {
+        let mut words = line.iter().filter(|word| !matches!(word.value, Word::Spaces));
+        match (words.next(), words.next()) {
+            (Some(word), None) => match &word.value {
+                Word::Comment(comment) => {
+                    last_line.get_or_insert(word.span.start.line);
+                    comment_lines.push(comment.as_str());
+                }
+                _ => break,
+            },
+            _ => break,
+        }
+    }
+
+    comment_lines.reverse();
+    last_line.map(|line| (comment_lines.join("\n"), line))
+}
+
+/// Bindings that just rename another binding (`Alias ← Name`) have no binding info of their own,
+/// so they are documented as the binding they refer to.
+fn get_alias(
+    binding: &Binding,
+    comment: Option<(String, u16)>,
+    scope: &[ItemContent],
+    extraction: &Extraction,
+    file: &str,
+    module_path: &[String],
+) -> Option<BindingDefinition> {
+    let mut words = binding.words.iter().filter(|word| word.value.is_code());
+    let target = match (words.next(), words.next()) {
+        (Some(word), None) => match &word.value {
+            Word::Ref(reference) if reference.path.is_empty() => reference.name.value.to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let target = scope.iter().rev().find_map(|item| match item {
+        ItemContent::Binding(definition) if definition.name == target => Some(definition),
+        _ => None,
+    })?;
+
+    let comment = comment_above(binding.name.span.start.line, comment);
+    let (text, directives) = match &comment {
+        Some(comment) => extract_directives(&comment.text),
+        None => (None, Directives::default()),
+    };
+    let signature = comment.and_then(|comment| comment.sig).map(Into::into);
+
+    let mut kind = target.kind.clone();
+    match &mut kind {
+        BindingType::Function(FunctionDefinition { named_signature, .. })
+        | BindingType::IndexMacro(IndexMacroDefinition { named_signature, .. })
+        | BindingType::CodeMacro(CodeMacroDefinition { named_signature, .. }) => *named_signature = signature,
+        BindingType::Const(_) => {}
+    }
+
+    let code = extraction.code(&binding.span());
+    Some(BindingDefinition {
+        name: binding.name.value.to_string(),
+        module_path: module_path.to_vec(),
+        primitives: used_primitives(&code),
+        location: SourceLocation::of_span(file, &binding.span()),
+        benchmark: None,
+        metrics: code_metrics(&binding.words),
+        unverified: None,
+        code,
+        public: binding.public,
+        show_private: false,
+        comment: text,
+        directives,
+        kind,
+    })
+}
+
+/// The doc comment of an item on the given line, which is the comment directly above it, if any.
+fn comment_above(line: u16, comment: Option<(String, u16)>) -> Option<DocComment> {
+    comment
+        .filter(|(_, comment_line)| comment_line + 1 == line)
+        .map(|(text, _)| DocComment::from(text.as_str()))
+}
+
+/// Documents a binding from its source alone, for libraries that aren't compiled.
+/// Only signatures declared in the code are known, and constants have no value.
+fn uncompiled_binding(
+    binding: &Binding,
+    comment: Option<(String, u16)>,
+    extraction: &Extraction,
+    file: &str,
+    module_path: &[String],
+) -> BindingDefinition {
+    let comment = comment_above(binding.name.span.start.line, comment);
+    let (text, directives) = match &comment {
+        Some(comment) => extract_directives(&comment.text),
+        None => (None, Directives::default()),
+    };
+    let named_signature = comment.and_then(|comment| comment.sig).map(Into::into);
+
+    let arguments = ident_modifier_args(&binding.name.value);
+    let kind = if binding.code_macro {
+        BindingType::CodeMacro(CodeMacroDefinition { arguments, named_signature })
+    } else if arguments > 0 {
+        BindingType::IndexMacro(IndexMacroDefinition { arguments, named_signature })
+    } else if binding.signature.is_none() && binding.words.iter().all(|word| is_literal(&word.value)) {
+        BindingType::Const(ConstantDefinition {
+            value: None,
+            computed_at_load: false,
+        })
+    } else {
+        BindingType::Function(FunctionDefinition {
+            signature: binding.signature.as_ref().map(|signature| signature.value.into()),
+            named_signature,
+        })
+    };
+
+    let code = extraction.code(&binding.span());
+    BindingDefinition {
+        name: binding.name.value.to_string(),
+        module_path: module_path.to_vec(),
+        primitives: used_primitives(&code),
+        location: SourceLocation::of_span(file, &binding.span()),
+        benchmark: None,
+        metrics: code_metrics(&binding.words),
+        unverified: None,
+        code,
+        public: binding.public,
+        show_private: false,
+        comment: text,
+        directives,
+        kind,
+    }
+}
+
+/// Documents a binding that failed to compile from its source, along with the error it caused.
+fn unverified_binding(
+    binding: &Binding,
+    comment: Option<(String, u16)>,
+    extraction: &Extraction,
+    file: &str,
+    module_path: &[String],
+) -> BindingDefinition {
+    BindingDefinition {
+        unverified: Some(Unverified {
+            error: extraction.compile_error_in(&binding.span()),
+        }),
+        ..uncompiled_binding(binding, comment, extraction, file, module_path)
+    }
+}
+
+/// Whether a word is part of a literal value, so a binding of nothing but such words is a constant.
+fn is_literal(word: &Word) -> bool {
+    match word {
+        Word::Number(_) | Word::Char(_) | Word::String(_) | Word::MultilineString(_) | Word::Spaces | Word::Comment(_) => true,
+        Word::Strand(items) => items.iter().all(|item| is_literal(&item.value)),
+        Word::Array(array) => array.lines.iter().flatten().all(|item| is_literal(&item.value)),
+        _ => false,
+    }
+}
+
+/// For bindings marked with `!doc merge` that only call another binding of the same scope,
+/// shows the implementation's code (and documentation, if missing) under the wrapper's name.
+fn merge_wrappers(items: &mut [ItemContent]) {
+    let implementations: HashMap<String, BindingDefinition> = items
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Binding(binding) => Some((binding.name.clone(), binding.clone())),
+            _ => None,
+        })
+        .collect();
+
+    for item in items.iter_mut() {
+        let ItemContent::Binding(wrapper) = item else {
+            continue;
+        };
+        if !wrapper.directives.has("merge") {
+            continue;
+        }
+
+        let Some(implementation) = wrapped_binding(wrapper).and_then(|name| implementations.get(&name)) else {
+            eprintln!(
+                "Warning: `{}` is marked with `!doc merge`, but doesn't just call another binding",
+                wrapper.name
+            );
+            continue;
+        };
+
+        // Keep the wrapper's own name and arrow, so its publicity stays visible
+        let split_at_arrow = |code: &str| code.find(['←', '↚']).map(|index| index + '←'.len_utf8());
+        if let (Some(head), Some(body)) = (split_at_arrow(&wrapper.code), split_at_arrow(&implementation.code)) {
+            wrapper.code = format!("{}{}", &wrapper.code[..head], &implementation.code[body..]);
+            wrapper.primitives = implementation.primitives.clone();
+            wrapper.metrics = implementation.metrics.clone();
+        }
+        if wrapper.comment.is_none() {
+            wrapper.comment = implementation.comment.clone();
+        }
+        if let (BindingType::Function(wrapper), BindingType::Function(implementation)) = (&mut wrapper.kind, &implementation.kind) {
+            if wrapper.named_signature.is_none() {
+                wrapper.named_signature = implementation.named_signature.clone();
+            }
+        }
+    }
+}
+
+/// The name of the binding a wrapper calls, if its body is nothing but a reference to it.
+fn wrapped_binding(wrapper: &BindingDefinition) -> Option<String> {
+    let paths = tokenize_code(&wrapper.code)
+        .into_iter()
+        .filter(|token| !matches!(token, CodeToken::Other))
+        .collect::<Vec<_>>();
+
+    match paths.as_slice() {
+        [CodeToken::Path(name), CodeToken::Path(target)] if name.len() == 1 && name[0] == wrapper.name && target.len() == 1 => {
+            Some(target[0].clone())
+        }
+        _ => None,
+    }
+}
+
+/// Reads long-form module documentation from `ModuleName.md` or `docs/ModuleName.md` in the library's directory.
+fn attach_module_readmes(items: &mut [ItemContent], root: &Path) {
+    for item in items {
+        if let ItemContent::Module(module) = item {
+            let file_name = format!("{}.md", module.name);
+            module.readme = [root.join(&file_name), root.join("docs").join(&file_name)]
+                .iter()
+                .find_map(|path| std::fs::read_to_string(path).ok());
+            attach_module_readmes(&mut module.items, root);
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ExtractError {
     #[error("Library file not found: {0}")]
@@ -379,24 +1157,119 @@ pub enum ExtractError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error("Invalid extracted items: {0}")]
+    JsonError(#[from] serde_json::Error),
+
     #[error("Uiua Error: {0}")]
-    UiuaError(#[from] uiua::UiuaError),
+    UiuaError(Box<uiua::UiuaError>),
+
+    #[error("Invalid exclude pattern: {0}")]
+    ExcludePattern(#[from] ignore::Error),
+}
+
+impl From<uiua::UiuaError> for ExtractError {
+    fn from(error: uiua::UiuaError) -> Self {
+        ExtractError::UiuaError(Box::new(error))
+    }
+}
+
+/// How the items of a library are extracted.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Documents the library from its source alone, without compiling it. Libraries that don't
+    /// compile can still be documented this way, but signatures are only known where declared.
+    pub parse_only: bool,
+    /// The system backend the library and its examples are compiled and run with, which decides
+    /// what their code can do on the host, like reading files. Defaults to full native access.
+    pub sys_backend: Option<Arc<dyn SysBackend>>,
+    /// Reuses the items extracted by the previous run while the library's sources are unchanged,
+    /// skipping compilation and benchmarks. Libraries that fail to compile aren't cached.
+    pub cache: bool,
+    /// The library's entry file, relative to its directory, for libraries whose main file isn't `lib.ua`.
+    pub library_file: Option<PathBuf>,
+    /// Patterns of the files and modules left out of the docs, like `internal/*.ua` or `Geo.Internal`.
+    pub exclude: Vec<String>,
+}
+
+impl ExtractOptions {
+    pub fn backend(&self) -> Arc<dyn SysBackend> {
+        self.sys_backend.clone().unwrap_or_else(|| Arc::new(NativeSys))
+    }
+
+    pub fn library_file(&self) -> &Path {
+        self.library_file.as_deref().unwrap_or(Path::new(LIBRARY_FILE))
+    }
+
+    /// The options that change the extracted items, so a cache made with other options isn't used.
+    fn cache_settings(&self) -> String {
+        format!(
+            "parse_only={} sandboxed={} library_file={} exclude={}",
+            self.parse_only,
+            self.sys_backend.is_some(),
+            self.library_file().display(),
+            self.exclude.join(" ")
+        )
+    }
+}
+
+/// The entry file of a library, unless it's given another one.
+pub const LIBRARY_FILE: &str = "lib.ua";
+
+pub fn extract_uiua_definitions(path: &Path, options: &ExtractOptions) -> Result<Vec<FileContent>, ExtractError> {
+    extract_entry_definitions(path, options.library_file(), options)
 }
 
-pub fn extract_uiua_definitions(path: &Path) -> Result<Vec<FileContent>, ExtractError> {
-    let lib_path = path.join("lib.ua");
+/// Reads the items printed by the `extract` command, to render them without extracting the library again.
+pub fn read_extracted_items(path: &Path) -> Result<Vec<FileContent>, ExtractError> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Extracts the files reachable from an entry point of the library, like the `main.ua` of a program next
+/// to its `lib.ua`, compiled on its own. The entry file is the main file, and only the library file is cached.
+pub fn extract_entry_definitions(path: &Path, entry: &Path, options: &ExtractOptions) -> Result<Vec<FileContent>, ExtractError> {
+    let lib_path = path.join(entry);
     if !lib_path.exists() || !lib_path.is_file() {
         return Err(ExtractError::LibraryNotFound(lib_path));
     }
+    let cache = options.cache && entry == options.library_file();
 
-    let backend = NativeSys;
-    let _ = backend.change_directory(path.to_str().unwrap());
+    env::set_current_dir(path)?;
 
-    let mut comp = Compiler::with_backend(backend);
-    let asm = comp.load_file(&lib_path)?.finish();
+    let root = canonicalize(path)?;
+    let excluded = exclude_patterns(path, &options.exclude)?;
+    if cache {
+        if let Some(mut files) = read_cached_extraction(path, &options.cache_settings()) {
+            // Module documentation lives outside the sources, so it's read again
+            for file in &mut files {
+                attach_module_readmes(&mut file.items, path);
+            }
+            return Ok(files);
+        }
+    }
 
-    let mut inputs = asm.inputs.clone();
-    let files: Vec<_> = inputs.files.iter().map(|file| (file.key().clone(), file.value().clone())).collect();
+    let mut comp = Compiler::with_backend(options.backend());
+    let (asm, mut inputs, compile_errors) = if options.parse_only {
+        (None, read_library_sources(&lib_path)?, Vec::new())
+    } else {
+        // The compiler keeps the library in scope, so benchmarks can refer to its bindings
+        let compile_errors = match comp.load_file(&lib_path) {
+            Ok(_) => Vec::new(),
+            Err(error) if matches!(error.kind, UiuaErrorKind::Parse(..)) => return Err(error.into()),
+            Err(error) => {
+                eprintln!(
+                    "Warning: The library doesn't compile, bindings that failed are documented from their source:\n{}",
+                    error
+                );
+                compile_errors(error)
+            }
+        };
+        let asm = comp.assembly().clone();
+        let inputs = asm.inputs.clone();
+        (Some(asm), inputs, compile_errors)
+    };
+    let mut files: Vec<_> = inputs.files.iter().map(|file| (file.key().clone(), file.value().clone())).collect();
+    // The compiler keeps the files in a hash map, so they are sorted for the order not to change between runs
+    files.sort();
 
     let mut output_files = Vec::new();
 
@@ -406,6 +1279,19 @@ pub fn extract_uiua_definitions(path: &Path) -> Result<Vec<FileContent>, Extract
         }
 
         let full_file_path = canonicalize(&file_path).unwrap();
+        let relative_path = full_file_path
+            .strip_prefix(&root)
+            .unwrap_or(&full_file_path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let main = is_same_file(&full_file_path, &lib_path)?;
+        // The entry file is what's documented, so it can't be excluded
+        if !main && is_excluded(&excluded, &relative_path) {
+            continue;
+        }
+
         let src = InputSrc::File(file_path.clone().into());
         let (items, errors, _) = parse(&file_content, src, &mut inputs);
 
@@ -413,14 +1299,77 @@ pub fn extract_uiua_definitions(path: &Path) -> Result<Vec<FileContent>, Extract
             return Err(ExtractError::ParseError(full_file_path, errors[0].clone()));
         }
 
-        let file_content = FileContent {
-            main: is_same_file(&full_file_path, &lib_path)?,
+        let extraction = Extraction {
+            inputs: &inputs,
+            assembly: asm.as_ref(),
+            compile_errors: &compile_errors,
+        };
+        let mut file_content = FileContent {
+            main,
             file: full_file_path.to_string_lossy().into_owned(),
-            items: handle_ast_items(items, &asm),
+            items: handle_ast_items(items, &extraction, &relative_path, &[]),
+            relative_path,
+            source: file_content.to_string(),
         };
+        let mut locations = Vec::new();
+        remove_modules(
+            &mut file_content.items,
+            &|name| is_excluded(&excluded, &name.replace('~', ".")),
+            &mut Vec::new(),
+            &mut locations,
+        );
+        blank_lines(&mut file_content.source, &locations);
+        link_variants(&mut file_content.items);
+        link_module_dependencies(&mut file_content.items);
+        flag_metric_outliers(&mut file_content.items);
+        attach_module_readmes(&mut file_content.items, path);
+        if file_content.main && asm.is_some() {
+            run_benchmarks(&mut file_content.items, &comp);
+        }
 
         output_files.push(file_content);
     }
 
+    if cache && compile_errors.is_empty() {
+        write_cached_extraction(path, &options.cache_settings(), &inputs, &output_files);
+    }
+
     Ok(output_files)
 }
+
+/// Reads the files of a library that isn't compiled, by following the imports from its entry file.
+pub(crate) fn read_library_sources(lib_path: &Path) -> Result<Inputs, ExtractError> {
+    let mut inputs = Inputs::default();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([lib_path.to_path_buf()]);
+
+    while let Some(file_path) = queue.pop_front() {
+        if !visited.insert(canonicalize(&file_path)?) {
+            continue;
+        }
+
+        let source = fs::read_to_string(&file_path)?;
+        let (items, errors, _) = parse(&source, InputSrc::File(file_path.clone().into()), &mut inputs);
+        if !errors.is_empty() {
+            return Err(ExtractError::ParseError(canonicalize(&file_path)?, errors[0].clone()));
+        }
+
+        // Imports are relative to the importing file
+        let directory = file_path.parent().unwrap_or(Path::new(""));
+        queue.extend(local_imports(&items).into_iter().map(|import| directory.join(import)));
+    }
+
+    Ok(inputs)
+}
+
+/// The paths of the files a file imports, except for libraries imported from git.
+fn local_imports(items: &[Item]) -> Vec<String> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            Item::Import(import) if !import.path.value.starts_with("git:") => vec![import.path.value.clone()],
+            Item::Module(module) => local_imports(&module.value.items),
+            _ => Vec::new(),
+        })
+        .collect()
+}
@@ -0,0 +1,364 @@
+use std::borrow::Cow;
+
+use brotli::enc::backward_references::BrotliEncoderMode;
+use brotli::enc::BrotliEncoderParams;
+
+/// The font the generated site shows code in, as the TrueType file the CDN and the LaTeX reference use.
+pub const FONT_FILE: &str = "Uiua386.ttf";
+
+/// The font as the site publishes it, packed as WOFF2.
+pub const WEB_FONT_FILE: &str = "Uiua386.woff2";
+
+/// The font as published in the Uiua repository, served by jsDelivr for the matching Uiua version.
+pub fn font_cdn_url() -> String {
+    format!("https://cdn.jsdelivr.net/gh/uiua-lang/uiua@{}/src/algorithm/{}", uiua::VERSION, FONT_FILE)
+}
+
+/// Where the site loads the font for code from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FontStrategy {
+    /// Publish the whole font with the site, so it works offline.
+    Embed,
+    /// Load the font from a CDN, which keeps it out of the site.
+    Cdn,
+    /// Use Uiua386 if it's installed on the reader's system, and a monospace font otherwise.
+    System,
+    /// Publish a copy of the font with only the glyphs of the characters on the site's pages, so it works offline
+    /// and is a fraction of the size.
+    #[default]
+    Subset,
+}
+
+impl FontStrategy {
+    /// The `@font-face` rule for the font, for a stylesheet at `path_to_root` from the root of the site.
+    pub fn font_face_css(self, path_to_root: &str) -> String {
+        let source = match self {
+            FontStrategy::Embed | FontStrategy::Subset => format!("url(\"{}{}\") format(\"woff2\")", path_to_root, WEB_FONT_FILE),
+            FontStrategy::Cdn => format!("url(\"{}\") format(\"truetype\")", font_cdn_url()),
+            FontStrategy::System => "local(\"Uiua386\")".to_owned(),
+        };
+        format!("@font-face {{ font-family: \"Uiua386\"; src: {}; }}\n", source)
+    }
+}
+
+/// Drops the outlines of the glyphs that none of the characters need from a TrueType font.
+///
+/// Glyphs keep their IDs, so the tables that refer to them stay valid without being rewritten,
+/// and only the outlines and the glyph names are removed. Returns `None` for fonts that can't be
+/// subset this way, like fonts with CFF outlines.
+pub fn subset_font(font: &[u8], characters: impl IntoIterator<Item = char>) -> Option<Vec<u8>> {
+    let face = ttf_parser::Face::parse(font, 0).ok()?;
+    let tables = read_tables(font)?;
+    let table = |tag: &[u8; 4]| tables.iter().find(|(table_tag, _)| table_tag == tag).map(|(_, data)| *data);
+
+    let head = table(b"head")?;
+    let glyf = table(b"glyf")?;
+    let loca = table(b"loca")?;
+    let glyph_count = face.number_of_glyphs() as usize;
+    let long_offsets = read_u16(head, 50)? == 1;
+    let offsets = (0..=glyph_count)
+        .map(|index| match long_offsets {
+            true => read_u32(loca, index * 4),
+            false => read_u16(loca, index * 2).map(|offset| offset as u32 * 2),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let glyph_data = |id: usize| glyf.get(offsets[id] as usize..offsets[id + 1] as usize);
+
+    // The .notdef glyph is shown for missing characters, and composite glyphs need their components
+    let mut kept = vec![false; glyph_count];
+    let mut queue = vec![0];
+    queue.extend(characters.into_iter().filter_map(|character| face.glyph_index(character)).map(|id| id.0));
+    while let Some(id) = queue.pop() {
+        let id = id as usize;
+        if id >= glyph_count || kept[id] {
+            continue;
+        }
+        kept[id] = true;
+        queue.extend(composite_components(glyph_data(id)?)?);
+    }
+
+    let mut subset_glyf = Vec::new();
+    let mut subset_loca = Vec::new();
+    for (id, kept) in kept.iter().enumerate() {
+        subset_loca.extend((subset_glyf.len() as u32).to_be_bytes());
+        if *kept {
+            subset_glyf.extend(glyph_data(id)?);
+            subset_glyf.resize(subset_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    subset_loca.extend((subset_glyf.len() as u32).to_be_bytes());
+
+    // The checksum adjustment is recalculated once the font is assembled, and the offsets are now long
+    let mut subset_head = head.to_vec();
+    subset_head[8..12].fill(0);
+    subset_head[50..52].copy_from_slice(&1u16.to_be_bytes());
+
+    let tables = tables
+        .iter()
+        .map(|(tag, data)| {
+            let data = match tag {
+                b"glyf" => Cow::Borrowed(subset_glyf.as_slice()),
+                b"loca" => Cow::Borrowed(subset_loca.as_slice()),
+                b"head" => Cow::Borrowed(subset_head.as_slice()),
+                // Version 3 of the table has the same header without the glyph names
+                b"post" if data.len() >= 32 => {
+                    let mut post = data[..32].to_vec();
+                    post[..4].copy_from_slice(&0x0003_0000u32.to_be_bytes());
+                    Cow::Owned(post)
+                }
+                _ => Cow::Borrowed(*data),
+            };
+            (*tag, data)
+        })
+        .collect::<Vec<_>>();
+
+    Some(write_font(&font[..4], &tables))
+}
+
+/// Packs a TrueType font as WOFF2, which browsers load like the font itself, only compressed with Brotli.
+///
+/// The tables are stored as they are, without the transforms WOFF2 has for the glyph outlines, which
+/// would only save a little more. Returns `None` for data that isn't a font.
+pub fn woff2_font(font: &[u8]) -> Option<Vec<u8>> {
+    let mut tables = read_tables(font)?;
+    tables.sort_by_key(|(tag, _)| *tag);
+    let head = tables.iter().find(|(tag, _)| tag == b"head").map(|(_, data)| *data)?;
+
+    let mut directory = Vec::new();
+    let mut data = Vec::new();
+    for (tag, table) in &tables {
+        // Tags are written out instead of as their index in the list of known tags, and the null transform is
+        // version 3 for the glyf and loca tables but version 0 for the others
+        let transform = match tag {
+            b"glyf" | b"loca" => 3,
+            _ => 0,
+        };
+        directory.push(transform << 6 | 63);
+        directory.extend(tag);
+        write_base128(&mut directory, table.len() as u32);
+        data.extend(*table);
+    }
+
+    let params = BrotliEncoderParams {
+        mode: BrotliEncoderMode::BROTLI_MODE_FONT,
+        quality: 11,
+        lgwin: 22,
+        ..Default::default()
+    };
+    let mut compressed = Vec::new();
+    brotli::BrotliCompress(&mut data.as_slice(), &mut compressed, &params).ok()?;
+
+    let sfnt_size = 12 + tables.len() * 16 + tables.iter().map(|(_, table)| table.len().next_multiple_of(4)).sum::<usize>();
+    let length = (48 + directory.len() + compressed.len()).next_multiple_of(4);
+    let mut woff2 = b"wOF2".to_vec();
+    woff2.extend(&font[..4]);
+    woff2.extend((length as u32).to_be_bytes());
+    woff2.extend((tables.len() as u16).to_be_bytes());
+    woff2.extend(0u16.to_be_bytes());
+    woff2.extend((sfnt_size as u32).to_be_bytes());
+    woff2.extend((compressed.len() as u32).to_be_bytes());
+    // The version of the font, followed by the offsets and lengths of the metadata and private data it doesn't have
+    woff2.extend(head.get(4..8)?);
+    woff2.extend([0; 20]);
+    woff2.extend(directory);
+    woff2.extend(compressed);
+    woff2.resize(length, 0);
+    Some(woff2)
+}
+
+/// Writes a number in the variable length format of WOFF2, seven bits per byte, most significant first.
+fn write_base128(output: &mut Vec<u8>, value: u32) {
+    let bytes = (1..5).take_while(|bytes| value >> (7 * bytes) != 0).count() + 1;
+    for byte in (0..bytes).rev() {
+        let continuation = if byte > 0 { 0x80 } else { 0 };
+        output.push((value >> (7 * byte)) as u8 & 0x7F | continuation);
+    }
+}
+
+/// The tables of a font, in the order of its table directory.
+fn read_tables(font: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let table_count = read_u16(font, 4)? as usize;
+    (0..table_count)
+        .map(|index| {
+            let record = 12 + index * 16;
+            let tag = font.get(record..record + 4)?.try_into().ok()?;
+            let offset = read_u32(font, record + 8)? as usize;
+            let length = read_u32(font, record + 12)? as usize;
+            Some((tag, font.get(offset..offset + length)?))
+        })
+        .collect()
+}
+
+/// The glyphs a composite glyph is made of, or none for a simple glyph.
+fn composite_components(glyph: &[u8]) -> Option<Vec<u16>> {
+    const ARGS_ARE_WORDS: u16 = 0x0001;
+    const HAS_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const HAS_X_AND_Y_SCALE: u16 = 0x0040;
+    const HAS_TWO_BY_TWO: u16 = 0x0080;
+
+    // Empty glyphs have no data, and simple glyphs a non-negative number of contours
+    if glyph.is_empty() || read_u16(glyph, 0)? as i16 >= 0 {
+        return Some(Vec::new());
+    }
+
+    let mut components = Vec::new();
+    let mut position = 10;
+    loop {
+        let flags = read_u16(glyph, position)?;
+        components.push(read_u16(glyph, position + 2)?);
+        position += 4 + if flags & ARGS_ARE_WORDS != 0 { 4 } else { 2 };
+        position += if flags & HAS_SCALE != 0 {
+            2
+        } else if flags & HAS_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & HAS_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(components);
+        }
+    }
+}
+
+fn write_font(sfnt_version: &[u8], tables: &[([u8; 4], Cow<[u8]>)]) -> Vec<u8> {
+    let table_count = tables.len() as u16;
+    let entry_selector = table_count.ilog2() as u16;
+    let search_range = (1 << entry_selector) * 16;
+
+    let mut font = sfnt_version.to_vec();
+    for value in [table_count, search_range, entry_selector, table_count * 16 - search_range] {
+        font.extend(value.to_be_bytes());
+    }
+
+    let mut offset = font.len() + tables.len() * 16;
+    for (tag, data) in tables {
+        font.extend(tag);
+        font.extend(checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+
+    let mut head_offset = None;
+    for (tag, data) in tables {
+        if tag == b"head" {
+            head_offset = Some(font.len());
+        }
+        font.extend(data.iter());
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &[u8] = include_bytes!("../design/Uiua386.ttf");
+
+    /// Unpacks a WOFF2 font written by `woff2_font` back into a TrueType font.
+    fn unpack_woff2(woff2: &[u8]) -> Vec<u8> {
+        assert_eq!(&woff2[..4], b"wOF2");
+        assert_eq!(read_u32(woff2, 8).unwrap() as usize, woff2.len());
+        let table_count = read_u16(woff2, 12).unwrap() as usize;
+        let compressed_length = read_u32(woff2, 20).unwrap() as usize;
+
+        let mut position = 48;
+        let mut directory = Vec::new();
+        for _ in 0..table_count {
+            assert_eq!(woff2[position] & 63, 63);
+            let tag: [u8; 4] = woff2[position + 1..position + 5].try_into().unwrap();
+            position += 5;
+            let mut length = 0;
+            loop {
+                let byte = woff2[position];
+                position += 1;
+                length = length << 7 | (byte & 0x7F) as usize;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+            directory.push((tag, length));
+        }
+
+        let mut data = Vec::new();
+        brotli::BrotliDecompress(&mut &woff2[position..position + compressed_length], &mut data).unwrap();
+        let mut offset = 0;
+        let tables = directory
+            .into_iter()
+            .map(|(tag, length)| {
+                offset += length;
+                (tag, Cow::Owned(data[offset - length..offset].to_vec()))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(offset, data.len());
+        write_font(&woff2[4..8], &tables)
+    }
+
+    #[test]
+    fn woff2_keeps_every_table() {
+        let woff2 = woff2_font(FONT).unwrap();
+        assert!(woff2.len() < FONT.len() / 2, "{} bytes", woff2.len());
+
+        let font = unpack_woff2(&woff2);
+        let mut original = read_tables(FONT).unwrap();
+        original.sort_by_key(|(tag, _)| *tag);
+        let unpacked = read_tables(&font).unwrap();
+        assert_eq!(original.len(), unpacked.len());
+        for ((tag, original), (unpacked_tag, unpacked)) in original.iter().zip(&unpacked) {
+            assert_eq!(tag, unpacked_tag);
+            // The checksum adjustment of head depends on where the tables are
+            if tag != b"head" {
+                assert!(original == unpacked, "{}", String::from_utf8_lossy(tag));
+            }
+        }
+
+        let face = ttf_parser::Face::parse(&font, 0).unwrap();
+        let original = ttf_parser::Face::parse(FONT, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), original.number_of_glyphs());
+        for character in ['⊂', '⍥', 'a', '∵'] {
+            let glyph = face.glyph_index(character).unwrap();
+            assert_eq!(Some(glyph), original.glyph_index(character));
+            assert_eq!(face.glyph_bounding_box(glyph), original.glyph_bounding_box(glyph));
+        }
+    }
+
+    #[test]
+    fn subset_woff2_has_only_the_used_glyphs() {
+        let subset = subset_font(FONT, "⊂+1".chars()).unwrap();
+        let woff2 = woff2_font(&subset).unwrap();
+        assert!(woff2.len() < woff2_font(FONT).unwrap().len() / 4, "{} bytes", woff2.len());
+
+        let font = unpack_woff2(&woff2);
+        let face = ttf_parser::Face::parse(&font, 0).unwrap();
+        for character in ['⊂', '+', '1'] {
+            assert!(face.glyph_bounding_box(face.glyph_index(character).unwrap()).is_some(), "{}", character);
+        }
+        assert!(face.glyph_bounding_box(face.glyph_index('⍥').unwrap()).is_none());
+    }
+}
@@ -1,15 +1,20 @@
 use leptos::view;
 use leptos::*;
-use uiua::{NativeSys, PrimClass, Primitive, Signature, SpanKind, Spans};
+use uiua::{Compiler, NativeSys, PrimClass, Primitive, SafeSys, Signature, SpanKind, Spans};
 use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug)]
 enum CodeFragment {
     Unspanned(String),
     Br,
-    Span(String, SpanKind),
+    Span(String, Box<SpanKind>),
+    /// Where a long line is soft-wrapped, shown as a break with a continuation marker.
+    Wrap,
 }
 
+/// The columns taken by the continuation marker at the start of a wrapped line.
+const WRAP_MARKER_WIDTH: usize = 2;
+
 struct CodeLines {
     frags: Vec<Vec<CodeFragment>>,
 }
@@ -54,7 +59,7 @@ fn sig_class(sig: Signature) -> &'static str {
     }
 }
 
-fn prim_sig_class(prim: Primitive, subscript: Option<i32>) -> &'static str {
+pub(crate) fn prim_sig_class(prim: Primitive, subscript: Option<i32>) -> &'static str {
     match prim {
         Primitive::Identity => "stack-function",
         prim if matches!(prim.class(), PrimClass::Stack | PrimClass::Debug) && prim.modifier_args().is_none() => "stack-function",
@@ -69,7 +74,12 @@ fn prim_sig_class(prim: Primitive, subscript: Option<i32>) -> &'static str {
     }
 }
 
-fn build_code_lines(code: &str) -> CodeLines {
+/// Windows line endings are a single grapheme.
+fn is_line_break(grapheme: &str) -> bool {
+    grapheme == "\n" || grapheme == "\r\n"
+}
+
+fn build_code_lines(code: &str, spans: Spans) -> CodeLines {
     let mut lines = CodeLines { frags: vec![Vec::new()] };
 
     let chars: Vec<&str> = code.graphemes(true).collect();
@@ -82,14 +92,14 @@ fn build_code_lines(code: &str) -> CodeLines {
         lines.line().push(CodeFragment::Unspanned(String::new()));
         let mut unspanned = String::new();
         while *curr < target {
-            if chars[*curr] == "\n" {
+            if is_line_break(chars[*curr]) {
                 if !unspanned.is_empty() {
                     lines.push_str(&unspanned);
                     unspanned.clear();
                 }
                 lines.new_line();
                 *curr += 1;
-                while *curr < target && chars[*curr] == "\n" {
+                while *curr < target && is_line_break(chars[*curr]) {
                     lines.new_line();
                     *curr += 1;
                 }
@@ -106,25 +116,19 @@ fn build_code_lines(code: &str) -> CodeLines {
     };
 
     let mut end = 0;
-    let spans = Spans::with_backend(code, NativeSys::default());
     for span in spans.spans {
         let kind = span.value;
         let span = span.span;
         push_unspanned(&mut lines, span.start.char_pos as usize, &mut end);
 
-        let text: String = chars[span.start.char_pos as usize..span.end.char_pos as usize].iter().copied().collect();
-
-        if !text.is_empty() && text.chars().all(|c| c == '\n') {
-            lines.new_line();
-            for _ in 0..text.chars().count() - 1 {
+        // Spans of multi-line strings and raw strings can cross lines, each line gets a fragment of the span's kind
+        let text = &chars[span.start.char_pos as usize..span.end.char_pos as usize];
+        for (i, text) in text.split(|grapheme| is_line_break(grapheme)).enumerate() {
+            if i > 0 {
                 lines.new_line();
             }
-        } else {
-            for (i, text) in text.lines().enumerate() {
-                if i > 0 {
-                    lines.new_line();
-                }
-                lines.line().push(CodeFragment::Span(text.into(), kind.clone()));
+            if !text.is_empty() {
+                lines.line().push(CodeFragment::Span(text.concat(), Box::new(kind.clone())));
             }
         }
 
@@ -140,26 +144,104 @@ fn build_code_lines(code: &str) -> CodeLines {
     lines
 }
 
-pub fn format_source_code(code: &str) -> String {
-    let CodeLines { frags } = build_code_lines(code);
-    let mut line_views = Vec::new();
+/// Breaks the lines longer than `column` graphemes into rows that fit, counting the continuation marker.
+///
+/// Tokens are moved to the next row whole when they fit on one, and split only when they're longer than a row.
+fn wrap_code_lines(lines: CodeLines, column: usize) -> CodeLines {
+    let column = column.max(WRAP_MARKER_WIDTH + 1);
+    let frags = lines
+        .frags
+        .into_iter()
+        .map(|line| {
+            let mut wrapped = Vec::new();
+            let mut width = 0;
+            for frag in line {
+                let (text, kind) = match frag {
+                    CodeFragment::Unspanned(text) => (text, None),
+                    CodeFragment::Span(text, kind) => (text, Some(kind)),
+                    frag => {
+                        wrapped.push(frag);
+                        continue;
+                    }
+                };
+
+                let mut graphemes = text.graphemes(true).collect::<Vec<_>>();
+                if kind.is_some() && width + graphemes.len() > column && graphemes.len() <= column - WRAP_MARKER_WIDTH && width > WRAP_MARKER_WIDTH {
+                    wrapped.push(CodeFragment::Wrap);
+                    width = WRAP_MARKER_WIDTH;
+                }
+                while !graphemes.is_empty() {
+                    if width == column {
+                        wrapped.push(CodeFragment::Wrap);
+                        width = WRAP_MARKER_WIDTH;
+                    }
+                    let rest = graphemes.split_off(graphemes.len().min(column - width));
+                    width += graphemes.len();
+                    let text = graphemes.concat();
+                    wrapped.push(match &kind {
+                        Some(kind) => CodeFragment::Span(text, kind.clone()),
+                        None => CodeFragment::Unspanned(text),
+                    });
+                    graphemes = rest;
+                }
+            }
+            wrapped
+        })
+        .collect();
+
+    CodeLines { frags }
+}
+
+/// Highlights the code, soft-wrapping its lines at `wrap` columns if given.
+pub fn format_source_code(code: &str, wrap: Option<usize>) -> String {
+    render_code_lines(format_source_lines(code, wrap))
+}
+
+/// Highlights the code like `format_source_code`, compiled as if it came after the code the compiler has loaded, so
+/// it can use the bindings of a library. The code can't use the system, even if the compiler can.
+pub fn format_source_code_in(code: &str, wrap: Option<usize>, compiler: &Compiler) -> String {
+    let mut compiler = compiler.clone();
+    compiler.set_backend(SafeSys::new());
+    // Code with errors is highlighted as far as it can be
+    let _ = compiler.load_str(code);
+    render_code_lines(format_spans(code, Spans::with_compiler(code, &compiler), wrap))
+}
+
+fn render_code_lines(lines: Vec<String>) -> String {
+    let line_views = lines
+        .into_iter()
+        .map(|line| view! { <div class="code-line" inner_html=line></div> })
+        .collect_view();
+
+    ssr::render_to_string(|| line_views).to_string()
+}
+
+/// Highlights the code and returns the markup of each line separately, soft-wrapped at `wrap` columns if given.
+pub fn format_source_lines(code: &str, wrap: Option<usize>) -> Vec<String> {
+    format_spans(code, Spans::with_backend(code, NativeSys), wrap)
+}
+
+fn format_spans(code: &str, spans: Spans, wrap: Option<usize>) -> Vec<String> {
+    let lines = build_code_lines(code, spans);
+    let CodeLines { frags } = match wrap {
+        Some(column) => wrap_code_lines(lines, column),
+        None => lines,
+    };
+    let mut lines = Vec::new();
     for line in frags {
         if line.is_empty() {
-            line_views.push(view! {
-                <div class="code-line">
-                    <br />
-                </div>
-            });
+            lines.push(ssr::render_to_string(|| view! { <br /> }).to_string());
             continue;
         }
         let mut frag_views = Vec::new();
-        let mut frags = line.into_iter().peekable();
-        while let Some(frag) = frags.next() {
+        for frag in line {
             match frag {
                 CodeFragment::Unspanned(s) => frag_views.push(view! { <span class="code-span">{s}</span> }.into_view()),
                 CodeFragment::Br => frag_views.push(view! { <br /> }.into_view()),
+                // The break and the marker are generated by the stylesheet, so they aren't copied with the code
+                CodeFragment::Wrap => frag_views.push(view! { <span class="code-wrap" aria-hidden="true"></span> }.into_view()),
                 CodeFragment::Span(text, kind) => {
-                    let color_class = match &kind {
+                    let color_class = match kind.as_ref() {
                         SpanKind::Primitive(prim, sig) => prim_sig_class(*prim, *sig),
                         SpanKind::Obverse(_) => prim_sig_class(Primitive::Obverse, None),
                         SpanKind::Number => "number-literal",
@@ -177,8 +259,8 @@ pub fn format_source_code(code: &str) -> String {
             }
         }
 
-        line_views.push(view! { <div class="code-line">{frag_views}</div> })
+        lines.push(ssr::render_to_string(|| frag_views.into_view()).to_string());
     }
 
-    ssr::render_to_string(|| line_views.into_view()).to_string()
+    lines
 }
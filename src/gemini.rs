@@ -0,0 +1,257 @@
+use crate::extractor::{
+    qualified_name, BindingDefinition, BindingType, DataDefinition, Definition, Documented, FileContent, ItemContent, ModuleDefinition,
+    NamedSignature, VariantDefinition,
+};
+use crate::generator::box_description;
+use crate::metadata::ProjectMetadata;
+use crate::summarizer::{generate_getting_started, has_getting_started, landing_doc_comments};
+use markdown::mdast::Node;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io;
+use std::path::Path;
+
+/// Writes the docs to `output_directory` as a capsule for Gemini, which has its own line-based markup instead of HTML.
+///
+/// The front page lists the library's top-level bindings, and every module gets a page of its own.
+pub fn generate_gemini_capsule(output_directory: &Path, content: &FileContent, landing: &FileContent, metadata: &ProjectMetadata) -> io::Result<()> {
+    if output_directory.exists() {
+        remove_dir_all(output_directory)?;
+    }
+    create_dir_all(output_directory.join("modules"))?;
+
+    let mut page = vec![format!("# {}", metadata.title())];
+    if let Some(description) = &metadata.description {
+        page.extend([String::new(), description.clone()]);
+    }
+    if let Some(version) = &metadata.version {
+        page.extend([String::new(), format!("Version {}", version)]);
+    }
+    page.extend([String::new(), "```".to_owned(), metadata.import_snippet(None), "```".to_owned()]);
+
+    let doc_comments = landing_doc_comments(landing, metadata);
+    for comment in &doc_comments {
+        page.extend([String::new(), markdown_to_gemtext(comment)]);
+    }
+    if !has_getting_started(&doc_comments) {
+        page.extend([String::new(), markdown_to_gemtext(&generate_getting_started(&content.items, metadata))]);
+    }
+
+    let modules = content
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Module(module) if module.has_public_items() => Some(module),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !modules.is_empty() {
+        page.extend([String::new(), "## Modules".to_owned(), String::new()]);
+    }
+    for module in modules {
+        page.push(match module.summary() {
+            Some(summary) => format!("=> {} {}: {}", module_page(module), module.name, summary),
+            None => format!("=> {} {}", module_page(module), module.name),
+        });
+        write(
+            output_directory.join(module_page(module)),
+            generate_module_page(module, metadata).join("\n") + "\n",
+        )?;
+    }
+
+    let bindings = content
+        .items
+        .iter()
+        .filter(|item| !matches!(item, ItemContent::Module(_)))
+        .flat_map(generate_item)
+        .collect::<Vec<_>>();
+    if !bindings.is_empty() {
+        page.extend([String::new(), "## Bindings".to_owned()]);
+        page.extend(bindings);
+    }
+
+    page.extend([String::new(), generator_note()]);
+    write(output_directory.join("index.gmi"), page.join("\n") + "\n")
+}
+
+/// The path of a module's page, relative to the root of the capsule.
+fn module_page(module: &ModuleDefinition) -> String {
+    format!("modules/{}.gmi", module.name)
+}
+
+fn generate_module_page(module: &ModuleDefinition, metadata: &ProjectMetadata) -> Vec<String> {
+    let mut page = vec![
+        format!("# {}", module.name),
+        String::new(),
+        format!("=> ../index.gmi Back to {}", metadata.title()),
+        String::new(),
+        "```".to_owned(),
+        metadata.import_snippet(Some(&module.name)),
+        "```".to_owned(),
+    ];
+    if let Some(comment) = module.comment() {
+        page.extend([String::new(), markdown_to_gemtext(comment)]);
+    }
+    if let Some(readme) = &module.readme {
+        page.extend([String::new(), markdown_to_gemtext(readme)]);
+    }
+    page.extend(module.items.iter().flat_map(generate_item));
+    page.extend([String::new(), generator_note()]);
+    page
+}
+
+fn generator_note() -> String {
+    format!("Generated by uiua-doc-gen for Uiua {}", uiua::VERSION)
+}
+
+/// The lines of an item, starting with a blank line. Nested modules are listed with their items.
+fn generate_item(item: &ItemContent) -> Vec<String> {
+    let (name, description, comment, code) = match item {
+        ItemContent::Binding(binding) if binding.is_documented() => (
+            qualified_name(&binding.module_path, &binding.name),
+            binding_description(binding),
+            binding.comment(),
+            Some(&binding.code).filter(|code| !code.is_empty()),
+        ),
+        ItemContent::Data(DataDefinition {
+            name,
+            module_path,
+            definition,
+            comment,
+            ..
+        }) => (
+            match name {
+                Some(name) => qualified_name(module_path, name),
+                None => module_path.join("~"),
+            },
+            data_description("data", definition.as_ref()),
+            comment.as_deref(),
+            None,
+        ),
+        ItemContent::Variant(VariantDefinition {
+            name,
+            module_path,
+            definition,
+            comment,
+            ..
+        }) => (
+            qualified_name(module_path, name),
+            data_description("variant", definition.as_ref()),
+            comment.as_deref(),
+            None,
+        ),
+        ItemContent::Module(module) if module.has_public_items() => {
+            let mut lines = vec![String::new(), format!("## {}", qualified_name(&module.module_path, &module.name))];
+            if let Some(comment) = module.comment() {
+                lines.extend([String::new(), markdown_to_gemtext(comment)]);
+            }
+            lines.extend(module.items.iter().flat_map(generate_item));
+            return lines;
+        }
+        _ => return Vec::new(),
+    };
+
+    let mut lines = vec![String::new(), format!("### {}", name), description];
+    if let Some(comment) = comment {
+        lines.extend([String::new(), markdown_to_gemtext(comment)]);
+    }
+    if let Some(code) = code {
+        lines.extend([String::new(), "```uiua".to_owned(), code.clone(), "```".to_owned()]);
+    }
+    lines
+}
+
+fn binding_description(binding: &BindingDefinition) -> String {
+    let named = |signature: Option<&NamedSignature>| match signature {
+        Some(signature) => format!(", {}", signature.to_comment().trim_start_matches("# ")),
+        None => String::new(),
+    };
+
+    match &binding.kind {
+        BindingType::Const(_) => "constant".to_owned(),
+        BindingType::Function(function) => match &function.signature {
+            Some(signature) => format!("function {}{}", signature, named(function.named_signature.as_ref())),
+            None => format!("function{}", named(function.named_signature.as_ref())),
+        },
+        BindingType::IndexMacro(index_macro) => format!(
+            "index macro with {} operands{}",
+            index_macro.arguments,
+            named(index_macro.named_signature.as_ref())
+        ),
+        BindingType::CodeMacro(code_macro) => format!("code macro{}", named(code_macro.named_signature.as_ref())),
+    }
+}
+
+fn data_description(kind: &str, definition: Option<&Definition>) -> String {
+    let fields = definition
+        .map(|definition| definition.fields.iter().map(|field| field.name.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    match fields.is_empty() {
+        true => format!("{}, {}", kind, box_description(definition)),
+        false => format!("{}, {}, fields: {}", kind, box_description(definition), fields.join(" ")),
+    }
+}
+
+/// Converts Markdown to gemtext, which has no inline markup, so links are listed after their block.
+fn markdown_to_gemtext(markdown: &str) -> String {
+    let root = markdown::to_mdast(markdown, &markdown::ParseOptions::gfm()).expect("Unable to parse markdown");
+    let mut blocks = Vec::new();
+    block_to_gemtext(&root, &mut blocks);
+    blocks.join("\n\n")
+}
+
+fn block_to_gemtext(node: &Node, blocks: &mut Vec<String>) {
+    let mut links = Vec::new();
+    let block = match node {
+        Node::Root(root) => {
+            root.children.iter().for_each(|child| block_to_gemtext(child, blocks));
+            return;
+        }
+        // The page title is the only first level heading
+        Node::Heading(heading) => format!("{} {}", "#".repeat((heading.depth as usize + 1).min(3)), inline_text(node, &mut links)),
+        Node::Code(code) => format!("```{}\n{}\n```", code.lang.as_deref().unwrap_or_default(), code.value),
+        Node::List(list) => list
+            .children
+            .iter()
+            .map(|item| format!("* {}", inline_text(item, &mut links)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Node::Blockquote(_) => format!("> {}", inline_text(node, &mut links)),
+        Node::ThematicBreak(_) => return,
+        _ => inline_text(node, &mut links),
+    };
+
+    blocks.push(
+        std::iter::once(block)
+            .chain(links.into_iter().map(|(url, text)| format!("=> {} {}", url, text)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+}
+
+/// The text of a node on a single line, collecting the links in it.
+fn inline_text(node: &Node, links: &mut Vec<(String, String)>) -> String {
+    match node {
+        Node::Text(text) => text.value.replace('\n', " "),
+        Node::InlineCode(code) => format!("`{}`", code.value),
+        Node::Html(html) => html.value.clone(),
+        Node::Break(_) => " ".to_owned(),
+        Node::Image(image) => {
+            links.push((image.url.clone(), image.alt.clone()));
+            image.alt.clone()
+        }
+        Node::Link(link) => {
+            let text = link.children.iter().map(|child| inline_text(child, links)).collect::<String>();
+            links.push((link.url.clone(), text.clone()));
+            text
+        }
+        node => {
+            let separator = match node {
+                Node::Paragraph(_) | Node::Emphasis(_) | Node::Strong(_) | Node::Delete(_) => "",
+                _ => " ",
+            };
+            node.children()
+                .map(|children| children.iter().map(|child| inline_text(child, links)).collect::<Vec<_>>().join(separator))
+                .unwrap_or_default()
+        }
+    }
+}
@@ -1,71 +1,390 @@
-use crate::formatter::format_source_code;
+use crate::analysis::{dependency_order, CodeMetrics, Permission};
+use crate::badges::build_badges;
+use crate::benchmark::{format_duration, Benchmark};
+use crate::cache::stable_hash;
+use crate::changes::{api_changes_feed, ApiSnapshot};
+use crate::examples::{ExampleNotebook, NotebookCell};
+use crate::fonts::{subset_font, woff2_font, FontStrategy, WEB_FONT_FILE};
+use crate::formatter::{format_source_code, format_source_lines, prim_sig_class};
+use crate::glossary::{Glossary, GLOSSARY_PAGE};
+use crate::hooks::MarkdownHooks;
+use crate::hosting::{base_path, Hosting};
+use crate::registry::RegistryPackage;
+use crate::search::{anchor_map_json, build_anchor_map, build_search_index, example_search_entries, search_index_json, SearchEntry};
+use crate::signatures::{build_signature_comments, signature_comments_json};
 use crate::{
     extractor::{
-        BindingDefinition, BindingType, CodeMacroDefinition, Colored, ConstantDefinition, DataDefinition, Definition, Documented, Field,
-        FunctionDefinition, IndexMacroDefinition, ItemContent, ModuleDefinition, NamedSignature, SignatureInfo, VariantDefinition,
+        qualified_name, BindingDefinition, BindingType, CodeMacroDefinition, Colored, ConstantDefinition, DataDefinition, Definition, Documented,
+        Field, FileContent, FunctionDefinition, GeneratedFunction, IndexMacroDefinition, ItemContent, ItemReference, ModuleDefinition,
+        NamedSignature, SignatureInfo, SourceLocation, Unverified, VariantDefinition,
+    },
+    summarizer::{
+        pluralize, ArityBucket, ContentItems, DocumentationSection, DocumentationSummary, ModuleCard, RenderingContent, RenderingItem, SectionType,
     },
-    summarizer::{DocumentationSummary, RenderingContent, RenderingItem},
 };
+use kuchiki::iter::NodeIterator;
 use kuchiki::traits::TendrilSink;
-use leptos::{html::Div, view, CollectView, HtmlElement, IntoView, View};
+use kuchiki::{Attribute, ExpandedName, NodeRef};
+use leptos::{
+    html::{Article, Div},
+    view, CollectView, HtmlElement, IntoView, View,
+};
+use markup5ever::{local_name, namespace_url, ns, LocalName, QualName};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
-use std::collections::HashMap;
-use std::fs::{create_dir_all, remove_dir_all};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
+use uiua::Primitive;
 
 #[derive(Error, Debug)]
 pub enum GenerationError {}
 
-pub fn generate_documentation_site(directory: &Path, summary: DocumentationSummary) -> Result<(), GenerationError> {
-    let output_directory = directory.join("doc-site");
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    /// Write the bodies of item panels to separate fragments, loaded when a panel is expanded.
+    pub lazy_panels: bool,
+    /// Show the size and complexity metrics of bindings on their panels.
+    pub metrics: bool,
+    /// Write every item panel as a standalone page, to be embedded in other sites.
+    pub embeds: bool,
+    /// Write a page with the title and summary of every item, which links to it share, and redirects to its panel.
+    pub share_pages: bool,
+    pub module_order: ModuleOrder,
+    /// Leave the code of bindings and the source pages out, for reference-only docs.
+    pub hide_code: bool,
+    /// Soft-wrap code lines longer than this many columns, instead of scrolling them.
+    pub wrap_code: Option<usize>,
+    /// Write the search index to a file of its own, fetched when the search is first used, when it's larger than this
+    /// many kilobytes. It's part of the front page otherwise.
+    pub search_index_limit: Option<usize>,
+    pub font: FontStrategy,
+    pub theme: Theme,
+    /// Where the site is published, to write a file with cache headers for it.
+    pub hosting: Option<Hosting>,
+    /// Name the fingerprinted files after their content instead of at random, so generating the same docs twice
+    /// gives the same files.
+    pub deterministic: bool,
+    /// The path the site is published under, like `/mylib/docs/`. Links to the site's own files and pages start with
+    /// it when it's set, instead of being relative to the page they're on.
+    pub base_path: Option<String>,
+    /// The URL of the docs of a release, with `{version}` where the release goes, to link the library's other releases
+    /// from the header.
+    pub version_url: Option<String>,
+    /// The old names of renamed and moved items and modules, with their new ones, to write pages at their old places
+    /// that redirect to the new ones.
+    pub moved: BTreeMap<String, String>,
+    pub markdown_hooks: MarkdownHooks,
+}
+
+/// How the modules are ordered in the sidebar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ModuleOrder {
+    /// In the order they are defined in the library.
+    #[default]
+    Source,
+    /// By name.
+    Alphabetical,
+    /// After the modules they refer to, so foundational modules come first.
+    Dependencies,
+}
+
+/// The theme pages are shown in until the reader switches it, which is remembered from then on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    /// Sets the theme the script applies when the reader hasn't picked one.
+    fn script_preamble(self) -> String {
+        let theme = match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        };
+        format!("const defaultTheme = '{}';\n", theme)
+    }
+}
+
+/// The page with a compact list of the library's bindings, meant to be printed.
+pub const CHEATSHEET_PAGE: &str = "cheatsheet.html";
+pub const ALL_ITEMS_PAGE: &str = "all.html";
+
+/// The page listing the bindings that use each primitive.
+pub const PRIMITIVES_PAGE: &str = "primitives.html";
+
+pub fn generate_documentation_site(
+    output_directory: &Path,
+    summary: DocumentationSummary,
+    sources: &[FileContent],
+    api: &ApiSnapshot,
+    registry: &RegistryPackage,
+    options: &GeneratorOptions,
+) -> Result<(), GenerationError> {
     if output_directory.exists() {
-        remove_dir_all(output_directory.clone()).expect("Unable to remove existing output directory");
+        remove_dir_all(output_directory).expect("Unable to remove existing output directory");
     }
-    create_dir_all(output_directory.clone()).expect("Unable to create output directory");
+    create_dir_all(output_directory).expect("Unable to create output directory");
 
-    let mut mangler = FilenameMangler::new();
+    let mut mangler = FilenameMangler::new(options.deterministic);
+    let versions = VersionMenu::new(summary.version.as_deref(), api, options.version_url.as_deref());
+    // Taken before the summary is moved into the front page, for the cache headers written at the end
+    let base_path = summary.documentation.as_deref().map(base_path).unwrap_or_else(|| "/".to_owned());
 
+    // The font is next to the stylesheet, so it only needs the base path when the stylesheet isn't loaded from it
+    let font_root = options.base_path.as_deref().unwrap_or("./");
+    let stylesheet = options.font.font_face_css(font_root) + include_str!("../design/style.css");
     save_static_file(
-        &output_directory,
-        mangler.mangle_filename("style.css".as_ref()),
-        include_bytes!("../design/style.css"),
+        output_directory,
+        mangler.mangle_filename("style.css".as_ref(), stylesheet.as_bytes()),
+        stylesheet.as_bytes(),
     );
 
+    let script = options.theme.script_preamble() + include_str!("../design/script.js");
     save_static_file(
-        &output_directory,
-        mangler.mangle_filename("script.js".as_ref()),
-        include_bytes!("../design/script.js"),
+        output_directory,
+        mangler.mangle_filename("script.js".as_ref(), script.as_bytes()),
+        script.as_bytes(),
     );
 
-    save_static_file(&output_directory, "Uiua386.ttf".parse().unwrap(), include_bytes!("../design/Uiua386.ttf"));
+    for source in sources.iter().filter(|_| !options.hide_code) {
+        let page_path = PathBuf::from("src").join(format!("{}.html", source.relative_path));
+        create_dir_all(output_directory.join(page_path.parent().unwrap())).expect("Unable to create source directory");
+        save_page(
+            output_directory,
+            page_path,
+            &render_document(generate_source_page(&summary.title, &versions, source, &mangler, options)),
+            options,
+        );
+    }
+
+    if !summary.examples.is_empty() {
+        create_dir_all(output_directory.join("examples")).expect("Unable to create examples directory");
+    }
+    for example in &summary.examples {
+        save_page(
+            output_directory,
+            example.page_path().into(),
+            &render_document(generate_example_page(&summary.title, &versions, example, &mangler, options)),
+            options,
+        );
+    }
 
+    save_static_file(output_directory, "api.json".into(), api.to_json().as_bytes());
+    save_static_file(output_directory, "registry.json".into(), registry.to_json().as_bytes());
     save_static_file(
-        &output_directory,
-        "index.html".parse().unwrap(),
-        generate_html(summary, &mut mangler).as_bytes(),
+        output_directory,
+        "anchors.json".into(),
+        anchor_map_json(&build_anchor_map(&summary)).as_bytes(),
     );
+    if !api.history.is_empty() {
+        save_static_file(output_directory, "feed.xml".into(), api_changes_feed(&summary.title, api).as_bytes());
+    }
+
+    if let Some(glossary) = &summary.glossary {
+        save_page(
+            output_directory,
+            GLOSSARY_PAGE.into(),
+            &render_document(generate_glossary_page(&summary.title, &versions, glossary, &mangler, options)),
+            options,
+        );
+    }
+
+    let search_entries = build_search_index(&summary);
+    let moved = moved_items(&search_entries, &options.moved);
+    if options.share_pages {
+        create_dir_all(output_directory.join("share")).expect("Unable to create share directory");
+        let mut shared = HashSet::new();
+        // Generated functions share the panel of their data definition, which comes first
+        for entry in search_entries.iter().filter(|entry| shared.insert(entry.anchor.clone())) {
+            save_page(
+                output_directory,
+                share_page_path(&entry.anchor),
+                &render_document(generate_share_page(&summary, entry)),
+                options,
+            );
+        }
+        for (old_anchor, entry) in &moved {
+            save_page(
+                output_directory,
+                share_page_path(old_anchor),
+                &render_document(generate_redirect_page(&summary.title, entry, format!("../index.html#{}", entry.anchor))),
+                options,
+            );
+        }
+    }
+
+    save_page(
+        output_directory,
+        ALL_ITEMS_PAGE.into(),
+        &render_document(generate_all_items_page(&summary.title, &versions, &search_entries, &mangler)),
+        options,
+    );
+
+    // Examples are only found by the search, the other lists are of the library's items
+    let mut search_entries = search_entries;
+    search_entries.extend(example_search_entries(&summary.examples));
+    let search_index = search_index_json(&search_entries);
+    let search_index = match options.search_index_limit {
+        Some(limit) if search_index.len() > limit * 1024 => {
+            let file_name = mangler.mangle_filename("search-index.json".as_ref(), search_index.as_bytes());
+            save_static_file(output_directory, file_name.clone(), search_index.as_bytes());
+            SearchIndex::File(file_name.to_string_lossy().replace('\\', "/"))
+        }
+        _ => SearchIndex::Inline(search_index),
+    };
+
+    let glossary = summary.glossary.clone();
+    let version = summary.version.clone();
+    let summary_title = summary.title.clone();
+    let document = render_document(generate_page(summary, search_index, &versions, &mangler, options));
+    if let Some(glossary) = &glossary {
+        link_glossary_terms(&document, glossary);
+    }
+    // Before the panels are taken out, so the embeds and fragments have the same links
+    if let Some(base_path) = &options.base_path {
+        apply_base_path(&document, Path::new("index.html"), base_path);
+    }
+    if options.embeds {
+        create_dir_all(output_directory.join("embed")).expect("Unable to create embed directory");
+        let root = options.base_path.as_deref().unwrap_or("../");
+        let mut embedded = HashSet::new();
+        for (anchor, embed) in extract_panel_embeds(&document, &summary_title, options.font, root) {
+            save_static_file(
+                output_directory,
+                PathBuf::from("embed").join(format!("{}.html", anchor)),
+                embed.as_bytes(),
+            );
+            embedded.insert(anchor);
+        }
+        // Module panels aren't embedded, so there's nothing to redirect to for them
+        for (old_anchor, entry) in moved.iter().filter(|(_, entry)| embedded.contains(&entry.anchor)) {
+            save_page(
+                output_directory,
+                PathBuf::from("embed").join(format!("{}.html", old_anchor)),
+                &render_document(generate_redirect_page(&summary_title, entry, format!("{}.html", entry.anchor))),
+                options,
+            );
+        }
+    }
+
+    if options.lazy_panels {
+        create_dir_all(output_directory.join("fragments")).expect("Unable to create fragments directory");
+        for (anchor, fragment) in extract_panel_fragments(&document) {
+            save_static_file(output_directory, fragment_path(&anchor), fragment.as_bytes());
+        }
+    }
+
+    // Again for the placeholders of lazy panels, which link to their fragments
+    save_page(output_directory, "index.html".into(), &document, options);
+
+    if let Some(main_file) = sources.iter().find(|source| source.main) {
+        save_page(
+            output_directory,
+            CHEATSHEET_PAGE.into(),
+            &render_document(generate_cheatsheet_page(&summary_title, &versions, &main_file.items, &mangler)),
+            options,
+        );
+
+        save_page(
+            output_directory,
+            PRIMITIVES_PAGE.into(),
+            &render_document(generate_primitives_page(&summary_title, &versions, &main_file.items, &mangler)),
+            options,
+        );
+
+        save_static_file(
+            output_directory,
+            "signatures.json".into(),
+            signature_comments_json(&build_signature_comments(&main_file.items)).as_bytes(),
+        );
+
+        create_dir_all(output_directory.join("badges")).expect("Unable to create badges directory");
+        for (file_name, badge) in build_badges(&main_file.items, version.as_deref()) {
+            save_static_file(output_directory, PathBuf::from("badges").join(file_name), badge.to_json().as_bytes());
+        }
+    }
+
+    // The font is saved last, so it can be subset to the characters of every page
+    let font = include_bytes!("../design/Uiua386.ttf");
+    let subset = match options.font {
+        FontStrategy::Subset => subset_font(font, site_characters(output_directory)),
+        _ => None,
+    };
+    match options.font {
+        FontStrategy::Embed | FontStrategy::Subset => {
+            let web_font = woff2_font(subset.as_deref().unwrap_or(font)).expect("The bundled font can be packed as WOFF2");
+            save_static_file(output_directory, WEB_FONT_FILE.into(), &web_font);
+        }
+        FontStrategy::Cdn | FontStrategy::System => {}
+    }
+
+    if let Some(hosting) = options.hosting {
+        let (file_name, config) = hosting.cache_headers(&base_path, &mangler.mangled_filenames());
+        save_static_file(output_directory, file_name.into(), config.as_bytes());
+    }
 
     Ok(())
 }
 
+/// The characters on the pages of the site and in the search index, along with printable ASCII for text the script shows.
+fn site_characters(directory: &Path) -> BTreeSet<char> {
+    let mut characters = (' '..='~').collect::<BTreeSet<_>>();
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in read_dir(directory).expect("Unable to read the output directory").flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "html") || is_search_index_file(&path) {
+                characters.extend(read_to_string(path).unwrap_or_default().chars());
+            }
+        }
+    }
+    characters
+}
+
+fn is_search_index_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().starts_with("search-index."))
+}
+
+/// Where the front page gets its search index from.
+enum SearchIndex {
+    Inline(String),
+    /// The path of the index's file, relative to the root of the site.
+    File(String),
+}
+
 struct FilenameMangler {
     map: HashMap<PathBuf, PathBuf>,
+    /// Derive the names from the content of the files, instead of picking them at random.
+    deterministic: bool,
 }
 
 impl FilenameMangler {
-    fn new() -> Self {
-        Self { map: HashMap::new() }
+    fn new(deterministic: bool) -> Self {
+        Self {
+            map: HashMap::new(),
+            deterministic,
+        }
     }
 
-    fn mangle_filename(&mut self, path: &Path) -> PathBuf {
-        let mut rng = thread_rng();
-        let random_string: String = (0..8).map(|_| rng.sample(Alphanumeric) as char).collect();
+    fn mangle_filename(&mut self, path: &Path, content: &[u8]) -> PathBuf {
+        let suffix: String = match self.deterministic {
+            true => content_hash(content),
+            false => {
+                let mut rng = thread_rng();
+                (0..8).map(|_| rng.sample(Alphanumeric) as char).collect()
+            }
+        };
 
         let mut new_filename = path.file_stem().unwrap().to_os_string();
         new_filename.push(".");
-        new_filename.push(&random_string);
+        new_filename.push(&suffix);
         if let Some(ext) = path.extension() {
             new_filename.push(".");
             new_filename.push(ext);
@@ -76,25 +395,204 @@ impl FilenameMangler {
         mangled_path
     }
 
+    /// The mangled names of all files, relative to the root of the site.
+    fn mangled_filenames(&self) -> Vec<String> {
+        let mut names = self
+            .map
+            .values()
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
     fn get_mangled_filename(&self, original: &Path) -> Option<&PathBuf> {
         self.map.get(original)
     }
 }
 
+/// A library of a workspace, listed on the landing page of its site.
+#[derive(Debug, Clone)]
+pub struct WorkspaceLibrary {
+    pub title: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// The directory of the library's docs, relative to the root of the site.
+    pub directory: String,
+}
+
+/// Writes the landing page of a workspace's site to `output_directory`, with a card for each library linking to its
+/// docs, which are generated into directories of their own next to it.
+pub fn generate_workspace_page(output_directory: &Path, title: &str, libraries: &[WorkspaceLibrary], theme: Theme) -> Result<(), GenerationError> {
+    // Building the workspace again then only changes the landing page when the libraries do
+    let mut mangler = FilenameMangler::new(true);
+
+    // The names of the libraries are the only code on the page, so the font isn't published with it
+    let stylesheet = FontStrategy::System.font_face_css("./") + include_str!("../design/style.css");
+    save_static_file(
+        output_directory,
+        mangler.mangle_filename("style.css".as_ref(), stylesheet.as_bytes()),
+        stylesheet.as_bytes(),
+    );
+
+    let script = theme.script_preamble() + include_str!("../design/script.js");
+    save_static_file(
+        output_directory,
+        mangler.mangle_filename("script.js".as_ref(), script.as_bytes()),
+        script.as_bytes(),
+    );
+
+    save_static_file(
+        output_directory,
+        "index.html".into(),
+        serialize_document(&render_document(generate_workspace_landing(title, libraries, &mangler))).as_bytes(),
+    );
+    Ok(())
+}
+
+fn generate_workspace_landing(title: &str, libraries: &[WorkspaceLibrary], mangler: &FilenameMangler) -> impl IntoView {
+    let stylesheet = mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap().to_string();
+    let script = mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap().to_string();
+
+    let cards = libraries
+        .iter()
+        .map(|library| {
+            view! {
+                <a class="module-card" href=format!("{}/index.html", library.directory)>
+                    <div class="module-card-name mono">{&library.title}</div>
+                    {library
+                        .description
+                        .as_ref()
+                        .map(|description| view! { <div class="module-card-summary">{description.clone()}</div> })}
+                    {library
+                        .version
+                        .as_ref()
+                        .map(|version| view! { <div class="module-card-stats">{format!("Version {}", version)}</div> })}
+                </a>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{title.to_owned()}</title>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+                <link rel="stylesheet" href=stylesheet />
+                <script src=script></script>
+            </head>
+            <body>
+                // The libraries each have a search index of their own, so there's nothing to search on this page
+                <header class="site-header">
+                    <a class="site-header-title" href="index.html">{title.to_owned()}</a>
+                    <button class="theme-toggle" title="Switch between the dark and light theme" aria-label="Switch between the dark and light theme">
+                        "◐"
+                    </button>
+                </header>
+                <div class="workspace-page">
+                    <h1>{title.to_owned()}</h1>
+                    <section class="module-cards" aria-label="Libraries">{cards}</section>
+                    <footer class="site-footer">
+                        <div class="site-footer-generator">
+                            "Generated by "<a href="https://github.com/ekgame/uiua-doc-gen">"uiua-doc-gen"</a>" for Uiua "{uiua::VERSION}
+                        </div>
+                    </footer>
+                </div>
+            </body>
+        </html>
+    }
+}
+
+/// Eight alphanumeric characters from the stable hash of the content.
+fn content_hash(content: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    let mut hash = stable_hash(content);
+    (0..8)
+        .map(|_| {
+            let character = ALPHABET[(hash % ALPHABET.len() as u64) as usize] as char;
+            hash /= ALPHABET.len() as u64;
+            character
+        })
+        .collect()
+}
+
 fn save_static_file(output_directory: &Path, file: PathBuf, content: &[u8]) {
     let destination = output_directory.join(file);
     std::fs::write(destination, content).expect("Unable to write static file");
 }
 
-fn generate_html(summary: DocumentationSummary, mangler: &mut FilenameMangler) -> String {
-    let page_content = generate_page(summary, mangler);
+/// Saves a page of the site, with the base path in front of its links when there is one.
+fn save_page(output_directory: &Path, file: PathBuf, document: &NodeRef, options: &GeneratorOptions) {
+    if let Some(base_path) = &options.base_path {
+        apply_base_path(document, &file, base_path);
+    }
+    save_static_file(output_directory, file, serialize_document(document).as_bytes());
+}
+
+/// The attributes that link to files of the site, which the script fetches or the browser loads.
+const LINK_ATTRIBUTES: [&str; 4] = ["href", "src", "data-src", "data-fragment"];
+
+/// Turns the links of the page at `page`, relative to the root of the site, into paths that start with the base path.
+/// Links within the page, to other sites and to absolute paths are left as they are.
+fn apply_base_path(document: &NodeRef, page: &Path, base_path: &str) {
+    let directory = page
+        .parent()
+        .map(|parent| parent.iter().map(|part| part.to_string_lossy().into_owned()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let resolve = |link: &str| -> Option<String> {
+        let (path, rest) = link.split_at(link.find(['?', '#']).unwrap_or(link.len()));
+        if path.is_empty() || path.starts_with('/') || path.split('/').next().is_some_and(|first| first.contains(':')) {
+            return None;
+        }
+
+        let mut parts = directory.clone();
+        for part in path.split('/') {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    parts.pop();
+                }
+                part => parts.push(part.to_owned()),
+            }
+        }
+        let trailing_slash = match path.ends_with('/') && !parts.is_empty() {
+            true => "/",
+            false => "",
+        };
+        Some(format!("{}{}{}{}", base_path, parts.join("/"), trailing_slash, rest))
+    };
+
+    for element in document.descendants().elements() {
+        let mut attributes = element.attributes.borrow_mut();
+        for name in LINK_ATTRIBUTES {
+            if let Some(link) = attributes.get(name).and_then(resolve) {
+                attributes.insert(name, link);
+            }
+        }
+        // Share pages and the pages of moved items redirect with a link in a `<meta http-equiv="refresh">`
+        let refresh = attributes
+            .get("content")
+            .and_then(|content| content.split_once("url="))
+            .filter(|_| attributes.get("http-equiv") == Some("refresh"))
+            .and_then(|(delay, link)| Some(format!("{}url={}", delay, resolve(link)?)));
+        if let Some(refresh) = refresh {
+            attributes.insert("content", refresh);
+        }
+    }
+}
+
+fn render_document(page_content: impl IntoView + 'static) -> NodeRef {
     let raw_output = leptos::ssr::render_to_string(|| page_content).to_string();
     let document = kuchiki::parse_html().from_utf8().one(raw_output.as_bytes());
 
-    // Remove comments
+    // Remove comments, collected first since detaching a node ends the traversal
     document
         .inclusive_descendants()
         .filter(|node| node.as_comment().is_some())
+        .collect::<Vec<_>>()
+        .into_iter()
         .for_each(|comment| comment.detach());
 
     // Remove data-hk attributes generated by leptos
@@ -102,59 +600,863 @@ fn generate_html(summary: DocumentationSummary, mangler: &mut FilenameMangler) -
         node.attributes.borrow_mut().remove("data-hk");
     });
 
-    // Serialize back to string
+    document
+}
+
+fn serialize_document(document: &NodeRef) -> String {
     let mut result = Vec::new();
     document.serialize(&mut result).unwrap();
     String::from_utf8(result).unwrap()
 }
 
-fn markdown_to_html(markdown: &str) -> String {
-    markdown::to_html_with_options(markdown, &markdown::Options::gfm())
-        .expect("Unable to convert markdown to HTML")
-        .replace('\n', "<br/>")
-}
+/// Copies every item panel into a standalone page with scoped styles, which can be shown
+/// in an iframe, or pasted into another page along with its `<style>` element.
+/// Links back into the site start with `root`, the path of the site's root from the `embed` directory.
+fn extract_panel_embeds(document: &NodeRef, title: &str, font: FontStrategy, root: &str) -> Vec<(String, String)> {
+    let mut embeds = Vec::new();
+
+    for panel in document.select("article.panel.feature[id]").unwrap() {
+        // Module panels hold other panels, which are embedded on their own
+        if panel.as_node().select(".feature").unwrap().count() > 1 {
+            continue;
+        }
+
+        let anchor = panel.attributes.borrow().get("id").unwrap().to_owned();
+        let fragment = kuchiki::parse_html().one(panel.as_node().to_string());
+
+        // Links point back into the site
+        for link in fragment.select("a[href]").unwrap() {
+            let mut attributes = link.attributes.borrow_mut();
+            let href = attributes.get("href").unwrap().to_owned();
+            if href.starts_with('#') {
+                attributes.insert("href", format!("{}index.html{}", root, href));
+            } else if !href.contains("://") && !href.starts_with('/') {
+                attributes.insert("href", format!("{}{}", root, href));
+            }
+        }
+
+        let body = fragment.select_first("body").unwrap();
+        let panel_html = body.as_node().children().map(|child| child.to_string()).collect::<String>();
+        embeds.push((
+            anchor.clone(),
+            format!(
+                "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\"><title>{} - {}</title><base target=\"_top\">\
+                 <style>{} body {{ margin: 0; }}</style></head>\
+                 <body><style>{}</style><div class=\"uiua-doc-embed\">{}</div></body></html>",
+                anchor,
+                title.replace('&', "&amp;").replace('<', "&lt;"),
+                font.font_face_css(root).trim_end(),
+                include_str!("../design/embed.css"),
+                panel_html
+            ),
+        ));
+    }
+
+    embeds
+}
+
+fn share_page_path(anchor: &str) -> PathBuf {
+    PathBuf::from("share").join(format!("{}.html", anchor))
+}
+
+/// A page with the OpenGraph tags of a single item, so links to it unfurl with the item's name and summary on social
+/// platforms and in chats, which don't see the anchor of a link. Readers are redirected to the item's panel.
+fn generate_share_page(summary: &DocumentationSummary, entry: &SearchEntry) -> impl IntoView {
+    let target = format!("../index.html#{}", entry.anchor);
+    let url = summary.documentation.as_ref().map(|documentation| {
+        format!(
+            "{}/{}",
+            documentation.trim_end_matches('/'),
+            share_page_path(&entry.anchor).to_string_lossy().replace('\\', "/")
+        )
+    });
+    // Previews show text, so the Markdown of the summary is reduced to it
+    let description = match &entry.summary {
+        Some(item_summary) => markdown::to_mdast(item_summary, &markdown::ParseOptions::gfm())
+            .map(|node| node.to_string())
+            .unwrap_or_else(|_| item_summary.clone()),
+        None => summary
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("A {} of {}", entry.kind, summary.title)),
+    };
+    let title = format!("{} - {}", entry.qualified_name, summary.title);
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{title.clone()}</title>
+                <meta charset="utf-8" />
+                <meta name="description" content=description.clone() />
+                <meta property="og:type" content="website" />
+                <meta property="og:site_name" content=summary.title.clone() />
+                <meta property="og:title" content=title />
+                <meta property="og:description" content=description />
+                {url.clone().map(|url| view! { <meta property="og:url" content=url /> })}
+                <meta name="twitter:card" content="summary" />
+                <link rel="canonical" href=url.unwrap_or_else(|| target.clone()) />
+                <meta http-equiv="refresh" content=format!("0; url={}", target) />
+            </head>
+            <body>
+                <a href=target>{format!("{} in the docs of {}", entry.qualified_name, summary.title)}</a>
+            </body>
+        </html>
+    }
+}
+
+/// Finds the items at the old places of the `[moved]` table of `uiua-doc.toml`, by the anchors they had there. Moving
+/// a module moves everything in it, and old places that are taken by other items again are left to them.
+fn moved_items(entries: &[SearchEntry], moved: &BTreeMap<String, String>) -> BTreeMap<String, SearchEntry> {
+    let anchors = entries.iter().map(|entry| entry.anchor.as_str()).collect::<HashSet<_>>();
+    let mut items = BTreeMap::new();
+
+    for (old_name, new_name) in moved {
+        for entry in entries {
+            let Some(rest) = entry.qualified_name.strip_prefix(new_name.as_str()) else {
+                continue;
+            };
+            if !rest.is_empty() && !rest.starts_with('~') {
+                continue;
+            }
+
+            // Anchors are the qualified names joined with dots
+            let old_anchor = format!("{}{}", old_name, rest).replace('~', ".");
+            if !anchors.contains(old_anchor.as_str()) {
+                items.entry(old_anchor).or_insert_with(|| entry.clone());
+            }
+        }
+    }
+
+    items
+}
+
+/// A page at the old place of a renamed or moved item, which redirects readers to the page at its new place, so
+/// bookmarks and links to it keep working.
+fn generate_redirect_page(title: &str, entry: &SearchEntry, target: String) -> impl IntoView {
+    let page_title = format!("{} - {}", entry.qualified_name, title);
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{page_title}</title>
+                <meta charset="utf-8" />
+                <meta name="robots" content="noindex" />
+                <link rel="canonical" href=target.clone() />
+                <meta http-equiv="refresh" content=format!("0; url={}", target) />
+            </head>
+            <body>
+                <a href=target>{format!("{} in the docs of {}", entry.qualified_name, title)}</a>
+            </body>
+        </html>
+    }
+}
+
+fn fragment_path(anchor: &str) -> PathBuf {
+    PathBuf::from("fragments").join(format!("{}.html", anchor))
+}
+
+/// Links the first occurrence of every glossary term in each rendered doc comment to its definition.
+fn link_glossary_terms(document: &NodeRef, glossary: &Glossary) {
+    // Longer terms win when several start at the same place, like "stack frame" over "stack"
+    let mut terms = glossary.terms.iter().collect::<Vec<_>>();
+    terms.sort_by_key(|term| std::cmp::Reverse(term.term.len()));
+
+    let is_excluded = |node: &NodeRef| {
+        node.ancestors().any(|ancestor| {
+            ancestor
+                .as_element()
+                .is_some_and(|element| matches!(&*element.name.local, "a" | "code" | "pre" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"))
+        })
+    };
+
+    for block in document.select(".feature-documentation, .rendered-documentation").unwrap() {
+        let mut linked = HashSet::new();
+        let text_nodes = block
+            .as_node()
+            .descendants()
+            .filter(|node| node.as_text().is_some() && !is_excluded(node))
+            .collect::<Vec<_>>();
+
+        for mut node in text_nodes {
+            loop {
+                let text = node.as_text().unwrap().borrow().clone();
+                let first_match = terms
+                    .iter()
+                    .filter(|term| !linked.contains(&term.term))
+                    .filter_map(|term| term.find_in(&text).map(|range| (range, *term)))
+                    .min_by_key(|(range, _)| range.start);
+                let Some((range, term)) = first_match else {
+                    break;
+                };
+
+                let link = html_element(
+                    local_name!("a"),
+                    &[("class", "glossary-term"), ("href", &term.url()), ("title", &term.definition)],
+                );
+                link.append(NodeRef::new_text(&text[range.clone()]));
+                let rest = NodeRef::new_text(&text[range.end..]);
+                *node.as_text().unwrap().borrow_mut() = text[..range.start].to_owned();
+                node.insert_after(link.clone());
+                link.insert_after(rest.clone());
+
+                linked.insert(term.term.clone());
+                node = rest;
+            }
+        }
+    }
+}
+
+/// Moves the bodies of item panels out of the page, leaving their headers and signatures
+/// in place. Returns the removed markup of every panel, keyed by the panel's anchor.
+fn extract_panel_fragments(document: &NodeRef) -> Vec<(String, String)> {
+    let panels = document.select("article.panel.feature[id]").unwrap().collect::<Vec<_>>();
+    let mut fragments = Vec::new();
+
+    for panel in panels {
+        // Module panels hold other panels, which are split up on their own
+        if panel.as_node().select(".feature").unwrap().count() > 1 {
+            continue;
+        }
+
+        // The heading and the signature stay, so the panel can still be skimmed
+        let is_kept = |child: &NodeRef| match child.as_element() {
+            Some(element) => {
+                let class = element.attributes.borrow().get("class").unwrap_or_default().to_owned();
+                class
+                    .split_whitespace()
+                    .any(|class| class == "panel-heading" || class == "function-summary" || class == "breadcrumbs")
+            }
+            None => child.text_contents().trim().is_empty(),
+        };
+        let body = panel.as_node().children().filter(|child| !is_kept(child)).collect::<Vec<_>>();
+        if body.is_empty() {
+            continue;
+        }
+
+        let anchor = panel.attributes.borrow().get("id").unwrap().to_owned();
+        let fragment = body.iter().map(|child| child.to_string()).collect::<String>();
+        body.iter().for_each(|child| child.detach());
+
+        let fragment_url = fragment_path(&anchor).to_string_lossy().replace('\\', "/");
+        let placeholder = html_element(local_name!("details"), &[("class", "lazy-panel"), ("data-fragment", &fragment_url)]);
+        let summary = html_element(local_name!("summary"), &[]);
+        summary.append(NodeRef::new_text("Details"));
+        placeholder.append(summary);
+        // Without scripts the fragment can't be loaded into the page, but it can still be opened
+        let body = html_element(local_name!("div"), &[("class", "lazy-panel-body")]);
+        let noscript = html_element(local_name!("noscript"), &[]);
+        let link = html_element(local_name!("a"), &[("href", &fragment_url)]);
+        link.append(NodeRef::new_text("Open the details"));
+        noscript.append(link);
+        body.append(noscript);
+        placeholder.append(body);
+        panel.as_node().append(placeholder);
+
+        fragments.push((anchor, fragment));
+    }
+
+    fragments
+}
+
+fn html_element(name: LocalName, attributes: &[(&str, &str)]) -> NodeRef {
+    NodeRef::new_element(
+        QualName::new(None, ns!(html), name),
+        attributes.iter().map(|(name, value)| {
+            (
+                ExpandedName::new(ns!(), LocalName::from(*name)),
+                Attribute {
+                    prefix: None,
+                    value: value.to_string(),
+                },
+            )
+        }),
+    )
+}
+
+fn markdown_to_html(hooks: &MarkdownHooks, markdown: &str) -> String {
+    hooks.after_render(hooks.to_html(markdown).replace('\n', "<br/>"))
+}
+
+/// Unlike doc comments, Markdown files are written with regular paragraph breaks in mind.
+fn readme_to_html(hooks: &MarkdownHooks, markdown: &str) -> String {
+    hooks.render(markdown)
+}
+
+fn generate_page(
+    summary: DocumentationSummary,
+    search_index: SearchIndex,
+    versions: &VersionMenu,
+    mangler: &FilenameMangler,
+    options: &GeneratorOptions,
+) -> impl IntoView {
+    let stylesheet = mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap().to_string();
+    let script = mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap().to_string();
+    let search_index = match search_index {
+        SearchIndex::Inline(json) => view! { <script type="application/json" id="search-index" inner_html=json></script> },
+        SearchIndex::File(path) => view! { <script type="application/json" id="search-index" data-src=path></script> },
+    };
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{&summary.title}</title>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+                {summary.description.as_ref().map(|description| view! { <meta name="description" content=description /> })}
+                <link rel="stylesheet" href=stylesheet />
+                <script src=script></script>
+            </head>
+            <body>
+                <div class="page-layout">
+                    {generate_site_header(&summary.title, versions, "", true)}
+                    <div class="container">
+                        <div class="sidebar">{generate_sidebar(&summary, options)}</div>
+                        <div class="content">
+                            <div class="content-wrapper">
+                                <h1>{&summary.title}</h1>
+                                <div class="panel">
+                                    {generate_import_snippet(&summary.import_snippet)}
+                                    {generate_permissions(&summary.permissions)}
+                                </div>
+                                {generate_content(&summary, options)}
+                                {generate_footer(&summary)}
+                            </div>
+                        </div>
+                    </div>
+                </div>
+                {search_index}
+            </body>
+        </html>
+    }
+}
+
+/// The version and description of the library, if it has them, and what generated the site.
+fn generate_footer(summary: &DocumentationSummary) -> impl IntoView {
+    let title = match &summary.version {
+        Some(version) => format!("{} {}", summary.title, version),
+        None => summary.title.clone(),
+    };
+
+    view! {
+        <footer class="site-footer">
+            <div class="site-footer-title">{title}</div>
+            {summary.description.as_ref().map(|description| view! { <div class="site-footer-description">{description}</div> })}
+            <div class="site-footer-links">
+                <a href=CHEATSHEET_PAGE>"Printable reference sheet"</a>
+                <a href=PRIMITIVES_PAGE>"Primitive usage"</a>
+            </div>
+            <div class="site-footer-generator">
+                "Generated by "<a href="https://github.com/ekgame/uiua-doc-gen">"uiua-doc-gen"</a>" for Uiua "{uiua::VERSION}
+            </div>
+        </footer>
+    }
+}
+
+/// A page with the highlighted code of one of the library's files, with an anchor on every line.
+fn generate_source_page(
+    title: &str,
+    versions: &VersionMenu,
+    source: &FileContent,
+    mangler: &FilenameMangler,
+    options: &GeneratorOptions,
+) -> impl IntoView {
+    // Source pages are nested in `src/`, so static files are referenced from the root
+    let root = "../".repeat(source.relative_path.matches('/').count() + 1);
+    let stylesheet = format!(
+        "{}{}",
+        root,
+        mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap()
+    );
+    let script = format!(
+        "{}{}",
+        root,
+        mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap()
+    );
+
+    let lines = format_source_lines(&source.source, options.wrap_code)
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let number = index + 1;
+            view! {
+                <div class="code-line" id=format!("L{}", number)>
+                    <a class="line-number" href=format!("#L{}", number)>
+                        {number}
+                    </a>
+                    <span inner_html=line></span>
+                </div>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{format!("{} - {}", source.relative_path, title)}</title>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+                <link rel="stylesheet" href=stylesheet />
+                <script src=script></script>
+            </head>
+            <body>
+                {generate_site_header(title, versions, &root, false)}
+                <div class="source-page">
+                    <h1>
+                        <a href=format!("{}index.html", root)>{title.to_owned()}</a>
+                        " / "
+                        <span class="mono">{source.relative_path.clone()}</span>
+                    </h1>
+                    <code class="source-code source-file">{lines}</code>
+                </div>
+            </body>
+        </html>
+    }
+}
+
+/// The glossary page, with every term under a heading of its own that linked terms point to.
+fn generate_glossary_page(
+    title: &str,
+    versions: &VersionMenu,
+    glossary: &Glossary,
+    mangler: &FilenameMangler,
+    options: &GeneratorOptions,
+) -> impl IntoView {
+    let stylesheet = mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap().to_string();
+    let script = mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap().to_string();
+
+    let terms = glossary
+        .terms
+        .iter()
+        .map(|term| {
+            view! {
+                <dt id=term.anchor()>{&term.term}</dt>
+                <dd inner_html=readme_to_html(&options.markdown_hooks, &term.definition)></dd>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{format!("Glossary - {}", title)}</title>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+                <link rel="stylesheet" href=stylesheet />
+                <script src=script></script>
+            </head>
+            <body>
+                {generate_site_header(title, versions, "", false)}
+                <div class="glossary-page">
+                    <h1>
+                        <a href="index.html">{title.to_owned()}</a>
+                        " / Glossary"
+                    </h1>
+                    <dl class="glossary">{terms}</dl>
+                </div>
+            </body>
+        </html>
+    }
+}
+
+/// A printable page listing every documented binding on a line, grouped by module and set in columns.
+fn generate_cheatsheet_page(title: &str, versions: &VersionMenu, items: &[ItemContent], mangler: &FilenameMangler) -> impl IntoView {
+    let stylesheet = mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap().to_string();
+    let script = mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap().to_string();
+
+    let mut groups = Vec::new();
+    collect_cheatsheet_groups(items, None, &mut groups);
+    let groups = groups
+        .into_iter()
+        .map(|(heading, bindings)| {
+            let entries = bindings
+                .into_iter()
+                .map(|binding| {
+                    let (color_class, signature) = match &binding.kind {
+                        BindingType::Function(function) => (
+                            function.signature.as_ref().map_or("", Colored::color_class),
+                            function.signature.as_ref().map(ToString::to_string),
+                        ),
+                        BindingType::IndexMacro(index_macro) => (index_macro.color_class(), None),
+                        BindingType::CodeMacro(_) => ("monadic-modifier", None),
+                        BindingType::Const(_) => ("", None),
+                    };
+                    let anchor = anchor_id(&binding.module_path, &binding.name);
+                    view! {
+                        <div class="cheatsheet-entry">
+                            <a class="mono" href=format!("index.html#{}", anchor)>
+                                <span class=color_class>{&binding.name}</span>
+                            </a>
+                            {signature.map(|signature| view! { " " <span class="cheatsheet-signature mono">{signature}</span> })}
+                            {binding.summary().map(|summary| view! { " " <span class="cheatsheet-summary">{summary.to_owned()}</span> })}
+                        </div>
+                    }
+                })
+                .collect_view();
+            view! {
+                <section class="cheatsheet-group">
+                    {heading.map(|heading| view! { <h2 class="mono">{heading}</h2> })}
+                    {entries}
+                </section>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{format!("Reference sheet - {}", title)}</title>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+                <link rel="stylesheet" href=stylesheet />
+                <script src=script></script>
+            </head>
+            <body>
+                {generate_site_header(title, versions, "", false)}
+                <div class="cheatsheet-page">
+                    <h1>
+                        <a href="index.html">{title.to_owned()}</a>
+                        " / Reference sheet"
+                    </h1>
+                    <div class="cheatsheet">{groups}</div>
+                </div>
+            </body>
+        </html>
+    }
+}
+
+/// Every item of the docs in a single list sorted by qualified name, like the `all.html` page of rustdoc.
+fn generate_all_items_page(title: &str, versions: &VersionMenu, entries: &[SearchEntry], mangler: &FilenameMangler) -> impl IntoView {
+    let stylesheet = mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap().to_string();
+    let script = mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap().to_string();
+
+    let mut entries = entries.iter().collect::<Vec<_>>();
+    entries.sort_by_cached_key(|entry| (entry.qualified_name.to_lowercase(), entry.qualified_name.clone()));
+    let rows = entries
+        .into_iter()
+        .map(|entry| {
+            view! {
+                <tr>
+                    <td>
+                        <a class="mono" href=format!("index.html#{}", entry.anchor)>{&entry.qualified_name}</a>
+                    </td>
+                    <td class="all-items-kind">
+                        {entry.kind}
+                        {entry.badges.iter().any(|badge| badge == "private").then(|| view! { " " <span class="badge private-badge">"private"</span> })}
+                    </td>
+                    <td class="all-items-summary">{entry.summary.clone()}</td>
+                </tr>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{format!("All items - {}", title)}</title>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+                <link rel="stylesheet" href=stylesheet />
+                <script src=script></script>
+            </head>
+            <body>
+                {generate_site_header(title, versions, "", false)}
+                <div class="all-items-page">
+                    <h1>
+                        <a href="index.html">{title.to_owned()}</a>
+                        " / All items"
+                    </h1>
+                    <table class="all-items">
+                        <tbody>{rows}</tbody>
+                    </table>
+                </div>
+            </body>
+        </html>
+    }
+}
+
+/// An index from every primitive the library uses to the documented bindings using it, in the order of the primitives.
+fn generate_primitives_page(title: &str, versions: &VersionMenu, items: &[ItemContent], mangler: &FilenameMangler) -> impl IntoView {
+    let stylesheet = mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap().to_string();
+    let script = mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap().to_string();
+
+    let mut bindings = Vec::new();
+    collect_documented_bindings(items, &mut bindings);
+    let usages = Primitive::all()
+        .map(|primitive| {
+            let users = bindings
+                .iter()
+                .filter(|binding| binding.primitives.contains(&primitive))
+                .copied()
+                .collect::<Vec<_>>();
+            (primitive, users)
+        })
+        .filter(|(_, users)| !users.is_empty())
+        .collect::<Vec<_>>();
+
+    let glyph = |primitive: Primitive| primitive.glyph().map_or_else(|| primitive.name().to_owned(), String::from);
+    let jump_links = usages
+        .iter()
+        .map(|(primitive, _)| {
+            view! {
+                <a class=format!("mono {}", prim_sig_class(*primitive, None)) href=format!("#{}", primitive_anchor(*primitive)) title=primitive.name()>
+                    {glyph(*primitive)}
+                </a>
+            }
+        })
+        .collect_view();
+    let sections = usages
+        .iter()
+        .map(|(primitive, users)| {
+            let entries = users
+                .iter()
+                .map(|binding| {
+                    let anchor = anchor_id(&binding.module_path, &binding.name);
+                    view! {
+                        <li>
+                            <a class="mono" href=format!("index.html#{}", anchor)>
+                                {qualified_name(&binding.module_path, &binding.name)}
+                            </a>
+                            {binding.summary().map(|summary| view! { " " <span class="primitive-usage-summary">{summary.to_owned()}</span> })}
+                        </li>
+                    }
+                })
+                .collect_view();
+            view! {
+                <section class="primitive-usage" id=primitive_anchor(*primitive)>
+                    <h2>
+                        <span class=format!("mono {}", prim_sig_class(*primitive, None))>{glyph(*primitive)}</span>
+                        " " {primitive.name()} " " <span class="badge">{users.len()}</span>
+                    </h2>
+                    <ul>{entries}</ul>
+                </section>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <title>{format!("Primitive usage - {}", title)}</title>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+                <link rel="stylesheet" href=stylesheet />
+                <script src=script></script>
+            </head>
+            <body>
+                {generate_site_header(title, versions, "", false)}
+                <div class="primitives-page">
+                    <h1>
+                        <a href="index.html">{title.to_owned()}</a>
+                        " / Primitive usage"
+                    </h1>
+                    <nav class="primitive-jump-links" aria-label="Primitives">{jump_links}</nav>
+                    {sections}
+                </div>
+            </body>
+        </html>
+    }
+}
+
+fn primitive_anchor(primitive: Primitive) -> String {
+    format!("prim-{}", primitive.name().replace(' ', "-"))
+}
+
+fn collect_documented_bindings<'a>(items: &'a [ItemContent], bindings: &mut Vec<&'a BindingDefinition>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.is_documented() => bindings.push(binding),
+            ItemContent::Module(module) => collect_documented_bindings(&module.items, bindings),
+            _ => {}
+        }
+    }
+}
+
+/// The documented bindings of each module, headed by the module's qualified name.
+fn collect_cheatsheet_groups<'a>(items: &'a [ItemContent], heading: Option<String>, groups: &mut Vec<(Option<String>, Vec<&'a BindingDefinition>)>) {
+    let bindings = items
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Binding(binding) if binding.is_documented() => Some(binding),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !bindings.is_empty() {
+        groups.push((heading, bindings));
+    }
+
+    for item in items {
+        if let ItemContent::Module(module) = item {
+            collect_cheatsheet_groups(&module.items, Some(qualified_name(&module.module_path, &module.name)), groups);
+        }
+    }
+}
+
+/// A notebook page for an example, with the output of every code cell shown under it.
+fn generate_example_page(
+    title: &str,
+    versions: &VersionMenu,
+    example: &ExampleNotebook,
+    mangler: &FilenameMangler,
+    options: &GeneratorOptions,
+) -> impl IntoView {
+    let stylesheet = format!("../{}", mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap());
+    let script = format!("../{}", mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap());
+    let markdown_hooks = options.markdown_hooks.on_page("../");
 
-fn generate_page(summary: DocumentationSummary, mangler: &mut FilenameMangler) -> impl IntoView {
-    let stylesheet = mangler.get_mangled_filename("style.css".as_ref()).unwrap().to_str().unwrap().to_string();
-    let script = mangler.get_mangled_filename("script.js".as_ref()).unwrap().to_str().unwrap().to_string();
+    let cells = example
+        .cells
+        .iter()
+        .map(|cell| match cell {
+            NotebookCell::Prose(prose) => view! { <div class="notebook-prose" inner_html=readme_to_html(&markdown_hooks, prose)></div> }.into_view(),
+            NotebookCell::Code {
+                code,
+                outputs,
+                error,
+                expect_error,
+            } => view! {
+                <div class="notebook-cell">
+                    <code class="source-code" inner_html=format_source_code(code, options.wrap_code)></code>
+                    {outputs
+                        .iter()
+                        .map(|output| view! { <pre class="notebook-output">{output}</pre> })
+                        .collect_view()}
+                    {error
+                        .as_ref()
+                        .map(|error| match expect_error {
+                            true => view! {
+                                <div class="notebook-expected-error">
+                                    <div class="notebook-expected-error-label">"Fails with"</div>
+                                    <pre class="notebook-error">{error}</pre>
+                                </div>
+                            }
+                            .into_view(),
+                            false => view! { <pre class="notebook-error">{error}</pre> }.into_view(),
+                        })}
+                </div>
+            }
+            .into_view(),
+        })
+        .collect_view();
 
     view! {
         <!DOCTYPE html>
         <html lang="en">
             <head>
-                <title>{&summary.title}</title>
+                <title>{format!("{} - {}", example.heading(), title)}</title>
                 <meta charset="utf-8" />
                 <meta name="viewport" content="width=device-width, initial-scale=1.0" />
                 <link rel="stylesheet" href=stylesheet />
                 <script src=script></script>
             </head>
             <body>
-                <div class="mobile-container">
-                    <div class="mobile-nav">
-                        <div class="hamburger">
+                {generate_site_header(title, versions, "../", false)}
+                <div class="notebook-page">
+                    <h1>
+                        <a href="../index.html">{title.to_owned()}</a>
+                        " / "
+                        {match &example.title {
+                            Some(example_title) => view! { <span>{example_title.clone()}</span> },
+                            None => view! { <span class="mono">{example.name.clone()}</span> },
+                        }}
+                    </h1>
+                    {cells}
+                </div>
+            </body>
+        </html>
+    }
+}
+
+/// The version in the header, with links to the docs of the library's other releases when their URL is known.
+#[derive(Debug, Clone, Default)]
+struct VersionMenu {
+    current: Option<String>,
+    /// The names and URLs of the other releases, newest first.
+    others: Vec<(String, String)>,
+}
+
+impl VersionMenu {
+    fn new(version: Option<&str>, api: &ApiSnapshot, version_url: Option<&str>) -> Self {
+        let others = match version_url {
+            Some(version_url) => api
+                .releases
+                .iter()
+                .filter(|release| **release != api.release && Some(release.as_str()) != version)
+                .map(|release| (release.clone(), version_url.replace("{version}", release)))
+                .collect(),
+            None => Vec::new(),
+        };
+        // Releases named after dates stand in for a version when there's a menu to show
+        let current = version.map(str::to_owned).or_else(|| (!others.is_empty()).then(|| api.release.clone()));
+        VersionMenu { current, others }
+    }
+}
+
+fn generate_version_menu(versions: &VersionMenu) -> Option<impl IntoView> {
+    let current = versions.current.clone()?;
+    Some(match versions.others.is_empty() {
+        true => view! { <span class="badge site-header-version">{current}</span> }.into_view(),
+        false => view! {
+            <details class="site-header-version version-menu">
+                <summary class="badge" title="Other versions">{current}</summary>
+                <ul class="version-menu-list">
+                    {versions
+                        .others
+                        .iter()
+                        .map(|(release, url)| view! { <li><a href=url.clone()>{release.clone()}</a></li> })
+                        .collect_view()}
+                </ul>
+            </details>
+        }
+        .into_view(),
+    })
+}
+
+/// The bar at the top of every page, with the library's name and version, the search box and the theme toggle.
+///
+/// `root` is the path from the page to the root of the site. Search results are filled in by the script from
+/// the index embedded in the front page, so on the other pages the search box takes the query there.
+fn generate_site_header(title: &str, versions: &VersionMenu, root: &str, menu: bool) -> impl IntoView {
+    view! {
+        <header class="site-header">
+            {menu
+                .then(|| {
+                    view! {
+                        <div class="hamburger" role="button" aria-label="Show the menu">
                             <div class="line"></div>
                             <div class="line"></div>
                             <div class="line"></div>
                         </div>
-                        <h1>{&summary.title}</h1>
-                    </div>
-                    <div class="container">
-                        <div class="sidebar">{generate_sidebar(&summary)}</div>
-                        <div class="content">
-                            <div class="content-wrapper">
-                                <h1 class="mobile-hidden">{&summary.title}</h1>
-                                {generate_content(&summary)}
-                            </div>
-                        </div>
-                    </div>
-                </div>
-            </body>
-        </html>
+                    }
+                })}
+            <a class="site-header-title" href=format!("{}index.html", root)>{title.to_owned()}</a>
+            {generate_version_menu(versions)}
+            <form class="search" action=format!("{}index.html", root) role="search">
+                <input
+                    type="search"
+                    name="search"
+                    class="search-input"
+                    placeholder="Search, e.g. Geo.Area or |2"
+                    aria-label="Search the documentation"
+                />
+                <ul class="search-results"></ul>
+            </form>
+            <button class="theme-toggle" title="Switch between the dark and light theme" aria-label="Switch between the dark and light theme">
+                "◐"
+            </button>
+        </header>
     }
 }
 
-fn generate_sidebar(summary: &DocumentationSummary) -> impl IntoView {
-    summary
+fn generate_sidebar(summary: &DocumentationSummary, options: &GeneratorOptions) -> impl IntoView {
+    let sections = summary
         .sections
         .iter()
         .map(|section| {
@@ -174,67 +1476,368 @@ fn generate_sidebar(summary: &DocumentationSummary) -> impl IntoView {
                                 }
                             })
                             .collect_view()}
-                        {section
-                            .content
-                            .iter()
-                            .filter(|item| matches!(&item.content, RenderingContent::Items(_)))
+                        {sidebar_items(section, options.module_order)
+                            .into_iter()
+                            .filter(|item| matches!(&item.content, RenderingContent::Items(_) | RenderingContent::ModifierTable(_)))
                             .map(|link| match &link.content {
-                                RenderingContent::Items(items) => {
-                                    view! {
-                                        <li>
-                                            <a href=format!(
-                                                "#{}",
-                                                &items.title.link_id,
-                                            )>{&items.title.title}</a>
-                                        </li>
-                                    }
-                                }
-                                _ => view! { <li>"N/A"</li> },
+                                RenderingContent::Items(items) | RenderingContent::ModifierTable(items) => generate_minimap_group(items).into_view(),
+                                _ => view! { <li>"N/A"</li> }.into_view(),
                             })
                             .collect_view()}
                     </ul>
                 </div>
             }
         })
-        .collect_view()
+        .collect_view();
+
+    view! {
+        {sections}
+        {generate_arity_histogram(&summary.arity_histogram)}
+        <div class="sidebar-footer">
+            <a href=ALL_ITEMS_PAGE>"All items"</a>
+        </div>
+    }
+}
+
+/// The items of a section in the order they are listed in the sidebar.
+fn sidebar_items(section: &DocumentationSection, module_order: ModuleOrder) -> Vec<&RenderingItem> {
+    let items = section.content.iter().collect::<Vec<_>>();
+    let modules = items
+        .iter()
+        .filter_map(|item| match &item.content {
+            RenderingContent::Items(ContentItems { items, .. }) => match items.first() {
+                Some(ItemContent::Module(module)) => Some(module),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !matches!(section.section_type, SectionType::Modules) || modules.len() != items.len() {
+        return items;
+    }
+
+    let order = match module_order {
+        ModuleOrder::Source => return items,
+        ModuleOrder::Alphabetical => {
+            let mut order = (0..modules.len()).collect::<Vec<_>>();
+            order.sort_by_key(|index| modules[*index].name.to_lowercase());
+            order
+        }
+        ModuleOrder::Dependencies => dependency_order(&modules),
+    };
+    order.into_iter().map(|index| items[index]).collect()
+}
+
+/// A sidebar entry for a group of items with its item count and links to its items. Without scripts every group is
+/// expanded; the script collapses them, and only keeps the rows in view of long lists in the document.
+fn generate_minimap_group(items: &ContentItems) -> impl IntoView {
+    let entries = minimap_entries(false, &items.items);
+    let count = entries.len();
+    let links = entries
+        .into_iter()
+        .map(|(name, anchor)| view! { <a class="minimap-item mono" href=format!("#{}", anchor)>{name}</a> })
+        .collect_view();
+
+    view! {
+        <li class="minimap-group">
+            <div class="minimap-heading">
+                <button class="minimap-toggle" aria-label="Show items" aria-expanded="false">
+                    "▸"
+                </button>
+                <a href=format!("#{}", &items.title.link_id)>{&items.title.title}</a>
+                <span class="minimap-count">{count}</span>
+            </div>
+            <div class="minimap-items">{links}</div>
+        </li>
+    }
+}
+
+/// Names and anchors of the items in a group; top-level modules are listed by their contents.
+fn minimap_entries(nested: bool, items: &[ItemContent]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .flat_map(|item| {
+            let (module_path, name) = match item {
+                ItemContent::Module(module) if !nested => return minimap_entries(true, &module.items),
+                ItemContent::Binding(binding) => (&binding.module_path, &binding.name),
+                ItemContent::Module(module) => (&module.module_path, &module.name),
+                ItemContent::Variant(variant) => (&variant.module_path, &variant.name),
+                ItemContent::Data(DataDefinition {
+                    name: Some(name),
+                    module_path,
+                    ..
+                }) => (module_path, name),
+                _ => return Vec::new(),
+            };
+            vec![(name.clone(), anchor_id(module_path, name))]
+        })
+        .collect()
+}
+
+fn generate_arity_histogram(buckets: &[ArityBucket]) -> impl IntoView {
+    let max_count = buckets.iter().map(|bucket| bucket.count).max().unwrap_or(0);
+    if max_count == 0 {
+        return None;
+    }
+
+    let bars = buckets
+        .iter()
+        .map(|bucket| {
+            let color_class = SignatureInfo {
+                inputs: bucket.inputs,
+                outputs: 1,
+            }
+            .color_class();
+            let style = format!("height: {}%", bucket.count * 100 / max_count);
+            let label = format!(
+                "{} {} with {} {}",
+                bucket.count,
+                pluralize(bucket.count, "function", "functions"),
+                bucket.inputs,
+                pluralize(bucket.inputs, "input", "inputs"),
+            );
+
+            if bucket.count > 0 {
+                view! {
+                    <a class=format!("arity-bar {}", color_class) href=format!("#{}", bucket.link_id) title=label>
+                        <span class="arity-count">{bucket.count}</span>
+                        <div class="bar-track">
+                            <div class="bar" style=style></div>
+                        </div>
+                        <span class="arity-inputs">{bucket.inputs}</span>
+                    </a>
+                }
+                .into_view()
+            } else {
+                view! {
+                    <span class="arity-bar empty" title=label>
+                        <span class="arity-count"></span>
+                        <div class="bar-track">
+                            <div class="bar"></div>
+                        </div>
+                        <span class="arity-inputs">{bucket.inputs}</span>
+                    </span>
+                }
+                .into_view()
+            }
+        })
+        .collect_view();
+
+    Some(view! {
+        <div class="sidebar-section">
+            <div class="section-name">"Function arities"</div>
+            <div class="arity-histogram mono">{bars}</div>
+        </div>
+    })
 }
 
-fn generate_content(summary: &DocumentationSummary) -> impl IntoView {
+fn generate_content(summary: &DocumentationSummary, options: &GeneratorOptions) -> impl IntoView {
     summary
         .sections
         .iter()
-        .map(|section| section.content.iter().map(|item| generate_rendering_item(item)).collect_view())
+        .map(|section| {
+            let cards = matches!(section.section_type, SectionType::Modules)
+                .then(|| generate_module_cards(&summary.module_cards, section, options.module_order));
+            view! {
+                {section_description(section.description.as_deref())}
+                {cards}
+                {section.content.iter().map(|item| generate_rendering_item(item, options)).collect_view()}
+            }
+        })
         .collect_view()
 }
 
-fn generate_rendering_item(item: &RenderingItem) -> impl IntoView {
+/// An overview of the modules before their sections, in the order of the sidebar.
+fn generate_module_cards(cards: &[ModuleCard], section: &DocumentationSection, module_order: ModuleOrder) -> impl IntoView {
+    let cards = sidebar_items(section, module_order)
+        .into_iter()
+        .filter_map(|item| match &item.content {
+            RenderingContent::Items(items) => cards.iter().find(|card| card.link_id == items.title.link_id),
+            _ => None,
+        })
+        .map(|card| {
+            view! {
+                <a class="module-card" href=format!("#{}", card.link_id)>
+                    <div class="module-card-name mono">{&card.name}</div>
+                    <div class="module-card-summary">{card.summary.clone()}</div>
+                    <div class="module-card-stats">
+                        <span class="module-card-count">
+                            {format!("{} {}", card.binding_count, pluralize(card.binding_count, "binding", "bindings"))}
+                        </span>
+                        {generate_arity_sparkline(&card.arity_mix)}
+                    </div>
+                </a>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <section class="module-cards" aria-label="Modules">{cards}</section>
+    }
+}
+
+/// A small bar chart of how many functions take each number of inputs.
+fn generate_arity_sparkline(buckets: &[ArityBucket]) -> impl IntoView {
+    let max_count = buckets.iter().map(|bucket| bucket.count).max().unwrap_or(0);
+    if max_count == 0 {
+        return None;
+    }
+
+    let label = buckets
+        .iter()
+        .filter(|bucket| bucket.count > 0)
+        .map(|bucket| format!("{} with {} {}", bucket.count, bucket.inputs, pluralize(bucket.inputs, "input", "inputs")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let bars = buckets
+        .iter()
+        .map(|bucket| {
+            let color_class = SignatureInfo {
+                inputs: bucket.inputs,
+                outputs: 1,
+            }
+            .color_class();
+            view! {
+                <span class=format!("sparkline-bar {}", color_class) style=format!("height: {}%", bucket.count * 100 / max_count)></span>
+            }
+        })
+        .collect_view();
+
+    Some(view! {
+        <span class="arity-sparkline" role="img" aria-label=format!("Functions: {}", label) title=format!("Functions: {}", label)>
+            {bars}
+        </span>
+    })
+}
+
+fn generate_rendering_item(item: &RenderingItem, options: &GeneratorOptions) -> impl IntoView {
     match &item.content {
-        RenderingContent::RenderedDocumentation(ref content) => view! { <div class="panel" inner_html=content></div> },
+        RenderingContent::RenderedDocumentation(ref content) => {
+            view! { <div class="panel rendered-documentation" inner_html=content></div> }.into_view()
+        }
         RenderingContent::Items(ref item) => view! {
-            <div>
+            <section aria-labelledby=&item.title.link_id>
                 <h2 id=&item.title.link_id>{&item.title.title}</h2>
-                {item.items.iter().map(|item| generate_content_item(None, item)).collect_view()}
-            </div>
-        },
+                {section_description(item.description.as_deref())}
+                {item.import_snippet.as_deref().map(generate_import_snippet)}
+                {item.items.iter().map(|item| generate_content_item(options, None, item)).collect_view()}
+            </section>
+        }
+        .into_view(),
+        RenderingContent::ModifierTable(ref table) => view! {
+            <section aria-labelledby=&table.title.link_id>
+                <h2 id=&table.title.link_id>{&table.title.title}</h2>
+                {section_description(table.description.as_deref())}
+                <div class="panel">
+                    <table class="modifier-table">
+                        <thead>
+                            <tr>
+                                <th>"Modifier"</th>
+                                <th>"Kind"</th>
+                                <th>"Operands"</th>
+                                <th>"Summary"</th>
+                            </tr>
+                        </thead>
+                        <tbody>{table.items.iter().map(generate_modifier_row).collect_view()}</tbody>
+                    </table>
+                </div>
+            </section>
+        }
+        .into_view(),
+    }
+}
+
+fn section_description(description: Option<&str>) -> impl IntoView {
+    description.map(|description| view! { <div class="section-description" inner_html=description.to_owned()></div> })
+}
+
+fn generate_import_snippet(snippet: &str) -> impl IntoView {
+    view! {
+        <div class="import-snippet">
+            <code>{snippet.to_owned()}</code>
+            <button class="copy-button" data-copy=snippet.to_owned() title="Copy import to clipboard">
+                "Copy"
+            </button>
+        </div>
+    }
+}
+
+/// What the library's code can do on the host, so readers can decide whether to trust it before importing it.
+fn generate_permissions(permissions: &[Permission]) -> impl IntoView {
+    if permissions.is_empty() {
+        return view! { <p class="permissions">"This library doesn't use any system functions."</p> }.into_view();
+    }
+
+    let descriptions = permissions.iter().map(|permission| permission.description.as_str()).collect::<Vec<_>>();
+    let sentence = match descriptions.as_slice() {
+        [rest @ .., last] if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => descriptions.join(""),
+    };
+    let rows = permissions
+        .iter()
+        .map(|permission| {
+            view! {
+                <li>
+                    {permission.description.clone()} ": "
+                    <span class="mono">{permission.functions.join(" ")}</span>
+                </li>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <details class="permissions">
+            <summary>
+                <strong>"Permissions: "</strong>
+                {format!("this library {}.", sentence)}
+            </summary>
+            <ul>{rows}</ul>
+        </details>
     }
+    .into_view()
+}
+
+fn generate_modifier_row(item: &ItemContent) -> impl IntoView {
+    let ItemContent::Binding(binding) = item else {
+        return None;
+    };
+
+    let (kind, operands, color_class) = match &binding.kind {
+        BindingType::IndexMacro(index_macro) => ("index macro", index_macro.arguments, index_macro.color_class()),
+        BindingType::CodeMacro(code_macro) => ("code macro", code_macro.arguments, "monadic-modifier"),
+        _ => return None,
+    };
+
+    Some(view! {
+        <tr>
+            <td class="mono">
+                <a class=color_class href=format!("#{}", anchor_id(&binding.module_path, &binding.name))>
+                    {&binding.name}
+                </a>
+            </td>
+            <td>{kind}</td>
+            <td>{operands}</td>
+            <td>{binding.summary().map(str::to_owned)}</td>
+        </tr>
+    })
 }
 
-fn generate_content_item(parent_module: Option<String>, item: &ItemContent) -> HtmlElement<Div> {
+fn generate_content_item(options: &GeneratorOptions, parent_module: Option<String>, item: &ItemContent) -> HtmlElement<Article> {
     match item {
-        ItemContent::Binding(binding) => generate_binding_item(parent_module, binding),
-        ItemContent::Module(module) => generate_module_item(parent_module, module),
-        ItemContent::Data(data) => generate_data_item(parent_module, data),
-        ItemContent::Variant(variant) => generate_variant_item(parent_module, variant),
-        _ => view! { <div class="panel">{format!("{:?}", item)}</div> },
+        ItemContent::Binding(binding) => generate_binding_item(options, parent_module, binding),
+        ItemContent::Module(module) => generate_module_item(options, parent_module, module),
+        ItemContent::Data(data) => generate_data_item(options, parent_module, data),
+        ItemContent::Variant(variant) => generate_variant_item(options, parent_module, variant),
+        _ => view! { <article class="panel">{format!("{:?}", item)}</article> },
     }
 }
 
-fn generate_binding_item(parent_module: Option<String>, item: &BindingDefinition) -> HtmlElement<Div> {
+fn generate_binding_item(options: &GeneratorOptions, parent_module: Option<String>, item: &BindingDefinition) -> HtmlElement<Article> {
     match &item.kind {
-        BindingType::Const(constant) => generate_constant_item(parent_module, item, constant),
-        BindingType::Function(function) => generate_function_item(parent_module, item, function),
-        BindingType::IndexMacro(index_macro) => generate_index_macro_item(parent_module, item, index_macro),
-        BindingType::CodeMacro(code_macro) => generate_code_macro_item(parent_module, item, code_macro),
+        BindingType::Const(constant) => generate_constant_item(options, parent_module, item, constant),
+        BindingType::Function(function) => generate_function_item(options, parent_module, item, function),
+        BindingType::IndexMacro(index_macro) => generate_index_macro_item(options, parent_module, item, index_macro),
+        BindingType::CodeMacro(code_macro) => generate_code_macro_item(options, parent_module, item, code_macro),
     }
 }
 
@@ -246,18 +1849,170 @@ fn module_qualifier(module: String) -> View {
     .into()
 }
 
-fn documentation(item: &impl Documented) -> impl IntoView {
+/// The qualified name of the module an item is in, for the panel's `data-module` attribute. Top-level items have none.
+fn module_attribute(module_path: &[String]) -> Option<String> {
+    (!module_path.is_empty()).then(|| module_path.join("~"))
+}
+
+/// The ID of an item's panel, its name qualified by every module it's in, like `Outer.Inner.Name`. Dots are used
+/// rather than the `~` of Uiua, since they don't need escaping in URLs.
+pub fn anchor_id(module_path: &[String], name: &str) -> String {
+    module_path
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(name))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The heading of an item panel, one level below the heading of the panel it's nested in,
+/// so screen readers and reader modes see the page's outline.
+fn panel_heading(module_path: &[String], content: impl IntoView) -> View {
+    let content = content.into_view();
+    match module_path.len() {
+        0 => view! { <h3 class="panel-heading mono">{content}</h3> }.into_view(),
+        1 => view! { <h4 class="panel-heading mono">{content}</h4> }.into_view(),
+        2 => view! { <h5 class="panel-heading mono">{content}</h5> }.into_view(),
+        _ => view! { <h6 class="panel-heading mono">{content}</h6> }.into_view(),
+    }
+}
+
+/// Links to the modules an item is nested in, outermost first.
+fn breadcrumbs(module_path: &[String]) -> impl IntoView {
+    if module_path.is_empty() {
+        return None;
+    }
+
+    let crumbs = module_path
+        .iter()
+        .enumerate()
+        .map(|(index, module)| {
+            view! {
+                {(index > 0).then_some(" › ")}
+                <a class="module" href=format!("#{}", anchor_id(&module_path[..index], module))>{module}</a>
+            }
+        })
+        .collect_view();
+
+    Some(view! { <nav class="breadcrumbs mono" aria-label="Module path">{crumbs}</nav> })
+}
+
+/// Links a binding to its definition on the page of its source file.
+fn source_link(location: &SourceLocation) -> impl IntoView {
+    view! {
+        <a
+            class="source-link"
+            href=format!("src/{}.html#L{}-L{}", location.file, location.start_line, location.end_line)
+        >
+            "source"
+        </a>
+    }
+}
+
+/// Copies the link to an item, or to its share page when there are share pages, so the link unfurls.
+fn permalink(options: &GeneratorOptions, anchor: &str) -> impl IntoView {
+    let share_page = options.share_pages.then(|| share_page_path(anchor).to_string_lossy().replace('\\', "/"));
+    view! {
+        <a
+            class="permalink"
+            href=format!("#{}", anchor)
+            data-share=share_page
+            title="Copy link to this item"
+            aria-label="Copy link to this item"
+        >
+            "#"
+        </a>
+    }
+}
+
+/// Marks private bindings, which are only documented when the docs include private items.
+fn private_badge(item: &BindingDefinition) -> impl IntoView {
+    (!item.public).then(|| {
+        view! {
+            " "
+            <span class="badge private-badge" title="This binding is private, so it can't be used outside the library">
+                "private"
+            </span>
+        }
+    })
+}
+
+/// Marks bindings documented from their source because they failed to compile.
+fn unverified_badge(item: &BindingDefinition) -> impl IntoView {
+    item.unverified.as_ref().map(|_| {
+        view! {
+            " "
+            <span class="badge unverified-badge" title="This binding failed to compile, so it's documented from its source alone">
+                "unverified"
+            </span>
+        }
+    })
+}
+
+/// The badges the author added with `!doc badge` directives.
+fn custom_badges(item: &BindingDefinition) -> impl IntoView {
+    item.directives
+        .badges()
+        .into_iter()
+        .map(|badge| {
+            view! {
+                " "
+                <span
+                    class="badge custom-badge"
+                    style=badge.color.map(|color| format!("background-color: {}", color))
+                >
+                    {badge.label}
+                </span>
+            }
+        })
+        .collect_view()
+}
+
+fn generate_compile_error_note(unverified: &Unverified) -> Option<impl IntoView> {
+    unverified.error.as_ref().map(|error| {
+        view! {
+            <div class="compile-error">
+                <strong>"Compile error: "</strong>
+                <span class="mono">{error}</span>
+            </div>
+        }
+    })
+}
+
+fn documentation(options: &GeneratorOptions, item: &impl Documented) -> impl IntoView {
     item.comment()
-        .map(|comment| view! { <div class="feature-documentation" inner_html=markdown_to_html(comment) /> })
+        .map(|comment| view! { <div class="feature-documentation" inner_html=markdown_to_html(&options.markdown_hooks, comment) /> })
 }
 
-fn generate_constant_item(parent_module: Option<String>, item: &BindingDefinition, constant: &ConstantDefinition) -> HtmlElement<Div> {
+fn generate_constant_item(
+    options: &GeneratorOptions,
+    parent_module: Option<String>,
+    item: &BindingDefinition,
+    constant: &ConstantDefinition,
+) -> HtmlElement<Article> {
+    let anchor = anchor_id(&item.module_path, &item.name);
+
     view! {
-        <div class="panel feature">
-            <h3 class="mono">
-                {parent_module.clone().map(module_qualifier)} <span inner_html=&item.name></span>
-                " " <span class="badge">"constant"</span>
-            </h3>
+        <article
+            class="panel feature"
+            id=anchor.clone()
+            data-name=&item.name
+            data-kind="constant"
+            data-module=module_attribute(&item.module_path)
+        >
+            {breadcrumbs(&item.module_path)}
+            {panel_heading(
+                &item.module_path,
+                view! {
+                    {parent_module.clone().map(module_qualifier)} <span>{&item.name}</span>
+                    " " <span class="badge">"constant"</span>
+                    {unverified_badge(item)}
+                    {private_badge(item)}
+                    {custom_badges(item)}
+                    {permalink(options, &anchor)}
+                    {(!options.hide_code).then(|| source_link(&item.location))}
+                },
+            )}
             {constant
                 .value
                 .as_ref()
@@ -269,8 +2024,22 @@ fn generate_constant_item(parent_module: Option<String>, item: &BindingDefinitio
                         </details>
                     }
                 })}
-            {documentation(item)}
-        </div>
+            {(constant.computed_at_load && !item.code.is_empty())
+                .then(|| {
+                    view! {
+                        <details>
+                            <summary>"Computed at load time"</summary>
+                            <p class="load-time-note">
+                                "The value isn't known until the library is loaded, so this is the expression that computes it."
+                            </p>
+                            <code class="source-code" inner_html=format_source_code(&item.code, options.wrap_code)></code>
+                        </details>
+                    }
+                })}
+            {item.unverified.as_ref().and_then(generate_compile_error_note)}
+            {documentation(options, item)}
+            {options.metrics.then(|| generate_metrics_note(&item.metrics))}
+        </article>
     }
 }
 
@@ -306,96 +2075,246 @@ fn generate_named_signature_item(signature: Option<SignatureInfo>, named_signatu
                             .iter()
                             .map(|input| view! { <span class="summary-badge input">{input}</span> })
                             .collect_view()}
+                        <button
+                            class="copy-button signature-copy"
+                            data-copy=signature.to_comment()
+                            title="Copy signature comment to clipboard"
+                        >
+                            "Copy"
+                        </button>
                     }
                 })}
         </div>
     }
 }
 
-fn generate_function_item(parent_module: Option<String>, item: &BindingDefinition, function: &FunctionDefinition) -> HtmlElement<Div> {
-    let source_code = format_source_code(&item.code);
+/// A widget that shows where the arguments of a call end up on the stack, filled in by the script.
+fn generate_stack_explorer(name: &str, signature: &NamedSignature) -> impl IntoView {
+    let json = |names: &[String]| serde_json::to_string(names).expect("Unable to serialize the signature");
+
+    view! {
+        <details
+            class="stack-explorer"
+            data-name=name.to_owned()
+            data-inputs=json(&signature.inputs)
+            data-outputs=json(&signature.outputs)
+        >
+            <summary>"Stack explorer"</summary>
+            <div class="stack-explorer-body"></div>
+        </details>
+    }
+}
+
+fn generate_function_item(
+    options: &GeneratorOptions,
+    parent_module: Option<String>,
+    item: &BindingDefinition,
+    function: &FunctionDefinition,
+) -> HtmlElement<Article> {
+    let anchor = anchor_id(&item.module_path, &item.name);
 
     view! {
-        <div class="panel feature">
-            <h3 class="mono">
-                {parent_module.map(module_qualifier)}
-                <span class=function.signature.color_class()>{&item.name}</span> " "
-                <span class="badge">"function"</span>
-            </h3>
+        <article
+            class="panel feature"
+            id=anchor.clone()
+            data-name=&item.name
+            data-kind="function"
+            data-arity=function.signature.as_ref().map(|signature| signature.inputs)
+            data-module=module_attribute(&item.module_path)
+        >
+            {breadcrumbs(&item.module_path)}
+            {panel_heading(
+                &item.module_path,
+                view! {
+                    {parent_module.map(module_qualifier)}
+                    <span class=function.signature.as_ref().map_or("", Colored::color_class)>{&item.name}</span> " "
+                    <span class="badge">"function"</span>
+                    {unverified_badge(item)}
+                    {private_badge(item)}
+                    {custom_badges(item)}
+                    {permalink(options, &anchor)}
+                    {(!options.hide_code).then(|| source_link(&item.location))}
+                },
+            )}
 
             {generate_named_signature_item(
-                Some(function.signature.clone()),
+                function.signature.clone(),
                 function.named_signature.clone(),
             )}
-            {documentation(item)}
+            {function
+                .named_signature
+                .as_ref()
+                .filter(|signature| !signature.inputs.is_empty())
+                .map(|signature| generate_stack_explorer(&item.name, signature))}
+            {item.unverified.as_ref().and_then(generate_compile_error_note)}
+            {documentation(options, item)}
+            {item.benchmark.as_ref().map(generate_benchmark_note)}
+            {options.metrics.then(|| generate_metrics_note(&item.metrics))}
+
+            {source_code_details(item, options)}
+        </article>
+    }
+}
 
+/// The code of a binding, unless it's hidden.
+fn source_code_details(item: &BindingDefinition, options: &GeneratorOptions) -> Option<impl IntoView> {
+    (!item.code.is_empty()).then(|| {
+        view! {
             <details>
                 <summary>"Source code"</summary>
-                <code class="source-code" inner_html=source_code></code>
+                <code class="source-code" inner_html=format_source_code(&item.code, options.wrap_code)></code>
             </details>
+        }
+    })
+}
+
+fn generate_benchmark_note(benchmark: &Benchmark) -> impl IntoView {
+    view! {
+        <div class="benchmark-note">
+            "Takes about " <strong>{format_duration(benchmark.median)}</strong> " for "
+            <code inner_html=format_source_code(&benchmark.expression, None)></code>
+            <span class="italics">
+                {format!(" (median of {} runs while generating these docs)", benchmark.runs)}
+            </span>
         </div>
     }
 }
 
-fn generate_index_macro_item(parent_module: Option<String>, item: &BindingDefinition, index_macro: &IndexMacroDefinition) -> HtmlElement<Div> {
-    let source_code = format_source_code(&item.code);
+fn generate_metrics_note(metrics: &CodeMetrics) -> impl IntoView {
+    let values = metrics
+        .values()
+        .into_iter()
+        .map(|(name, value)| {
+            let class = if metrics.outliers.iter().any(|outlier| outlier == name) {
+                "metric outlier"
+            } else {
+                "metric"
+            };
+            view! { <span class=class>{format!("{} {}", value, name)}</span> }
+        })
+        .collect_view();
+
+    view! { <div class="metrics-note">{values}</div> }
+}
+
+fn generate_index_macro_item(
+    options: &GeneratorOptions,
+    parent_module: Option<String>,
+    item: &BindingDefinition,
+    index_macro: &IndexMacroDefinition,
+) -> HtmlElement<Article> {
+    let anchor = anchor_id(&item.module_path, &item.name);
 
     view! {
-        <div class="panel feature">
-            <h3 class="mono">
-                {parent_module.map(module_qualifier)}
-                <span class=index_macro.color_class()>{&item.name}</span> " "
-                <span class="badge">"index macro"</span>
-            </h3>
+        <article
+            class="panel feature"
+            id=anchor.clone()
+            data-name=&item.name
+            data-kind="index-macro"
+            data-arity=index_macro.arguments
+            data-module=module_attribute(&item.module_path)
+        >
+            {breadcrumbs(&item.module_path)}
+            {panel_heading(
+                &item.module_path,
+                view! {
+                    {parent_module.map(module_qualifier)}
+                    <span class=index_macro.color_class()>{&item.name}</span> " "
+                    <span class="badge">"index macro"</span>
+                    {unverified_badge(item)}
+                    {private_badge(item)}
+                    {custom_badges(item)}
+                    {permalink(options, &anchor)}
+                    {(!options.hide_code).then(|| source_link(&item.location))}
+                },
+            )}
 
             {generate_named_signature_item(None, index_macro.named_signature.clone())}
-            {documentation(item)}
+            {item.unverified.as_ref().and_then(generate_compile_error_note)}
+            {documentation(options, item)}
+            {options.metrics.then(|| generate_metrics_note(&item.metrics))}
 
-            <details>
-                <summary>"Source code"</summary>
-                <code class="source-code" inner_html=source_code></code>
-            </details>
-        </div>
+            {source_code_details(item, options)}
+        </article>
     }
 }
 
-fn generate_code_macro_item(parent_module: Option<String>, item: &BindingDefinition, index_macro: &CodeMacroDefinition) -> HtmlElement<Div> {
-    let source_code = format_source_code(&item.code);
+fn generate_code_macro_item(
+    options: &GeneratorOptions,
+    parent_module: Option<String>,
+    item: &BindingDefinition,
+    index_macro: &CodeMacroDefinition,
+) -> HtmlElement<Article> {
+    let anchor = anchor_id(&item.module_path, &item.name);
 
     view! {
-        <div class="panel feature">
-            <h3 class="mono">
-                {parent_module.map(module_qualifier)}
-                <span class="monadic-modifier">{&item.name}</span> " "
-                <span class="badge">"code macro"</span>
-            </h3>
+        <article
+            class="panel feature"
+            id=anchor.clone()
+            data-name=&item.name
+            data-kind="code-macro"
+            data-arity=index_macro.arguments
+            data-module=module_attribute(&item.module_path)
+        >
+            {breadcrumbs(&item.module_path)}
+            {panel_heading(
+                &item.module_path,
+                view! {
+                    {parent_module.map(module_qualifier)}
+                    <span class="monadic-modifier">{&item.name}</span> " "
+                    <span class="badge">"code macro"</span>
+                    {unverified_badge(item)}
+                    {private_badge(item)}
+                    {custom_badges(item)}
+                    {permalink(options, &anchor)}
+                    {(!options.hide_code).then(|| source_link(&item.location))}
+                },
+            )}
 
             {generate_named_signature_item(None, index_macro.named_signature.clone())}
-            {documentation(item)}
+            {item.unverified.as_ref().and_then(generate_compile_error_note)}
+            {documentation(options, item)}
+            {options.metrics.then(|| generate_metrics_note(&item.metrics))}
 
-            <details>
-                <summary>"Source code"</summary>
-                <code class="source-code" inner_html=source_code></code>
-            </details>
-        </div>
+            {source_code_details(item, options)}
+        </article>
     }
 }
 
-fn generate_module_item(parent_module: Option<String>, module: &ModuleDefinition) -> HtmlElement<Div> {
+fn generate_module_item(options: &GeneratorOptions, parent_module: Option<String>, module: &ModuleDefinition) -> HtmlElement<Article> {
+    let anchor = anchor_id(&module.module_path, &module.name);
+    // Top-level modules are already anchored by their section heading
+    let panel_id = parent_module.is_some().then(|| anchor.clone());
+
     view! {
-        <div class="panel feature">
-            <h3 class="mono">
-                {parent_module.map(module_qualifier)} <span class="module">{&module.name}</span> " "
-                <span class="badge">"module"</span>
-            </h3>
-            {documentation(module)}
+        <article
+            class="panel feature"
+            id=panel_id
+            data-name=&module.name
+            data-kind="module"
+            data-module=module_attribute(&module.module_path)
+        >
+            {breadcrumbs(&module.module_path)}
+            {panel_heading(
+                &module.module_path,
+                view! {
+                    {parent_module.map(module_qualifier)} <span class="module">{&module.name}</span> " "
+                    <span class="badge">"module"</span>
+                    {permalink(options, &anchor)}
+                },
+            )}
+            {documentation(options, module)}
+            {module
+                .readme
+                .as_deref()
+                .map(|readme| view! { <div class="feature-documentation module-readme" inner_html=readme_to_html(&options.markdown_hooks, readme) /> })}
             <br />
             {module
                 .items
                 .iter()
-                .map(|item| generate_content_item(Some(module.name.clone()), item))
+                .map(|item| generate_content_item(options, Some(module.name.clone()), item))
                 .collect_view()}
-        </div>
+        </article>
     }
 }
 
@@ -411,65 +2330,90 @@ pub fn box_description(definition: Option<&Definition>) -> &'static str {
     }
 }
 
-fn generate_data_item(parent_module: Option<String>, data: &DataDefinition) -> HtmlElement<Div> {
-    fn badge_row(field: &Field) -> View {
+fn generate_data_item(options: &GeneratorOptions, parent_module: Option<String>, data: &DataDefinition) -> HtmlElement<Article> {
+    fn badge_row((index, field): (usize, &Field)) -> View {
         view! {
             <div class="badge-row">
-                <span class="data-badge input">{&field.clone().name}</span>
-                {&field
+                <span class="data-badge index">{index}</span>
+                <span class="data-badge input">{&field.name}</span>
+                {field
                     .validator
-                    .clone()
+                    .as_ref()
                     .map(|validator| {
-                        let (name, italics) = match validator.as_str() {
-                            "°0type" => ("number array".into(), true),
-                            "°1type" => ("complex array".into(), true),
-                            "°2type" => ("box array".into(), true),
-                            "°3type" => ("complex array".into(), true),
-                            _ => (None, false),
-                        };
-                        let italics = if italics { "italics" } else { "" };
-                        let name = if let Some(name) = name { name.to_string() } else { validator };
-
-                        view! { <span class=format!("data-badge type {}", italics)>{name}</span> }
-                    })
-                    .collect_view()}
+                        view! { <span class="data-badge type" inner_html=format_source_code(validator, None)></span> }
+                    })}
+                {field
+                    .validator_explanation
+                    .clone()
+                    .map(|explanation| {
+                        view! { <span class="validator-explanation italics">{explanation}</span> }
+                    })}
             </div>
         }
         .into_view()
     }
 
+    let anchor = data.name.as_ref().map(|name| anchor_id(&data.module_path, name));
+    let qualifier = data.name.clone().or(parent_module.clone());
+
     view! {
-        <div class="panel feature">
-            <h3 class="mono">
-                {parent_module.map(module_qualifier)}
-                <span class="module">{data.name.clone().unwrap_or_default()}</span> " "
-                <span class="badge">"data"</span> " "
-                <span class="badge">{box_description(data.definition.as_ref())}</span>
-            </h3>
-            {documentation(data)}
+        <article
+            class="panel feature"
+            id=anchor.clone()
+            data-name=data.name.clone()
+            data-kind="data"
+            data-module=module_attribute(&data.module_path)
+        >
+            {breadcrumbs(&data.module_path)}
+            {panel_heading(
+                &data.module_path,
+                view! {
+                    {parent_module.map(module_qualifier)}
+                    <span class="module">{data.name.clone().unwrap_or_default()}</span> " "
+                    <span class="badge">"data"</span> " "
+                    <span class="badge">{box_description(data.definition.as_ref())}</span>
+                    {anchor.as_deref().map(|anchor| permalink(options, anchor))}
+                },
+            )}
+            {documentation(options, data)}
             {data
                 .definition
                 .as_ref()
                 .map(|definition| {
                     view! {
                         <div class="data-summary">
-                            {definition.fields.iter().map(badge_row).collect_view()}
+                            {definition.fields.iter().enumerate().map(badge_row).collect_view()}
                         </div>
                     }
                 })}
-        </div>
+            {generate_data_shape(data.definition.as_ref(), None)}
+            {generate_generated_functions(options, qualifier, &data.generated_functions)}
+        </article>
     }
 }
 
-fn generate_variant_item(parent_module: Option<String>, data: &VariantDefinition) -> HtmlElement<Div> {
+fn generate_variant_item(options: &GeneratorOptions, parent_module: Option<String>, data: &VariantDefinition) -> HtmlElement<Article> {
+    let anchor = anchor_id(&data.module_path, &data.name);
+
     view! {
-        <div class="panel feature">
-            <h3 class="mono">
-                {parent_module.map(module_qualifier)} <span class="module">{&data.name}</span> " "
-                <span class="badge">"variant"</span> " "
-                <span class="badge">{box_description(data.definition.as_ref())}</span>
-            </h3>
-            {documentation(data)}
+        <article
+            class="panel feature"
+            id=anchor.clone()
+            data-name=&data.name
+            data-kind="variant"
+            data-module=module_attribute(&data.module_path)
+        >
+            {breadcrumbs(&data.module_path)}
+            {panel_heading(
+                &data.module_path,
+                view! {
+                    {parent_module.map(module_qualifier)} <span class="module">{&data.name}</span> " "
+                    <span class="badge">"variant"</span> " "
+                    <span class="badge">{box_description(data.definition.as_ref())}</span>
+                    {permalink(options, &anchor)}
+                },
+            )}
+            {documentation(options, data)}
             {data
                 .definition
                 .as_ref()
@@ -479,9 +2423,11 @@ fn generate_variant_item(parent_module: Option<String>, data: &VariantDefinition
                             {definition
                                 .fields
                                 .iter()
-                                .map(|field| {
+                                .enumerate()
+                                .map(|(index, field)| {
                                     view! {
                                         <div class="badge-row">
+                                            <span class="data-badge index">{index + 1}</span>
                                             <span class="data-badge input">{&field.name}</span>
                                         </div>
                                     }
@@ -490,6 +2436,100 @@ fn generate_variant_item(parent_module: Option<String>, data: &VariantDefinition
                         </div>
                     }
                 })}
+            {generate_data_shape(data.definition.as_ref(), Some(data.index))}
+            {generate_references("Produced by", &data.produced_by)}
+            {generate_references("Consumed by", &data.consumed_by)}
+            {generate_generated_functions(options, Some(data.name.clone()), &data.generated_functions)}
+        </article>
+    }
+}
+
+fn generate_references(label: &'static str, references: &[ItemReference]) -> impl IntoView {
+    if references.is_empty() {
+        return None;
+    }
+
+    Some(view! {
+        <div class="item-references">
+            {label} ": "
+            {references
+                .iter()
+                .enumerate()
+                .map(|(index, reference)| {
+                    view! {
+                        {(index > 0).then_some(", ")}
+                        <a class="mono" href=format!("#{}", anchor_id(&reference.module_path, &reference.name))>
+                            {qualified_name(&reference.module_path, &reference.name)}
+                        </a>
+                    }
+                })
+                .collect_view()}
+        </div>
+    })
+}
+
+/// Describes the array a data definition's constructor produces, so users know how to destructure it.
+fn generate_data_shape(definition: Option<&Definition>, variant_index: Option<usize>) -> impl IntoView {
+    let tag = variant_index.map(|index| index.to_string());
+    let fields = definition.map(|definition| definition.fields.iter().map(|field| field.name.as_str()).collect::<Vec<_>>());
+
+    let (shape, description) = match (fields, definition.map(|definition| definition.boxed)) {
+        (Some(fields), Some(boxed)) => {
+            let elements = tag.iter().map(String::as_str).chain(fields.iter().copied()).collect::<Vec<_>>().join(" ");
+            let shape = if boxed { format!("{{{}}}", elements) } else { format!("[{}]", elements) };
+            let description = match (&tag, boxed) {
+                (Some(tag), true) => format!("A list of boxes starting with the variant tag {}, followed by the fields.", tag),
+                (Some(tag), false) => format!("An array starting with the variant tag {}, followed by the fields.", tag),
+                (None, true) => format!("A list of {} boxed fields.", fields.len()),
+                (None, false) => format!("An array with {} fields as rows.", fields.len()),
+            };
+            (shape, description)
+        }
+        _ => match &tag {
+            Some(tag) => (tag.clone(), "Just the variant tag.".to_owned()),
+            None => return None,
+        },
+    };
+
+    Some(view! {
+        <div class="data-shape">
+            "Constructed values: " <code>{shape}</code> " " <span class="italics">{description}</span>
         </div>
+    })
+}
+
+fn generate_generated_functions(options: &GeneratorOptions, qualifier: Option<String>, functions: &[GeneratedFunction]) -> impl IntoView {
+    if functions.is_empty() {
+        return None;
     }
+
+    Some(view! {
+        <details>
+            <summary>"Generated functions"</summary>
+            <div class="generated-functions">
+                {functions
+                    .iter()
+                    .map(|function| {
+                        view! {
+                            <div class="generated-function">
+                                <span class="mono">
+                                    {qualifier.clone().map(module_qualifier)}
+                                    <span class=function
+                                        .function
+                                        .signature
+                                        .as_ref()
+                                        .map_or("", Colored::color_class)>{&function.name}</span>
+                                </span>
+                                {generate_named_signature_item(
+                                    function.function.signature.clone(),
+                                    function.function.named_signature.clone(),
+                                )}
+                                {documentation(options, function)}
+                            </div>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+        </details>
+    })
 }
@@ -0,0 +1,96 @@
+use serde::Serialize;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Terms from the library's `glossary.md`, linked from doc comments to a generated glossary page.
+#[derive(Debug, Clone, Serialize)]
+pub struct Glossary {
+    pub terms: Vec<GlossaryTerm>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GlossaryTerm {
+    pub term: String,
+    /// Markdown of the definition.
+    pub definition: String,
+}
+
+pub const GLOSSARY_PAGE: &str = "glossary.html";
+
+impl GlossaryTerm {
+    pub fn anchor(&self) -> String {
+        let slug = self
+            .term
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        format!("term-{}", slug)
+    }
+
+    pub fn url(&self) -> String {
+        format!("{}#{}", GLOSSARY_PAGE, self.anchor())
+    }
+
+    /// Byte range of the first occurrence of the term as a whole word, ignoring ASCII case.
+    pub fn find_in(&self, text: &str) -> Option<Range<usize>> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let length = self.term.len();
+        text.char_indices()
+            .map(|(start, _)| start)
+            .find(|&start| {
+                text[start..]
+                    .get(..length)
+                    .is_some_and(|candidate| candidate.eq_ignore_ascii_case(&self.term))
+                    && !text[..start].chars().next_back().is_some_and(is_word)
+                    && !text[start + length..].chars().next().is_some_and(is_word)
+            })
+            .map(|start| start..start + length)
+    }
+}
+
+/// Reads `glossary.md` (or `docs/glossary.md`) from the library's directory.
+///
+/// Every unindented `Term: definition` line starts a new term. Other lines continue the
+/// definition above them, and headings are skipped.
+pub fn read_glossary(root: &Path) -> Option<Glossary> {
+    let source = ["glossary.md", "docs/glossary.md"]
+        .iter()
+        .find_map(|path| fs::read_to_string(root.join(path)).ok())?;
+
+    let mut terms: Vec<GlossaryTerm> = Vec::new();
+    for line in source.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let entry = line.strip_prefix("- ").or(line.strip_prefix("* ")).unwrap_or(line);
+        match entry.split_once(": ") {
+            Some((term, definition)) if !line.starts_with(char::is_whitespace) && !term.trim().is_empty() => {
+                terms.push(GlossaryTerm {
+                    term: term.trim().trim_matches('*').to_owned(),
+                    definition: definition.trim().to_owned(),
+                });
+            }
+            _ => {
+                if let Some(last) = terms.last_mut() {
+                    last.definition.push('\n');
+                    last.definition.push_str(line.trim());
+                }
+            }
+        }
+    }
+
+    for term in &mut terms {
+        term.definition = term.definition.trim().to_owned();
+    }
+
+    if terms.is_empty() {
+        eprintln!("Warning: the glossary doesn't define any `Term: definition` lines");
+        return None;
+    }
+
+    Some(Glossary { terms })
+}
@@ -0,0 +1,106 @@
+use crate::sanitizer::{sanitize_html, RawHtmlPolicy};
+use std::fmt;
+use std::rc::Rc;
+
+/// Transforms the Markdown of doc comments before it's rendered, and the resulting HTML after,
+/// so tools built on the generator can add their own shortcodes without changing it.
+pub trait MarkdownHook {
+    fn before_render(&self, markdown: String) -> String {
+        markdown
+    }
+
+    /// Like `before_render`, for Markdown shown on a page that isn't at the root of the site, like an example.
+    /// `root` is the path from the page to the root, like `../`, for hooks that link to other pages of the site.
+    fn before_render_on_page(&self, markdown: String, root: &str) -> String {
+        let _ = root;
+        self.before_render(markdown)
+    }
+
+    fn after_render(&self, html: String) -> String {
+        html
+    }
+}
+
+struct BeforeRender<F>(F);
+
+impl<F: Fn(String) -> String> MarkdownHook for BeforeRender<F> {
+    fn before_render(&self, markdown: String) -> String {
+        (self.0)(markdown)
+    }
+}
+
+struct AfterRender<F>(F);
+
+impl<F: Fn(String) -> String> MarkdownHook for AfterRender<F> {
+    fn after_render(&self, html: String) -> String {
+        (self.0)(html)
+    }
+}
+
+/// The hooks all rendered Markdown goes through, in the order they were added.
+#[derive(Clone, Default)]
+pub struct MarkdownHooks {
+    hooks: Vec<Rc<dyn MarkdownHook>>,
+    /// What happens to HTML written in the Markdown. Hooks are trusted, so their output isn't sanitized.
+    pub raw_html: RawHtmlPolicy,
+    /// The path from the page the Markdown is shown on to the root of the site, empty for pages at the root.
+    root: String,
+}
+
+impl MarkdownHooks {
+    pub fn add(&mut self, hook: impl MarkdownHook + 'static) {
+        self.hooks.push(Rc::new(hook));
+    }
+
+    /// Adds a closure that transforms Markdown before it's rendered.
+    pub fn add_before_render(&mut self, hook: impl Fn(String) -> String + 'static) {
+        self.add(BeforeRender(hook));
+    }
+
+    /// Adds a closure that transforms the HTML rendered from Markdown.
+    pub fn add_after_render(&mut self, hook: impl Fn(String) -> String + 'static) {
+        self.add(AfterRender(hook));
+    }
+
+    /// The same hooks, for Markdown shown on a page that's `root` away from the root of the site, like `../`.
+    pub fn on_page(&self, root: &str) -> MarkdownHooks {
+        MarkdownHooks {
+            root: root.to_owned(),
+            ..self.clone()
+        }
+    }
+
+    pub fn before_render(&self, markdown: &str) -> String {
+        self.hooks
+            .iter()
+            .fold(markdown.to_owned(), |markdown, hook| hook.before_render_on_page(markdown, &self.root))
+    }
+
+    pub fn after_render(&self, html: String) -> String {
+        self.hooks.iter().fold(html, |html, hook| hook.after_render(html))
+    }
+
+    /// Renders GitHub-flavored Markdown, passing it through the hooks.
+    pub fn render(&self, markdown: &str) -> String {
+        self.after_render(self.to_html(markdown))
+    }
+
+    /// Renders Markdown after the hooks transformed it, applying the raw HTML policy,
+    /// but without the hooks that transform the resulting HTML.
+    pub fn to_html(&self, markdown: &str) -> String {
+        let mut options = markdown::Options::gfm();
+        options.compile.allow_dangerous_html = self.raw_html != RawHtmlPolicy::Escape;
+
+        let html = markdown::to_html_with_options(&self.before_render(markdown), &options).expect("Unable to convert markdown to HTML");
+        match self.raw_html {
+            RawHtmlPolicy::Sanitize => sanitize_html(&html),
+            RawHtmlPolicy::Escape | RawHtmlPolicy::Allow => html,
+        }
+    }
+}
+
+impl fmt::Debug for MarkdownHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MarkdownHooks({} hooks, {:?} raw HTML)", self.hooks.len(), self.raw_html)
+    }
+}
@@ -0,0 +1,61 @@
+/// Where the site is published, which decides how it's told to cache the site's files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Hosting {
+    /// Netlify, with a `_headers` file.
+    Netlify,
+    /// Cloudflare Pages, with a `_headers` file.
+    Cloudflare,
+    /// Apache, with an `.htaccess` file.
+    Apache,
+}
+
+/// Fingerprinted files get a new name whenever they change, so browsers can keep them forever.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+impl Hosting {
+    /// The configuration file with cache headers for the fingerprinted files, and its name. `base_path` is where the
+    /// site is on its host, like `/mylib/docs/`.
+    pub fn cache_headers(self, base_path: &str, fingerprinted: &[String]) -> (&'static str, String) {
+        match self {
+            Hosting::Netlify | Hosting::Cloudflare => {
+                let rules = fingerprinted
+                    .iter()
+                    .map(|file| format!("{}{}\n  Cache-Control: {}\n", base_path, file, IMMUTABLE))
+                    .collect::<Vec<_>>();
+                ("_headers", rules.join("\n"))
+            }
+            Hosting::Apache => {
+                let files = fingerprinted.iter().map(|file| escape_regex(file)).collect::<Vec<_>>();
+                let config = format!(
+                    "<IfModule mod_headers.c>\n  <FilesMatch \"^({})$\">\n    Header set Cache-Control \"{}\"\n  </FilesMatch>\n</IfModule>\n",
+                    files.join("|"),
+                    IMMUTABLE
+                );
+                (".htaccess", config)
+            }
+        }
+    }
+}
+
+/// The path of the site on its host, starting and ending with a slash, from the URL it's published at, like
+/// `/mylib/docs/` for `https://example.com/mylib/docs`. The URL can also be just the path.
+pub fn base_path(base_url: &str) -> String {
+    let path = match base_url.split_once("://") {
+        Some((_, address)) => address.find('/').map(|start| &address[start..]).unwrap_or_default(),
+        None => base_url,
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default().trim_matches('/');
+    match path.is_empty() {
+        true => "/".to_owned(),
+        false => format!("/{}/", path),
+    }
+}
+
+fn escape_regex(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            true => vec![c],
+            false => vec!['\\', c],
+        })
+        .collect()
+}
@@ -0,0 +1,169 @@
+use crate::extractor::{BindingType, Definition, FileContent, ItemContent, NamedSignature};
+use crate::metadata::ProjectMetadata;
+use serde::Serialize;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io;
+use std::path::Path;
+
+/// The public interface of a library without any of its code, to share the API of closed-source libraries.
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryInterface {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub uiua_version: &'static str,
+    pub items: Vec<InterfaceItem>,
+}
+
+/// A public item, tagged with its `kind`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InterfaceItem {
+    Constant {
+        name: String,
+        comment: Option<String>,
+    },
+    Function {
+        name: String,
+        signature: Option<String>,
+        /// The named signature in doc comment syntax, like `Area ? Width Height`.
+        named_signature: Option<String>,
+        comment: Option<String>,
+    },
+    IndexMacro {
+        name: String,
+        operands: usize,
+        named_signature: Option<String>,
+        comment: Option<String>,
+    },
+    CodeMacro {
+        name: String,
+        named_signature: Option<String>,
+        comment: Option<String>,
+    },
+    Module {
+        name: String,
+        comment: Option<String>,
+        items: Vec<InterfaceItem>,
+    },
+    /// A data definition, without a name when it defines the module it's in.
+    Data {
+        name: Option<String>,
+        comment: Option<String>,
+        shape: Option<InterfaceShape>,
+    },
+    Variant {
+        name: String,
+        comment: Option<String>,
+        shape: Option<InterfaceShape>,
+    },
+}
+
+/// The fields of a data definition.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceShape {
+    pub boxed: bool,
+    pub fields: Vec<InterfaceField>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceField {
+    pub name: String,
+    /// What the field's validator accepts, in words, like "a number". The validator's code is left out.
+    pub accepts: Option<String>,
+}
+
+impl LibraryInterface {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Unable to serialize the library interface")
+    }
+}
+
+/// Writes the interface of the library to `interface.json` in `output_directory`, instead of a site.
+pub fn generate_interface_file(output_directory: &Path, content: &FileContent, metadata: &ProjectMetadata) -> io::Result<()> {
+    if output_directory.exists() {
+        remove_dir_all(output_directory)?;
+    }
+    create_dir_all(output_directory)?;
+    write(
+        output_directory.join("interface.json"),
+        build_library_interface(&content.items, metadata).to_json(),
+    )
+}
+
+/// Describes the documented items of a library, leaving out their code and the private items.
+pub fn build_library_interface(items: &[ItemContent], metadata: &ProjectMetadata) -> LibraryInterface {
+    LibraryInterface {
+        name: metadata.name.clone(),
+        version: metadata.version.clone(),
+        description: metadata.description.clone(),
+        uiua_version: uiua::VERSION,
+        items: interface_items(items),
+    }
+}
+
+fn interface_items(items: &[ItemContent]) -> Vec<InterfaceItem> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Binding(binding) if binding.is_documented() => {
+                let name = binding.name.clone();
+                let comment = binding.comment.clone();
+                Some(match &binding.kind {
+                    BindingType::Const(_) => InterfaceItem::Constant { name, comment },
+                    BindingType::Function(function) => InterfaceItem::Function {
+                        name,
+                        signature: function.signature.as_ref().map(ToString::to_string),
+                        named_signature: named_signature(function.named_signature.as_ref()),
+                        comment,
+                    },
+                    BindingType::IndexMacro(index_macro) => InterfaceItem::IndexMacro {
+                        name,
+                        operands: index_macro.arguments,
+                        named_signature: named_signature(index_macro.named_signature.as_ref()),
+                        comment,
+                    },
+                    BindingType::CodeMacro(code_macro) => InterfaceItem::CodeMacro {
+                        name,
+                        named_signature: named_signature(code_macro.named_signature.as_ref()),
+                        comment,
+                    },
+                })
+            }
+            ItemContent::Module(module) if module.has_public_items() => Some(InterfaceItem::Module {
+                name: module.name.clone(),
+                comment: module.comment.clone(),
+                items: interface_items(&module.items),
+            }),
+            ItemContent::Data(data) => Some(InterfaceItem::Data {
+                name: data.name.clone(),
+                comment: data.comment.clone(),
+                shape: data.definition.as_ref().map(interface_shape),
+            }),
+            ItemContent::Variant(variant) => Some(InterfaceItem::Variant {
+                name: variant.name.clone(),
+                comment: variant.comment.clone(),
+                shape: variant.definition.as_ref().map(interface_shape),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn named_signature(signature: Option<&NamedSignature>) -> Option<String> {
+    signature.map(|signature| signature.to_comment().trim_start_matches("# ").to_owned())
+}
+
+fn interface_shape(definition: &Definition) -> InterfaceShape {
+    InterfaceShape {
+        boxed: definition.boxed,
+        fields: definition
+            .fields
+            .iter()
+            .map(|field| InterfaceField {
+                name: field.name.clone(),
+                accepts: field.validator_explanation.clone(),
+            })
+            .collect(),
+    }
+}
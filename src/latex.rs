@@ -0,0 +1,248 @@
+use crate::extractor::{qualified_name, BindingDefinition, BindingType, Documented, FileContent, ItemContent, ModuleDefinition};
+use crate::fonts::FONT_FILE;
+use crate::metadata::ProjectMetadata;
+use markdown::mdast::Node;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io;
+use std::path::Path;
+
+const PREAMBLE: &str = r"% Needs XeLaTeX or LuaLaTeX for the Uiua font
+\usepackage{fontspec}
+\usepackage{fancyvrb}
+\usepackage{longtable}
+\usepackage{hyperref}
+\newfontfamily\uiuafont{Uiua386}[Path=./, Extension=.ttf]
+\DefineVerbatimEnvironment{uiuacode}{Verbatim}{formatcom=\uiuafont, frame=single, framesep=2mm}
+\newcommand{\uiua}[1]{{\uiuafont #1}}
+";
+
+/// Writes the docs as a reference appendix for LaTeX documents, to `output_directory`.
+///
+/// `reference.tex` is the appendix itself, `preamble.tex` the packages and the font it needs, and
+/// `main.tex` a document with both that compiles on its own.
+pub fn generate_latex_reference(output_directory: &Path, content: &FileContent, metadata: &ProjectMetadata) -> io::Result<()> {
+    if output_directory.exists() {
+        remove_dir_all(output_directory)?;
+    }
+    create_dir_all(output_directory)?;
+
+    let mut reference = vec![
+        format!("% Generated by uiua-doc-gen for Uiua {}", uiua::VERSION),
+        format!(
+            "\\section{{Reference for {}}}\\label{{uiua:{}}}",
+            escape(metadata.title()),
+            label(&metadata.name)
+        ),
+    ];
+    if let Some(description) = &metadata.description {
+        reference.extend([String::new(), escape(description)]);
+    }
+    reference.extend([
+        String::new(),
+        "\\begin{uiuacode}".to_owned(),
+        metadata.import_snippet(None),
+        "\\end{uiuacode}".to_owned(),
+    ]);
+
+    let bindings = content
+        .items
+        .iter()
+        .filter(|item| !matches!(item, ItemContent::Module(_)))
+        .collect::<Vec<_>>();
+    if bindings.iter().any(|item| documented_binding(item).is_some()) {
+        reference.extend([String::new(), "\\subsection{Bindings}".to_owned()]);
+        reference.extend(generate_section(&bindings));
+    }
+
+    for item in &content.items {
+        if let ItemContent::Module(module) = item {
+            if module.has_public_items() {
+                reference.extend(generate_module(module, metadata));
+            }
+        }
+    }
+
+    write(output_directory.join("reference.tex"), reference.join("\n") + "\n")?;
+    write(output_directory.join("preamble.tex"), PREAMBLE)?;
+    write(output_directory.join("main.tex"), generate_main(metadata))?;
+    write(output_directory.join(FONT_FILE), include_bytes!("../design/Uiua386.ttf"))
+}
+
+fn generate_main(metadata: &ProjectMetadata) -> String {
+    [
+        "\\documentclass{article}",
+        "\\input{preamble}",
+        &format!("\\title{{{}}}", escape(metadata.title())),
+        "\\date{}",
+        "\\begin{document}",
+        "\\maketitle",
+        "\\appendix",
+        "\\input{reference}",
+        "\\end{document}",
+    ]
+    .join("\n")
+        + "\n"
+}
+
+fn generate_module(module: &ModuleDefinition, metadata: &ProjectMetadata) -> Vec<String> {
+    let mut lines = vec![
+        String::new(),
+        format!(
+            "\\subsection{{Module \\uiua{{{}}}}}\\label{{uiua:{}}}",
+            escape(&module.name),
+            label(&qualified_name(&module.module_path, &module.name))
+        ),
+    ];
+    if let Some(comment) = module.comment() {
+        lines.extend([String::new(), markdown_to_latex(comment)]);
+    }
+    lines.extend([
+        String::new(),
+        "\\begin{uiuacode}".to_owned(),
+        metadata.import_snippet(Some(&module.name)),
+        "\\end{uiuacode}".to_owned(),
+    ]);
+
+    // Nested modules are flattened into the module, their bindings keep their qualified names
+    let mut items = Vec::new();
+    collect_items(&module.items, &mut items);
+    lines.extend(generate_section(&items));
+    lines
+}
+
+fn collect_items<'a>(items: &'a [ItemContent], collected: &mut Vec<&'a ItemContent>) {
+    for item in items {
+        match item {
+            ItemContent::Module(module) => collect_items(&module.items, collected),
+            item => collected.push(item),
+        }
+    }
+}
+
+fn documented_binding(item: &ItemContent) -> Option<&BindingDefinition> {
+    match item {
+        ItemContent::Binding(binding) if binding.is_documented() => Some(binding),
+        _ => None,
+    }
+}
+
+/// A table with the signatures of the bindings, followed by a subsection for each.
+fn generate_section(items: &[&ItemContent]) -> Vec<String> {
+    let bindings = items.iter().filter_map(|item| documented_binding(item)).collect::<Vec<_>>();
+    if bindings.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![
+        String::new(),
+        "\\begin{longtable}{lll}".to_owned(),
+        "\\textbf{Name} & \\textbf{Kind} & \\textbf{Signature} \\\\".to_owned(),
+        "\\hline".to_owned(),
+        "\\endhead".to_owned(),
+    ];
+    lines.extend(bindings.iter().map(|binding| {
+        format!(
+            "\\hyperref[uiua:{}]{{\\uiua{{{}}}}} & {} & \\uiua{{{}}} \\\\",
+            label(&qualified_name(&binding.module_path, &binding.name)),
+            escape(&qualified_name(&binding.module_path, &binding.name)),
+            binding.kind.name(),
+            escape(&signature(binding))
+        )
+    }));
+    lines.push("\\end{longtable}".to_owned());
+
+    for binding in bindings {
+        let name = qualified_name(&binding.module_path, &binding.name);
+        lines.extend([
+            String::new(),
+            format!("\\subsubsection*{{\\uiua{{{}}}}}\\label{{uiua:{}}}", escape(&name), label(&name)),
+        ]);
+        if let Some(comment) = binding.comment() {
+            lines.extend([String::new(), markdown_to_latex(comment)]);
+        }
+        if !binding.code.is_empty() {
+            lines.extend([
+                String::new(),
+                "\\begin{uiuacode}".to_owned(),
+                binding.code.clone(),
+                "\\end{uiuacode}".to_owned(),
+            ]);
+        }
+    }
+    lines
+}
+
+/// The signature of a binding for the table, with its named signature when it has one.
+fn signature(binding: &BindingDefinition) -> String {
+    let (signature, named_signature) = match &binding.kind {
+        BindingType::Const(_) => (None, None),
+        BindingType::Function(function) => (function.signature.as_ref().map(ToString::to_string), function.named_signature.as_ref()),
+        BindingType::IndexMacro(index_macro) => (None, index_macro.named_signature.as_ref()),
+        BindingType::CodeMacro(code_macro) => (None, code_macro.named_signature.as_ref()),
+    };
+    let named_signature = named_signature.map(|named| named.to_comment().trim_start_matches("# ").to_owned());
+    match (signature, named_signature) {
+        (Some(signature), Some(named)) => format!("{}  {}", signature, named),
+        (signature, named) => signature.or(named).unwrap_or_default(),
+    }
+}
+
+/// Labels can't have the characters LaTeX treats specially, so those are spelled out.
+fn label(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_string(),
+            false => format!("-{:x}-", c as u32),
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => "\\textbackslash{}".to_owned(),
+            '~' => "\\textasciitilde{}".to_owned(),
+            '^' => "\\textasciicircum{}".to_owned(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Converts Markdown to LaTeX, with code in the Uiua font.
+fn markdown_to_latex(markdown: &str) -> String {
+    let root = markdown::to_mdast(markdown, &markdown::ParseOptions::gfm()).expect("Unable to parse markdown");
+    node_to_latex(&root)
+}
+
+fn node_to_latex(node: &Node) -> String {
+    let children = |separator: &str| {
+        node.children()
+            .map(|children| children.iter().map(node_to_latex).collect::<Vec<_>>().join(separator))
+            .unwrap_or_default()
+    };
+
+    match node {
+        Node::Root(_) => children("\n\n"),
+        Node::Paragraph(_) => children(""),
+        Node::Heading(_) => format!("\\paragraph*{{{}}}", children("")),
+        Node::Text(text) => escape(&text.value),
+        Node::Emphasis(_) => format!("\\emph{{{}}}", children("")),
+        Node::Strong(_) => format!("\\textbf{{{}}}", children("")),
+        Node::Delete(_) => children(""),
+        Node::InlineCode(code) => format!("\\uiua{{{}}}", escape(&code.value)),
+        Node::Code(code) => format!("\\begin{{uiuacode}}\n{}\n\\end{{uiuacode}}", code.value),
+        Node::Link(link) => format!("\\href{{{}}}{{{}}}", link.url.replace('%', "\\%").replace('#', "\\#"), children("")),
+        Node::Image(image) => escape(&image.alt),
+        Node::Break(_) => "\\\\\n".to_owned(),
+        Node::Blockquote(_) => format!("\\begin{{quote}}\n{}\n\\end{{quote}}", children("\n\n")),
+        Node::List(list) => {
+            let environment = if list.ordered { "enumerate" } else { "itemize" };
+            format!("\\begin{{{0}}}\n{1}\n\\end{{{0}}}", environment, children("\n"))
+        }
+        Node::ListItem(_) => format!("\\item {}", children("\n\n")),
+        // Raw HTML and anything else without a LaTeX counterpart is left out
+        Node::Html(_) | Node::ThematicBreak(_) => String::new(),
+        _ => children(" "),
+    }
+}
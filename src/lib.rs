@@ -0,0 +1,38 @@
+//! Generates documentation sites for Uiua libraries.
+//!
+//! The `uiua-doc-gen` binary is a thin wrapper around these modules. Tools built on the
+//! generator can use them directly, for example to add [`hooks::MarkdownHook`]s that
+//! expand custom shortcodes in doc comments.
+
+pub mod analysis;
+pub mod ast;
+pub mod badges;
+pub mod benchmark;
+pub mod cache;
+pub mod changes;
+pub mod config;
+pub mod diff;
+pub mod directives;
+pub mod examples;
+pub mod extractor;
+pub mod fonts;
+pub mod formatter;
+pub mod gemini;
+pub mod generator;
+pub mod glossary;
+pub mod hooks;
+pub mod hosting;
+pub mod interface;
+pub mod latex;
+pub mod links;
+pub mod markdown_docs;
+pub mod metadata;
+pub mod registry;
+pub mod sandbox;
+pub mod sanitizer;
+pub mod search;
+pub mod server;
+pub mod signatures;
+pub mod summarizer;
+pub mod update;
+pub mod validator;
@@ -0,0 +1,215 @@
+use crate::extractor::{qualified_name, ItemContent};
+use crate::generator::anchor_id;
+use crate::hooks::MarkdownHook;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use thiserror::Error;
+
+/// Resolves references like `[Geo.Area]` in doc comments to the URL of the item they name.
+///
+/// References are given with `~` between the modules, like `Geo~Area`, whether they were written
+/// with `.` or `~`. Relative URLs are relative to the root of the site, and are adjusted for pages
+/// that aren't at the root. Sites that document several libraries together can add their own
+/// resolvers to link between them.
+pub trait LinkResolver {
+    fn resolve(&self, reference: &str) -> Option<String>;
+}
+
+/// The resolvers references are tried against, in the order they were added. The first URL wins.
+///
+/// Added to the Markdown hooks, it turns the references it resolves into links, and leaves the
+/// others as they were written.
+#[derive(Clone, Default)]
+pub struct LinkResolvers {
+    resolvers: Vec<Rc<dyn LinkResolver>>,
+}
+
+impl LinkResolvers {
+    pub fn add(&mut self, resolver: impl LinkResolver + 'static) {
+        self.resolvers.push(Rc::new(resolver));
+    }
+
+    pub fn resolve(&self, reference: &str) -> Option<String> {
+        let reference = reference.replace('.', "~");
+        self.resolvers.iter().find_map(|resolver| resolver.resolve(&reference))
+    }
+}
+
+impl MarkdownHook for LinkResolvers {
+    fn before_render(&self, markdown: String) -> String {
+        self.before_render_on_page(markdown, "")
+    }
+
+    /// Defines the resolved references before the Markdown, so they render as regular links.
+    /// References the author defined themselves are left to their definitions.
+    fn before_render_on_page(&self, markdown: String, root: &str) -> String {
+        let definitions = find_references(&markdown)
+            .into_iter()
+            .filter(|reference| !markdown.lines().any(|line| line.trim_start().starts_with(&format!("[{}]:", reference))))
+            .filter_map(|reference| {
+                self.resolve(reference)
+                    .map(|url| format!("[{}]: <{}>", reference, relative_to(root, url)))
+            })
+            .collect::<Vec<_>>();
+        match definitions.is_empty() {
+            true => markdown,
+            false => format!("{}\n\n{}", definitions.join("\n"), markdown),
+        }
+    }
+}
+
+/// A URL relative to the root of the site, made relative to a page that's `root` away from it. Absolute URLs and
+/// links within the page are left as they are.
+fn relative_to(root: &str, url: String) -> String {
+    let path = &url[..url.find(['?', '#']).unwrap_or(url.len())];
+    match path.is_empty() || path.starts_with('/') || path.contains(':') {
+        true => url,
+        false => format!("{}{}", root, url),
+    }
+}
+
+/// Shortcut references to names, like `[Area]` or `[Geo~Area]`, that aren't part of an inline link.
+fn find_references(markdown: &str) -> BTreeSet<&str> {
+    let mut references = BTreeSet::new();
+    let mut rest = markdown;
+    while let Some(start) = rest.find('[') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(']') else {
+            break;
+        };
+        let reference = &after[..end];
+        let escaped = rest[..start].ends_with('\\');
+        let followed = after[end + 1..].starts_with(['(', '[', ':']);
+        if !escaped && !followed && is_reference(reference) {
+            references.insert(reference);
+        }
+        rest = after;
+    }
+    references
+}
+
+fn is_reference(text: &str) -> bool {
+    text.split(['.', '~'])
+        .all(|part| part.starts_with(|c: char| c.is_alphabetic()) && part.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+/// Resolves references to the library's own items, to their panels on the front page.
+pub struct LocalLinks {
+    anchors: HashMap<String, String>,
+}
+
+impl LocalLinks {
+    pub fn new(items: &[ItemContent]) -> Self {
+        let mut anchors = HashMap::new();
+        collect_anchors(&[], items, &mut anchors);
+        LocalLinks { anchors }
+    }
+}
+
+impl LinkResolver for LocalLinks {
+    fn resolve(&self, reference: &str) -> Option<String> {
+        self.anchors.get(reference).map(|anchor| format!("index.html#{}", anchor))
+    }
+}
+
+fn collect_anchors(path: &[String], items: &[ItemContent], anchors: &mut HashMap<String, String>) {
+    for item in items {
+        let name = match item {
+            ItemContent::Binding(binding) if binding.is_documented() => &binding.name,
+            ItemContent::Module(module) if module.has_public_items() => {
+                let path = path.iter().cloned().chain(std::iter::once(module.name.clone())).collect::<Vec<_>>();
+                collect_anchors(&path, &module.items, anchors);
+                &module.name
+            }
+            ItemContent::Data(data) => match &data.name {
+                Some(name) => name,
+                None => continue,
+            },
+            ItemContent::Variant(variant) => &variant.name,
+            _ => continue,
+        };
+        anchors.insert(qualified_name(path, name), anchor_id(path, name));
+    }
+}
+
+/// Resolves references to items of other libraries, like `[OtherLib.Func]`, from a map of library
+/// names to the URLs of their documentation.
+#[derive(Debug, Clone, Default)]
+pub struct ExternalLinks {
+    libraries: HashMap<String, String>,
+}
+
+#[derive(Error, Debug)]
+pub enum LinkMapError {
+    #[error("Unable to read the link map: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid link map: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl ExternalLinks {
+    pub fn new(libraries: HashMap<String, String>) -> Self {
+        ExternalLinks { libraries }
+    }
+
+    /// Reads the map from a JSON object, like `{"OtherLib": "https://example.com/other-lib/index.html"}`.
+    pub fn read(path: &Path) -> Result<Self, LinkMapError> {
+        Ok(ExternalLinks::new(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+}
+
+impl LinkResolver for ExternalLinks {
+    /// A reference to the library itself links to its documentation, and one to an item to the
+    /// item's anchor, which is named the way this generator names them.
+    fn resolve(&self, reference: &str) -> Option<String> {
+        let mut parts = reference.split('~').map(str::to_owned).collect::<Vec<_>>();
+        let url = self.libraries.get(&parts.remove(0))?;
+        match parts.split_last() {
+            Some((name, module_path)) => Some(format!("{}#{}", url, anchor_id(module_path, name))),
+            None => Some(url.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::MarkdownHooks;
+
+    struct Items;
+
+    impl LinkResolver for Items {
+        fn resolve(&self, reference: &str) -> Option<String> {
+            match reference {
+                "Foo" => Some("index.html#Foo".to_owned()),
+                "Other~Bar" => Some("https://example.com/other/index.html#Bar".to_owned()),
+                _ => None,
+            }
+        }
+    }
+
+    fn hooks() -> MarkdownHooks {
+        let mut resolvers = LinkResolvers::default();
+        resolvers.add(Items);
+        let mut hooks = MarkdownHooks::default();
+        hooks.add(resolvers);
+        hooks
+    }
+
+    #[test]
+    fn links_from_the_root_of_the_site() {
+        let html = hooks().render("See [Foo] and [Other.Bar].");
+        assert!(html.contains(r#"href="index.html#Foo""#), "{}", html);
+        assert!(html.contains(r#"href="https://example.com/other/index.html#Bar""#), "{}", html);
+    }
+
+    #[test]
+    fn links_from_a_page_in_a_directory() {
+        let html = hooks().on_page("../").render("See [Foo] and [Other.Bar].");
+        assert!(html.contains(r#"href="../index.html#Foo""#), "{}", html);
+        assert!(html.contains(r#"href="https://example.com/other/index.html#Bar""#), "{}", html);
+    }
+}
@@ -1,15 +1,41 @@
-mod extractor;
-mod formatter;
-mod generator;
-mod summarizer;
-
-use crate::summarizer::summarize_content;
-use clap::Parser;
-use extractor::extract_uiua_definitions;
+use clap::{Args, Parser, Subcommand};
 use std::env;
+use std::ffi::{OsStr, OsString};
 use std::fs;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
 use thiserror::Error;
+use uiua_doc_gen::analysis::{
+    binding_coverage, find_directive_problems, find_shadowed_names, find_undocumented_bindings, metrics_report, required_permissions, BindingCoverage,
+};
+use uiua_doc_gen::ast::{ast_json, parse_library_ast};
+use uiua_doc_gen::changes::{build_api_snapshot, build_time, read_api_snapshot};
+use uiua_doc_gen::config::{read_config_file, GeneratorConfig, CONFIG_FILE, CONFIG_TEMPLATE};
+use uiua_doc_gen::diff::{compare_directories, print_diffs};
+use uiua_doc_gen::examples::{copy_data_files, extract_examples};
+use uiua_doc_gen::extractor::{
+    drop_benchmarks, exclude_modules, extract_entry_definitions, extract_uiua_definitions, hide_code, include_private, read_extracted_items,
+    ExtractOptions, FileContent, LIBRARY_FILE,
+};
+use uiua_doc_gen::fonts::FontStrategy;
+use uiua_doc_gen::gemini::generate_gemini_capsule;
+use uiua_doc_gen::generator::{self, GeneratorOptions, ModuleOrder, Theme, WorkspaceLibrary};
+use uiua_doc_gen::glossary::read_glossary;
+use uiua_doc_gen::hooks::MarkdownHooks;
+use uiua_doc_gen::hosting::{base_path, Hosting};
+use uiua_doc_gen::interface::generate_interface_file;
+use uiua_doc_gen::latex::generate_latex_reference;
+use uiua_doc_gen::links::{ExternalLinks, LinkResolver, LinkResolvers, LocalLinks};
+use uiua_doc_gen::markdown_docs::generate_markdown_docs;
+use uiua_doc_gen::metadata::{infer_project_name, read_project_file, ProjectFile, ProjectMetadata, PROJECT_FILE};
+use uiua_doc_gen::registry::build_registry_package;
+use uiua_doc_gen::sandbox::SandboxSys;
+use uiua_doc_gen::sanitizer::RawHtmlPolicy;
+use uiua_doc_gen::server::{serve, watch_library, Builds, Highlighter, MissingLinks};
+use uiua_doc_gen::summarizer::{configurable_sections, generate_model_file, pluralize, summarize_content, summarize_entry_point};
+use uiua_doc_gen::update::{self_update, update_notice, UpdateOutcome};
 
 #[derive(Error, Debug)]
 enum AppError {
@@ -24,16 +50,304 @@ enum AppError {
 
     #[error("Permission denied: {0}")]
     PermissionDenied(PathBuf),
+
+    #[error("The output directory can't contain the library: {0}")]
+    OutputContainsLibrary(PathBuf),
+
+    #[error("{0} already exists, pass --force to replace it")]
+    ConfigFileExists(PathBuf),
+
+    #[error("Unable to generate the docs to compare them with {0}")]
+    DiffBuildFailed(PathBuf),
+
+    #[error("No libraries to document, pass --dir or list them in the [workspace] members of {CONFIG_FILE}")]
+    NoWorkspaceMembers,
+
+    #[error("{0} and {1} are both named {2}, give one of them another name in its {PROJECT_FILE}")]
+    DuplicateLibraryName(PathBuf, PathBuf, String),
 }
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
+// Without a subcommand the docs are built, so the commands written before there were subcommands keep working
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    build: BuildArgs,
+}
+
+/// Where the library is and how it's compiled, for the subcommands that extract its items.
+#[derive(Args)]
+struct LibraryArgs {
+    /// Directory of the library (defaults to the current directory)
     #[arg(short, long)]
     dir: Option<PathBuf>,
 
+    /// Document the library as it was at a git revision, like a tag or a commit, instead of its working tree
+    #[arg(long, value_name = "REF")]
+    rev: Option<String>,
+
+    /// The checkout of `--rev` to document, passed when the generator runs itself on it
+    #[arg(long, hide = true)]
+    snapshot: Option<PathBuf>,
+
+    /// The library's entry file, relative to its directory (defaults to the lib in uiua-doc.toml, or lib.ua)
+    #[arg(long, value_name = "FILE")]
+    lib: Option<PathBuf>,
+
+    /// Another entry point to document next to the library, like main.ua, in a section of its own (can be repeated)
+    #[arg(long)]
+    entry: Vec<PathBuf>,
+
+    /// Document the library from its source without compiling it, for libraries that don't compile.
+    /// Only signatures declared in the code are shown
+    #[arg(long)]
+    no_compile: bool,
+
+    /// Compile and run the library and its examples in a sandbox that can only read the library's own files
+    #[arg(long)]
+    sandbox: bool,
+
+    /// Compile the library even if its sources haven't changed since the last run
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Leave the files or modules matching a pattern out of the docs, like internal/*.ua or Geo.Internal (can be
+    /// repeated, added to the exclude patterns in uiua-doc.toml)
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+}
+
+/// Which items of the library are documented.
+#[derive(Args)]
+struct ItemArgs {
+    /// Document private bindings too, marked as private, for internal docs
+    #[arg(long)]
+    include_private: bool,
+
+    /// Leave the code of bindings out of the docs and the JSON output, keeping their signatures and comments
+    #[arg(long)]
+    hide_code: bool,
+}
+
+#[derive(Args)]
+struct BuildArgs {
+    #[command(flatten)]
+    library: LibraryArgs,
+
+    #[command(flatten)]
+    items: ItemArgs,
+
+    /// Directory to write the docs to (defaults to the output in uiua-doc.toml, or doc-site in the library's directory).
+    /// Its contents are replaced
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// The kind of documentation to generate
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
+
+    /// Name of the library (defaults to the name in uiua.toml, the git remote or the directory)
     #[arg(short, long)]
-    name: String,
+    name: Option<String>,
+
+    /// Title of the docs (defaults to the title in uiua-doc.toml, or the library's name)
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Repository of the library (e.g. github.com/user/repo), used for import snippets
+    #[arg(short, long)]
+    repository: Option<String>,
+
+    /// URL the docs are published at, like https://example.com/mylib/docs/, used for links in registry.json and the
+    /// cache headers of --hosting (defaults to the base_url in uiua-doc.toml)
+    #[arg(long, alias = "docs-url", value_name = "URL")]
+    base_url: Option<String>,
+
+    /// Load the bodies of item panels only when they are expanded, keeping large pages small
+    #[arg(long)]
+    lazy_panels: bool,
+
+    /// Render the items printed by a previous `extract` run, instead of extracting them from the library again
+    #[arg(long, value_name = "FILE")]
+    from_json: Option<PathBuf>,
+
+    /// Soft-wrap code lines longer than this many columns, instead of scrolling them
+    #[arg(long, value_name = "COLUMN")]
+    wrap_code: Option<usize>,
+
+    /// Write the search index to a file of its own, fetched when the search is first used, when it's larger than
+    /// this many kilobytes. 0 always writes it to a file
+    #[arg(long, value_name = "KB")]
+    search_index_limit: Option<usize>,
+
+    /// Stop before rendering, and write the model the site would be rendered from to model.json instead. The same as
+    /// `--format json`
+    #[arg(long, conflicts_with = "format")]
+    no_render: bool,
+
+    /// Report the documented bindings that no test scope or example uses
+    #[arg(long)]
+    coverage: bool,
+
+    /// Show size and complexity metrics on binding panels and report bindings that stand out
+    #[arg(long)]
+    metrics: bool,
+
+    /// The library file whose `!doc` comments form the front page (defaults to the library's entry file)
+    #[arg(long)]
+    landing: Option<PathBuf>,
+
+    /// Also write every item panel as a standalone page that other sites can embed
+    #[arg(long)]
+    embeds: bool,
+
+    /// Also write a page for every item with its name and summary, so links to it show them on social platforms
+    #[arg(long)]
+    share_pages: bool,
+
+    /// The `api.json` of a previously published version, to list the API changes in a feed
+    #[arg(long)]
+    previous_api: Option<PathBuf>,
+
+    /// Name of the release the docs are generated for, used in the feed (defaults to the date)
+    #[arg(long)]
+    release: Option<String>,
+
+    /// URL of the docs of a release, with {version} where the release goes, like https://example.com/mylib/{version}/,
+    /// to link the releases in the --previous-api snapshot from the header (defaults to the version_url in
+    /// uiua-doc.toml)
+    #[arg(long, value_name = "URL")]
+    version_url: Option<String>,
+
+    /// How the modules are ordered in the sidebar
+    #[arg(long, value_enum, default_value_t)]
+    module_order: ModuleOrder,
+
+    /// Where the site loads the font for code from (defaults to the font in uiua-doc.toml, or subset)
+    #[arg(long, value_enum)]
+    font: Option<FontStrategy>,
+
+    /// The theme pages are shown in until the reader switches it (defaults to the theme in uiua-doc.toml, or dark)
+    #[arg(long, value_enum)]
+    theme: Option<Theme>,
+
+    /// Where the site is published, to write a file that lets browsers cache its fingerprinted files
+    #[arg(long, value_enum)]
+    hosting: Option<Hosting>,
+
+    /// Make the output byte-identical between builds of the same source: files are named after their content,
+    /// benchmark timings are left out, and the API snapshot is dated with SOURCE_DATE_EPOCH (or the Unix epoch)
+    #[arg(long)]
+    deterministic: bool,
+
+    /// What to do with HTML written in doc comments and Markdown files
+    #[arg(long, value_enum, default_value_t)]
+    raw_html: RawHtmlPolicy,
+
+    /// A JSON object of library names and the URLs of their docs, to link references like `[OtherLib.Func]`
+    #[arg(long)]
+    link_map: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    /// A website
+    #[default]
+    Html,
+    /// A capsule of gemtext pages for Gemini
+    Gemini,
+    /// A reference appendix for LaTeX documents
+    Latex,
+    /// Markdown files, for wikis and repositories that render Markdown
+    Markdown,
+    /// The model the site is rendered from, as JSON for other tools
+    Json,
+    /// The names, signatures, comments and data definitions of the public items, without any code
+    ApiOnly,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the docs, which is also what happens without a subcommand
+    Build(BuildArgs),
+    /// Generate the site and serve it on localhost, regenerating it and reloading the page when the library changes
+    Serve {
+        /// The port to listen on
+        #[arg(short, long, default_value_t = 8000)]
+        port: u16,
+
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Compile the library and report the problems building its docs would warn about, without writing them. Fails
+    /// when a public binding has no doc comment, or a function or modifier no signature comment
+    Check {
+        #[command(flatten)]
+        library: LibraryArgs,
+
+        /// Also report the documented bindings that no test scope or example uses
+        #[arg(long)]
+        coverage: bool,
+
+        /// Exit with an error when there are warnings, for CI
+        #[arg(long)]
+        strict: bool,
+
+        /// Also generate the docs and compare them with the docs in this directory, printing a diff of the files
+        /// that changed and exiting with an error when they're out of date
+        #[arg(long, value_name = "DIR")]
+        diff: Option<PathBuf>,
+
+        /// Options of `build` the docs are generated with for `--diff`, after `--`
+        #[arg(last = true)]
+        build: Vec<OsString>,
+    },
+    /// Write a uiua-doc.toml with the generator's options to the library's directory
+    Init {
+        /// Directory of the library (defaults to the current directory)
+        #[arg(short, long)]
+        dir: Option<PathBuf>,
+
+        /// Replace the library's uiua-doc.toml if it already has one
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the items extracted from the library as JSON
+    Extract {
+        #[command(flatten)]
+        library: LibraryArgs,
+
+        #[command(flatten)]
+        items: ItemArgs,
+
+        /// Print the parsed syntax tree instead, with the spans of all words and comments
+        #[arg(long)]
+        ast: bool,
+    },
+    /// Document several libraries into one site, with a landing page listing them
+    Workspace {
+        /// Directories of the libraries (defaults to the members in the uiua-doc.toml of the current directory)
+        #[arg(short, long)]
+        dir: Vec<PathBuf>,
+
+        /// Directory to write the site to (defaults to the output in uiua-doc.toml, or ./doc-site)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// The title of the landing page (defaults to the title in uiua-doc.toml, or the name of the current directory)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Options of `build` for every library, after `--`
+        #[arg(last = true)]
+        build: Vec<OsString>,
+    },
+    /// Replace this binary with the latest release from GitHub
+    SelfUpdate,
 }
 
 fn validate_directory(dir: Option<PathBuf>) -> Result<PathBuf, AppError> {
@@ -72,41 +386,897 @@ fn validate_directory(dir: Option<PathBuf>) -> Result<PathBuf, AppError> {
     Ok(working_dir)
 }
 
-fn main() {
-    let cli = Cli::parse();
+/// A path given on the command line, which is relative to the directory the generator was started in.
+fn invocation_path(path: &Path) -> PathBuf {
+    env::current_dir().map(|dir| dir.join(path)).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Resolves the directory the docs are written to. The one passed on the command line is relative to the current
+/// directory, and the one in the config file to the library's directory.
+fn output_directory(output: Option<PathBuf>, configured: Option<&Path>, working_dir: &Path) -> Result<PathBuf, AppError> {
+    let output = match (output, configured) {
+        (Some(output), _) => env::current_dir()?.join(output),
+        (None, Some(configured)) => working_dir.join(configured),
+        (None, None) => return Ok(working_dir.join("doc-site")),
+    };
+
+    // The output directory is emptied before the docs are written, so it can't be the library's directory or one above it
+    if output.exists() && fs::canonicalize(working_dir)?.starts_with(fs::canonicalize(&output)?) {
+        return Err(AppError::OutputContainsLibrary(output));
+    }
+    Ok(output)
+}
+
+/// Finds a file of the library by its path relative to the library's directory.
+fn find_library_file<'a>(files: &'a [FileContent], landing: &Path) -> Option<&'a FileContent> {
+    let landing = landing
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    files.iter().find(|file| file.relative_path == landing)
+}
+
+fn run_self_update() {
+    match self_update() {
+        Ok(UpdateOutcome::UpToDate(version)) => println!("uiua-doc-gen {} is the latest version.", version),
+        Ok(UpdateOutcome::Updated { from, to }) => println!("Updated uiua-doc-gen from {} to {}.", from, to),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
 
-    let working_dir = match validate_directory(cli.dir) {
+/// Generates the site by running `build` with the same options, so a library that stops compiling
+/// while it's being edited doesn't stop the server.
+fn run_server(working_dir: &Path, output_dir: &Path, port: u16, highlighter: Highlighter) {
+    let executable = match env::current_exe() {
+        Ok(executable) => executable,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let arguments = build_arguments();
+    let generate = move || {
+        std::process::Command::new(&executable)
+            .args(&arguments)
+            .status()
+            .is_ok_and(|status| status.success())
+    };
+
+    let builds = Builds::default();
+    if generate() {
+        builds.finished(output_dir);
+    }
+
+    let watched_builds = builds.clone();
+    let (working_dir, watched_output_dir) = (working_dir.to_path_buf(), output_dir.to_path_buf());
+    thread::spawn(move || {
+        watch_library(&working_dir, &watched_output_dir, || {
+            println!("The library changed, regenerating.");
+            if generate() {
+                watched_builds.finished(&watched_output_dir);
+            }
+        })
+    });
+
+    // The missing files and anchors readers ran into are listed once the server is stopped
+    let missing = MissingLinks::default();
+    let report = missing.clone();
+    let stopped = ctrlc::set_handler(move || {
+        if let Some(report) = report.report() {
+            eprintln!("{}", report);
+        }
+        std::process::exit(0);
+    });
+    if let Err(err) = stopped {
+        eprintln!("Warning: Unable to list the missing files and anchors when the server stops: {}", err);
+    }
+
+    println!("Serving the docs at http://localhost:{}/", port);
+    if let Err(err) = serve(output_dir, port, builds, highlighter, missing) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+/// Checks the revision out into a temporary worktree and documents it by running the generator again, so the worktree
+/// is removed whether generating succeeds or not. The output and `uiua-doc.toml` stay those of the working tree.
+fn run_at_revision(working_dir: &Path, rev: &str) -> ! {
+    // Git's own message is the clearest explanation of what went wrong
+    let git = |args: &[&OsStr]| {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(working_dir)
+            .args(args)
+            .output()
+            .map_err(|err| err.to_string())?;
+        match output.status.success() {
+            true => Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned()),
+            false => Err(String::from_utf8_lossy(&output.stderr).trim().to_owned()),
+        }
+    };
+
+    // The library can be in a subdirectory of the repository
+    let prefix = match git(&["rev-parse".as_ref(), "--show-prefix".as_ref()]) {
+        Ok(prefix) => prefix,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let worktree = env::temp_dir().join(format!("uiua-doc-gen-{}", std::process::id()));
+    let added = git(&[
+        "worktree".as_ref(),
+        "add".as_ref(),
+        "--detach".as_ref(),
+        worktree.as_os_str(),
+        rev.as_ref(),
+    ]);
+    if let Err(err) = added {
+        eprintln!("Error: Unable to check out the revision {}: {}", rev, err);
+        std::process::exit(1);
+    }
+
+    // The snapshot goes right after the subcommand, since the arguments after `--` of `check` are passed to `build`
+    let status = env::current_exe().and_then(|executable| {
+        std::process::Command::new(executable)
+            .args(env::args_os().skip(1).take(1))
+            .arg("--snapshot")
+            .arg(worktree.join(prefix))
+            .args(env::args_os().skip(2))
+            .status()
+    });
+    if git(&["worktree".as_ref(), "remove".as_ref(), "--force".as_ref(), worktree.as_os_str()]).is_err() {
+        eprintln!("Warning: Unable to remove the worktree at {}", worktree.display());
+    }
+    match status {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The arguments of the `serve` subcommand, as the arguments of `build` with the same options.
+fn build_arguments() -> Vec<OsString> {
+    // Options can't come before a subcommand, so `serve` is always the first argument
+    let mut arguments = env::args_os().skip(2);
+    let mut build = vec![OsString::from("build")];
+    while let Some(argument) = arguments.next() {
+        match argument.to_str() {
+            Some("-p" | "--port") => {
+                arguments.next();
+            }
+            Some(argument) if argument.starts_with("--port=") || argument.strip_prefix("-p").is_some_and(|port| port.parse::<u16>().is_ok()) => {}
+            _ => build.push(argument),
+        }
+    }
+    build
+}
+
+fn print_coverage_report(coverage: &[BindingCoverage]) {
+    let tested = coverage.iter().filter(|binding| binding.tested).count();
+    let exampled = coverage.iter().filter(|binding| binding.exampled).count();
+    println!(
+        "Of {} documented bindings, {} are used by tests and {} by examples.",
+        coverage.len(),
+        tested,
+        exampled
+    );
+
+    let uncovered = coverage.iter().filter(|binding| !binding.tested || !binding.exampled).collect::<Vec<_>>();
+    if !uncovered.is_empty() {
+        println!("Bindings missing tests or examples:");
+        for binding in uncovered {
+            let missing = match (binding.tested, binding.exampled) {
+                (false, false) => "no tests or examples",
+                (false, true) => "no tests",
+                _ => "no examples",
+            };
+            println!("  {}: {}", binding.name, missing);
+        }
+    }
+}
+
+/// The library's directory and its `uiua-doc.toml`.
+fn open_library(dir: Option<PathBuf>) -> (PathBuf, GeneratorConfig) {
+    let working_dir = match validate_directory(dir) {
         Ok(dir) => dir,
         Err(err) => {
             eprintln!("Error: {}", err);
             std::process::exit(1);
         }
     };
+    match read_config_file(&working_dir) {
+        Ok(config) => (working_dir, config.unwrap_or_default()),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
 
-    let extracted = match extract_uiua_definitions(&working_dir) {
+/// The directory the library's files are read from, which is a checkout of `--rev` when it's given. Documenting a
+/// revision runs the generator again on the checkout, so this doesn't return then.
+fn source_directory(working_dir: &Path, library: &LibraryArgs) -> PathBuf {
+    match (&library.rev, &library.snapshot) {
+        (Some(rev), None) => run_at_revision(working_dir, rev),
+        (_, Some(snapshot)) => snapshot.clone(),
+        (None, None) => working_dir.to_path_buf(),
+    }
+}
+
+fn extract_options(working_dir: &Path, library: &LibraryArgs, config: &GeneratorConfig) -> ExtractOptions {
+    let sys_backend = match library.sandbox.then(|| SandboxSys::new(working_dir)).transpose() {
+        Ok(sandbox) => sandbox.map(|sandbox| Arc::new(sandbox) as _),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    ExtractOptions {
+        parse_only: library.no_compile,
+        sys_backend,
+        // The worktrees of --rev are removed after the run, so their cache would never be read
+        cache: !library.no_cache && library.snapshot.is_none(),
+        library_file: library.lib.clone().or_else(|| config.lib.clone()),
+        exclude: [config.exclude.clone(), library.exclude.clone()].concat(),
+    }
+}
+
+/// Extracts the items of the library, followed by the files of its other entry points.
+fn extract_library(working_dir: &Path, entries: &[PathBuf], options: &ExtractOptions) -> Vec<FileContent> {
+    let mut extracted = match extract_uiua_definitions(working_dir, options) {
         Ok(extracted) => extracted,
         Err(err) => {
             eprintln!("Error: {}", err);
             std::process::exit(1);
         }
     };
+    // Entry points are compiled on their own, and only their own file is added, next to the library's files
+    for entry in entries {
+        match extract_entry_definitions(working_dir, entry, options) {
+            Ok(files) => {
+                let entry_file = files.into_iter().find(|file| file.main);
+                if let Some(entry_file) = entry_file.filter(|file| find_library_file(&extracted, Path::new(&file.relative_path)).is_none()) {
+                    extracted.push(FileContent { main: false, ..entry_file });
+                }
+            }
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+    extracted
+}
+
+fn read_project(working_dir: &Path) -> ProjectFile {
+    match read_project_file(working_dir) {
+        Ok(project_file) => project_file.unwrap_or_default(),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
 
+fn main_file(extracted: &[FileContent]) -> &FileContent {
     // TODO: handle more than one file
-    let maybe_main_file = extracted.iter().find(|item| item.main);
-    let main_file = match maybe_main_file {
+    match extracted.iter().find(|item| item.main) {
         Some(main_file) => main_file,
         None => {
             eprintln!("No main file found");
             std::process::exit(1);
         }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match cli.command.unwrap_or(Command::Build(cli.build)) {
+        Command::Build(build) => run_build(build),
+        Command::Serve { port, build } => run_serve(port, build),
+        Command::Check {
+            library,
+            coverage,
+            strict,
+            diff,
+            build,
+        } => run_check(library, coverage, strict, diff, build),
+        Command::Init { dir, force } => run_init(dir, force),
+        Command::Extract { library, items, ast } => run_extract(library, items, ast),
+        Command::Workspace { dir, output, title, build } => run_workspace(dir, output, title, build),
+        Command::SelfUpdate => run_self_update(),
+    }
+}
+
+fn run_serve(port: u16, build: BuildArgs) {
+    let (working_dir, config) = open_library(build.library.dir.clone());
+    let output_dir = match output_directory(build.output, config.output.as_deref(), &working_dir) {
+        Ok(dir) => dir,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let options = extract_options(&working_dir, &build.library, &config);
+    let library_file = (!options.parse_only).then(|| working_dir.join(options.library_file()));
+    let highlighter = Highlighter::new(library_file, options.backend(), build.wrap_code);
+    run_server(&working_dir, &output_dir, port, highlighter);
+}
+
+fn run_workspace(dirs: Vec<PathBuf>, output: Option<PathBuf>, title: Option<String>, build: Vec<OsString>) {
+    let (root, config) = open_library(None);
+    let exit = |err: AppError| -> ! {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    };
+
+    // The members in the config file are relative to its directory
+    let dirs = match dirs.is_empty() {
+        true => config.workspace.members.iter().map(|member| root.join(member)).collect(),
+        false => dirs,
+    };
+    if dirs.is_empty() {
+        exit(AppError::NoWorkspaceMembers);
+    }
+
+    let output_dir = output_directory(output, config.output.as_deref(), &root).unwrap_or_else(|err| exit(err));
+    let mut libraries: Vec<(PathBuf, WorkspaceLibrary)> = Vec::new();
+    for dir in dirs {
+        let (member, member_config) = open_library(Some(dir));
+        if let Err(err) = output_directory(Some(output_dir.clone()), None, &member) {
+            exit(err);
+        }
+
+        let project_file = read_project(&member);
+        let Some(name) = project_file.name.or_else(|| infer_project_name(&member)) else {
+            eprintln!("No library name for {}, set it in its {}", member.display(), PROJECT_FILE);
+            std::process::exit(1);
+        };
+        // Each library's docs are in a directory named after it
+        let directory = name
+            .chars()
+            .map(|c| match c.is_alphanumeric() || c == '-' || c == '_' {
+                true => c,
+                false => '-',
+            })
+            .collect::<String>();
+        if let Some((other, _)) = libraries.iter().find(|(_, library)| library.directory == directory) {
+            exit(AppError::DuplicateLibraryName(other.clone(), member, directory));
+        }
+
+        libraries.push((
+            member,
+            WorkspaceLibrary {
+                title: member_config.title.unwrap_or(name),
+                version: project_file.version,
+                description: project_file.description,
+                directory,
+            },
+        ));
+    }
+
+    if output_dir.exists() {
+        if let Err(err) = fs::remove_dir_all(&output_dir) {
+            exit(AppError::Io(err));
+        }
+    }
+    if let Err(err) = fs::create_dir_all(&output_dir) {
+        exit(AppError::Io(err));
+    }
+
+    // Every library is built on its own, the same way `build` would document it
+    let executable = env::current_exe().unwrap_or_else(|err| exit(AppError::Io(err)));
+    for (member, library) in &libraries {
+        println!("Documenting {}.", library.title);
+        let status = std::process::Command::new(&executable)
+            .arg("build")
+            .arg("--dir")
+            .arg(member)
+            .arg("--output")
+            .arg(output_dir.join(&library.directory))
+            .args(&build)
+            .status();
+        if !status.is_ok_and(|status| status.success()) {
+            eprintln!("Error: Unable to document {}", member.display());
+            std::process::exit(1);
+        }
+    }
+
+    let title = title
+        .or(config.title)
+        .or_else(|| infer_project_name(&root))
+        .unwrap_or_else(|| "Libraries".to_owned());
+    let libraries = libraries.into_iter().map(|(_, library)| library).collect::<Vec<_>>();
+    if let Err(err) = generator::generate_workspace_page(&output_dir, &title, &libraries, config.theme.default.unwrap_or_default()) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+    println!(
+        "Documented {} {} in {}.",
+        libraries.len(),
+        pluralize(libraries.len(), "library", "libraries"),
+        output_dir.display()
+    );
+}
+
+fn run_init(dir: Option<PathBuf>, force: bool) {
+    let working_dir = match validate_directory(dir) {
+        Ok(dir) => dir,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let path = working_dir.join(CONFIG_FILE);
+    if path.exists() && !force {
+        eprintln!("Error: {}", AppError::ConfigFileExists(path));
+        std::process::exit(1);
+    }
+    if let Err(err) = fs::write(&path, CONFIG_TEMPLATE) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+    println!("Created {}.", path.display());
+}
+
+fn run_extract(library: LibraryArgs, items: ItemArgs, ast: bool) {
+    let (root, config) = open_library(library.dir.clone());
+    let working_dir = source_directory(&root, &library);
+
+    if ast {
+        let library_file = library.lib.as_deref().or(config.lib.as_deref()).unwrap_or(Path::new(LIBRARY_FILE));
+        match parse_library_ast(&working_dir, library_file) {
+            Ok(files) => println!("{}", ast_json(&files)),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut extracted = extract_library(&working_dir, &library.entry, &extract_options(&working_dir, &library, &config));
+    for shadowed in find_shadowed_names(&extracted) {
+        eprintln!("Warning: {}", shadowed.message());
+    }
+
+    let project_file = read_project(&working_dir);
+    hide_code(&mut extracted, items.hide_code);
+    if items.include_private {
+        include_private(&mut extracted);
+    }
+    let excluded = [project_file.exclude_modules, config.exclude_modules].concat();
+    for module in exclude_modules(&mut extracted, &excluded) {
+        eprintln!("Warning: The excluded module {} doesn't exist", module);
+    }
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&extracted).expect("Unable to serialize the extracted items")
+    );
+}
+
+fn run_check(library: LibraryArgs, coverage: bool, strict: bool, diff: Option<PathBuf>, build: Vec<OsString>) {
+    let (root, config) = open_library(library.dir.clone());
+    let working_dir = source_directory(&root, &library);
+    // Extracting the library changes the current directory, which the options of `build` are relative to
+    let invocation_dir = env::current_dir().unwrap_or_default();
+    let diff = diff.as_deref().map(invocation_path);
+    // Checks run in CI, where the cache would only be written and never read, so the library is always compiled
+    let extract_options = ExtractOptions {
+        cache: false,
+        ..extract_options(&working_dir, &library, &config)
+    };
+    let mut extracted = extract_library(&working_dir, &library.entry, &extract_options);
+    let project_file = read_project(&working_dir);
+
+    let mut warnings = find_shadowed_names(&extracted)
+        .iter()
+        .map(|shadowed| shadowed.message())
+        .collect::<Vec<_>>();
+    let excluded = [project_file.exclude_modules, config.exclude_modules].concat();
+    warnings.extend(
+        exclude_modules(&mut extracted, &excluded)
+            .into_iter()
+            .map(|module| format!("The excluded module {} doesn't exist", module)),
+    );
+    warnings.extend(
+        project_file
+            .sections
+            .keys()
+            .filter(|key| !configurable_sections().contains(&key.as_str()))
+            .map(|key| format!("Unknown section in {}: {}", PROJECT_FILE, key)),
+    );
+    let main_file = main_file(&extracted);
+    warnings.extend(find_directive_problems(&extracted, main_file).iter().map(|problem| problem.message()));
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    if coverage {
+        let examples = match extract_examples(&working_dir, &extract_options) {
+            Ok(examples) => examples,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        };
+        print_coverage_report(&binding_coverage(&main_file.items, &extracted, &examples));
+    }
+
+    let undocumented = find_undocumented_bindings(&main_file.items);
+    if !undocumented.is_empty() {
+        println!("Bindings missing documentation:");
+        for binding in &undocumented {
+            println!("  {}", binding.message());
+        }
+    }
+
+    let up_to_date = match &diff {
+        Some(docs) => docs_up_to_date(&root, &invocation_dir, docs, &library, &build),
+        None => true,
+    };
+
+    match (undocumented.len(), warnings.len()) {
+        (0, 0) => println!("Checked the library, no problems."),
+        (undocumented, warnings) => println!(
+            "Checked the library, {} undocumented {} and {} {}.",
+            undocumented,
+            pluralize(undocumented, "binding", "bindings"),
+            warnings,
+            pluralize(warnings, "warning", "warnings")
+        ),
+    }
+    if !undocumented.is_empty() || (strict && !warnings.is_empty()) || !up_to_date {
+        std::process::exit(1);
+    }
+}
+
+/// Generates the docs into a temporary directory with `build` and the library options of `check`, and compares them
+/// with `docs`, printing the differences. The docs are generated deterministically, since they'd never match otherwise.
+fn docs_up_to_date(root: &Path, invocation_dir: &Path, docs: &Path, library: &LibraryArgs, build: &[OsString]) -> bool {
+    let generated = env::temp_dir().join(format!("uiua-doc-gen-check-{}", std::process::id()));
+    let exit = |err: AppError| -> ! {
+        let _ = fs::remove_dir_all(&generated);
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    };
+    let executable = env::current_exe().unwrap_or_else(|err| exit(AppError::Io(err)));
+
+    let mut arguments = vec![
+        OsString::from("build"),
+        "--dir".into(),
+        root.into(),
+        "--output".into(),
+        generated.clone().into(),
+    ];
+    arguments.extend(library_arguments(library));
+    // Checks leave no files behind, which includes the cache
+    for flag in ["--deterministic", "--no-cache"] {
+        if !build.iter().chain(&arguments).any(|argument| argument == flag) {
+            arguments.push(flag.into());
+        }
+    }
+    arguments.extend(build.iter().cloned());
+
+    println!("Generating the docs to compare them with {}.", docs.display());
+    let status = std::process::Command::new(executable)
+        .current_dir(invocation_dir)
+        .args(&arguments)
+        .stdout(std::process::Stdio::null())
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(_) => exit(AppError::DiffBuildFailed(docs.to_path_buf())),
+        Err(err) => exit(AppError::Io(err)),
+    }
+    let changes = compare_directories(docs, &generated).unwrap_or_else(|err| exit(AppError::Io(err)));
+
+    // Logs of CI services show colours too, but not when they're turned off with NO_COLOR
+    let color = (std::io::stdout().is_terminal() || env::var_os("CI").is_some()) && env::var_os("NO_COLOR").is_none();
+    print_diffs(docs, &generated, &changes, color);
+    let _ = fs::remove_dir_all(&generated);
+
+    match changes.len() {
+        0 => println!("The docs in {} are up to date.", docs.display()),
+        changed => println!(
+            "The docs in {} are out of date, {} {} changed.",
+            docs.display(),
+            changed,
+            pluralize(changed, "file", "files")
+        ),
+    }
+    changes.is_empty()
+}
+
+/// The options of `check` that `build` takes too, which say where the library is and how it's compiled.
+fn library_arguments(library: &LibraryArgs) -> Vec<OsString> {
+    let mut arguments = Vec::new();
+    // A `--rev` check already runs on the checkout, which the build reads as well
+    if let Some(snapshot) = &library.snapshot {
+        arguments.extend(["--snapshot".into(), snapshot.into()]);
+    }
+    if let Some(lib) = &library.lib {
+        arguments.extend(["--lib".into(), lib.into()]);
+    }
+    for entry in &library.entry {
+        arguments.extend(["--entry".into(), entry.into()]);
+    }
+    for pattern in &library.exclude {
+        arguments.extend(["--exclude".into(), pattern.into()]);
+    }
+    for (flag, set) in [("--no-compile", library.no_compile), ("--sandbox", library.sandbox)] {
+        if set {
+            arguments.push(flag.into());
+        }
+    }
+    arguments
+}
+
+fn run_build(build: BuildArgs) {
+    let (root, config) = open_library(build.library.dir.clone());
+    let output_dir = match output_directory(build.output, config.output.as_deref(), &root) {
+        Ok(dir) => dir,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let working_dir = source_directory(&root, &build.library);
+    // Extracting the library changes the current directory, so the files given on the command line are resolved first
+    let link_map = build.link_map.as_deref().map(invocation_path);
+    let previous_api = build.previous_api.as_deref().map(invocation_path);
+
+    let extract_options = extract_options(&working_dir, &build.library, &config);
+    // The output of `extract` already has the entry points
+    let mut extracted = match &build.from_json {
+        Some(path) => match read_extracted_items(path) {
+            Ok(extracted) => extracted,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        },
+        None => extract_library(&working_dir, &build.library.entry, &extract_options),
+    };
+
+    for shadowed in find_shadowed_names(&extracted) {
+        eprintln!("Warning: {}", shadowed.message());
+    }
+
+    let project_file = read_project(&working_dir);
+
+    // Excluded modules still run when the library is imported, so what they do on the host is part of the permissions
+    let permissions = required_permissions(&extracted);
+
+    hide_code(&mut extracted, build.items.hide_code);
+    if build.deterministic {
+        drop_benchmarks(&mut extracted);
+    }
+    if build.items.include_private {
+        include_private(&mut extracted);
+    }
+    let excluded = [project_file.exclude_modules, config.exclude_modules].concat();
+    for module in exclude_modules(&mut extracted, &excluded) {
+        eprintln!("Warning: The excluded module {} doesn't exist", module);
+    }
+
+    let main_file = main_file(&extracted);
+    let landing_file = match &build.landing {
+        Some(landing) => match find_library_file(&extracted, landing) {
+            Some(landing_file) => landing_file,
+            None => {
+                let files = extracted.iter().map(|file| file.relative_path.as_str()).collect::<Vec<_>>();
+                eprintln!(
+                    "Landing file {} is not part of the library, which consists of: {}",
+                    landing.display(),
+                    files.join(", ")
+                );
+                std::process::exit(1);
+            }
+        },
+        None => main_file,
+    };
+    for problem in find_directive_problems(&extracted, landing_file) {
+        eprintln!("Warning: {}", problem.message());
+    }
+
+    let name = match build.name.or(project_file.name).or_else(|| infer_project_name(&root)) {
+        Some(name) => name,
+        None => {
+            eprintln!("No library name given, pass --name or set it in {}", PROJECT_FILE);
+            std::process::exit(1);
+        }
+    };
+
+    // Only a base URL given for the docs moves their links, the homepage in uiua.toml can be anywhere
+    let base_path = build.base_url.as_deref().or(config.base_url.as_deref()).map(base_path);
+    let metadata = ProjectMetadata {
+        name,
+        title: build.title.or(config.title),
+        version: project_file.version,
+        description: project_file.description,
+        repository: build.repository.or(project_file.repository),
+        documentation: build.base_url.or(config.base_url).or(project_file.documentation),
+        library_file: Some(main_file.relative_path.clone()).filter(|path| path != LIBRARY_FILE),
+        sections: project_file.sections,
+        groups: config.groups,
+    };
+    for key in metadata.sections.keys().filter(|key| !configurable_sections().contains(&key.as_str())) {
+        eprintln!("Warning: Unknown section in {}: {}", PROJECT_FILE, key);
+    }
+
+    let format = match build.no_render {
+        true => OutputFormat::Json,
+        false => build.format,
+    };
+    let generated = match format {
+        OutputFormat::Html | OutputFormat::Markdown | OutputFormat::Json => None,
+        OutputFormat::Gemini => Some((generate_gemini_capsule(&output_dir, main_file, landing_file, &metadata), "Gemini capsule")),
+        OutputFormat::Latex => Some((generate_latex_reference(&output_dir, main_file, &metadata), "LaTeX reference")),
+        OutputFormat::ApiOnly => Some((generate_interface_file(&output_dir, main_file, &metadata), "library interface")),
     };
+    if let Some((result, output)) = generated {
+        if let Err(err) = result {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        println!("Generated the {}.", output);
+        return;
+    }
+
+    let local_links = LocalLinks::new(&main_file.items);
+    for (old_name, new_name) in &config.moved {
+        if local_links.resolve(new_name).is_none() {
+            eprintln!(
+                "Warning: {} is moved to {} in {}, which isn't in the docs",
+                old_name, new_name, CONFIG_FILE
+            );
+        } else if local_links.resolve(old_name).is_some() {
+            eprintln!(
+                "Warning: {} is moved to {} in {}, but is still in the docs",
+                old_name, new_name, CONFIG_FILE
+            );
+        }
+    }
+
+    let mut link_resolvers = LinkResolvers::default();
+    link_resolvers.add(local_links);
+    if let Some(link_map) = &link_map {
+        match ExternalLinks::read(link_map) {
+            Ok(external_links) => link_resolvers.add(external_links),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut markdown_hooks = MarkdownHooks::default();
+    markdown_hooks.raw_html = build.raw_html;
+    markdown_hooks.add(link_resolvers);
 
-    let summary = summarize_content(main_file, cli.name);
-    let result = generator::generate_documentation_site(&working_dir, summary);
+    let options = GeneratorOptions {
+        lazy_panels: build.lazy_panels,
+        metrics: build.metrics,
+        embeds: build.embeds,
+        share_pages: build.share_pages,
+        module_order: build.module_order,
+        hide_code: build.items.hide_code,
+        wrap_code: build.wrap_code,
+        search_index_limit: build.search_index_limit,
+        font: build.font.or(config.theme.font).unwrap_or_default(),
+        theme: build.theme.or(config.theme.default).unwrap_or_default(),
+        hosting: build.hosting,
+        deterministic: build.deterministic,
+        base_path,
+        version_url: build.version_url.or(config.version_url),
+        moved: config.moved,
+        markdown_hooks,
+    };
+
+    if options.metrics {
+        let report = metrics_report(&main_file.items);
+        if report.is_empty() {
+            println!("No bindings with outlying metrics.");
+        } else {
+            println!("Bindings with outlying metrics:");
+            for line in report {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    let previous_api = match previous_api.as_deref().map(read_api_snapshot).transpose() {
+        Ok(previous_api) => previous_api,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let release = build.release.or_else(|| metadata.version.clone());
+    let api = build_api_snapshot(
+        &main_file.items,
+        &metadata,
+        release,
+        previous_api.as_ref(),
+        build_time(build.deterministic),
+    );
+    let registry = build_registry_package(&main_file.items, &metadata);
+
+    let examples = match extract_examples(&working_dir, &extract_options) {
+        Ok(examples) => examples,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    if build.coverage {
+        print_coverage_report(&binding_coverage(&main_file.items, &extracted, &examples));
+    }
+    let glossary = read_glossary(&working_dir);
+    let mut summary = summarize_content(
+        main_file,
+        landing_file,
+        &metadata,
+        examples,
+        glossary,
+        permissions,
+        &options.markdown_hooks,
+    );
+    summary.sections.extend(
+        build
+            .library
+            .entry
+            .iter()
+            .filter_map(|entry| find_library_file(&extracted, entry))
+            .filter_map(summarize_entry_point),
+    );
+    // These render the summary instead of the extracted items
+    let generated = match format {
+        OutputFormat::Markdown => Some((generate_markdown_docs(&output_dir, &summary), "Markdown docs")),
+        OutputFormat::Json => Some((generate_model_file(&output_dir, &summary), "documentation model")),
+        _ => None,
+    };
+    if let Some((result, output)) = generated {
+        if let Err(err) = result {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        println!("Generated the {}.", output);
+        return;
+    }
+    let has_examples = !summary.examples.is_empty();
+    let result = generator::generate_documentation_site(&output_dir, summary, &extracted, &api, &registry, &options);
     if let Err(err) = result {
         eprintln!("Error: {}", err);
         std::process::exit(1);
     }
+    // The files examples read are only worth publishing with the examples
+    let copied = match has_examples {
+        true => copy_data_files(&working_dir, &output_dir),
+        false => Ok(()),
+    };
+    if let Err(err) = copied {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+
+    println!("Generated the documentation.");
 
-    println!("Generated the documentation.")
+    // Only people running the generator themselves can act on the notice, so CI runs don't check
+    if std::io::stderr().is_terminal() {
+        if let Some(notice) = update_notice() {
+            eprintln!("{}", notice);
+        }
+    }
 }
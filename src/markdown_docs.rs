@@ -0,0 +1,307 @@
+use crate::examples::{ExampleNotebook, NotebookCell};
+use crate::extractor::{
+    qualified_name, BindingDefinition, BindingType, DataDefinition, Definition, Documented, ItemContent, ModuleDefinition, NamedSignature,
+    VariantDefinition,
+};
+use crate::generator::box_description;
+use crate::glossary::Glossary;
+use crate::summarizer::{ContentItems, DocumentationSummary, RenderingContent, RenderingItem, SectionType};
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io;
+use std::path::Path;
+
+/// Writes the docs to `output_directory` as Markdown files, for wikis and repositories that render Markdown.
+///
+/// `index.md` has the front page, and every module, example and the glossary get a file of their own. Text that the
+/// summary holds as HTML, like the `!doc` comments of the front page, is kept as HTML, which Markdown allows.
+pub fn generate_markdown_docs(output_directory: &Path, summary: &DocumentationSummary) -> io::Result<()> {
+    if output_directory.exists() {
+        remove_dir_all(output_directory)?;
+    }
+    create_dir_all(output_directory)?;
+
+    let mut page = vec![format!("# {}", summary.title)];
+    if let Some(description) = &summary.description {
+        page.extend([String::new(), description.clone()]);
+    }
+    if let Some(version) = &summary.version {
+        page.extend([String::new(), format!("Version {}", version)]);
+    }
+    page.extend([String::new(), code_block("uiua", &summary.import_snippet)]);
+
+    for section in &summary.sections {
+        match section.section_type {
+            SectionType::Documentation => page.extend(section.content.iter().filter_map(|item| match &item.content {
+                RenderingContent::RenderedDocumentation(html) => Some(format!("\n{}", html)),
+                _ => None,
+            })),
+            // Both link to the pages of the site, so they are listed with the Markdown files instead
+            SectionType::Examples | SectionType::Glossary => {}
+            SectionType::Modules => {
+                create_dir_all(output_directory.join("modules"))?;
+                page.extend([String::new(), format!("## {}", section.title)]);
+                page.extend(section.description.iter().map(|description| format!("\n{}", description)));
+                page.push(String::new());
+                for module in section_modules(&section.content) {
+                    let link = format!("[{}](modules/{}.md)", module.name, module.name);
+                    page.push(match module.summary() {
+                        Some(summary) => format!("- {}: {}", link, summary),
+                        None => format!("- {}", link),
+                    });
+                    write(
+                        output_directory.join("modules").join(format!("{}.md", module.name)),
+                        join_lines(generate_module_page(summary, module)),
+                    )?;
+                }
+            }
+            SectionType::Bindings | SectionType::EntryPoint => {
+                page.extend([String::new(), format!("## {}", section.title)]);
+                page.extend(section.description.iter().map(|description| format!("\n{}", description)));
+                for item in &section.content {
+                    // The modifier overview only repeats items listed in the other groups
+                    if let RenderingContent::Items(items) = &item.content {
+                        page.extend(generate_group(items));
+                    }
+                }
+            }
+        }
+    }
+
+    if !summary.examples.is_empty() {
+        create_dir_all(output_directory.join("examples"))?;
+        page.extend([String::new(), "## Examples".to_owned(), String::new()]);
+    }
+    for example in &summary.examples {
+        let link = format!("[{}](examples/{}.md)", example.heading(), example.name);
+        page.push(match example.summary() {
+            Some(summary) => format!("- {}: {}", link, summary),
+            None => format!("- {}", link),
+        });
+        write(
+            output_directory.join("examples").join(format!("{}.md", example.name)),
+            join_lines(generate_example_page(summary, example)),
+        )?;
+    }
+
+    if let Some(glossary) = &summary.glossary {
+        page.extend([
+            String::new(),
+            "## Glossary".to_owned(),
+            String::new(),
+            "The [glossary](glossary.md) defines the terms used throughout the docs.".to_owned(),
+        ]);
+        write(
+            output_directory.join("glossary.md"),
+            join_lines(generate_glossary_page(summary, glossary)),
+        )?;
+    }
+
+    page.extend([String::new(), generator_note()]);
+    write(output_directory.join("index.md"), join_lines(page))
+}
+
+fn join_lines(lines: Vec<String>) -> String {
+    lines.join("\n") + "\n"
+}
+
+fn generator_note() -> String {
+    format!(
+        "---\n\nGenerated by [uiua-doc-gen](https://github.com/ekgame/uiua-doc-gen) for Uiua {}",
+        uiua::VERSION
+    )
+}
+
+fn code_block(language: &str, code: &str) -> String {
+    // A fence longer than any run of backticks in the code, so the code can't end the block
+    let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{}{}\n{}\n{}", fence, language, code, fence)
+}
+
+fn section_modules(content: &[RenderingItem]) -> Vec<&ModuleDefinition> {
+    content
+        .iter()
+        .filter_map(|item| match &item.content {
+            RenderingContent::Items(items) => Some(&items.items),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            ItemContent::Module(module) => Some(module),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A group of bindings, like the monadic functions, under a heading of its own.
+fn generate_group(items: &ContentItems) -> Vec<String> {
+    let mut lines = vec![String::new(), format!("### {}", items.title.title)];
+    lines.extend(items.description.iter().map(|description| format!("\n{}", description)));
+    if let Some(snippet) = &items.import_snippet {
+        lines.extend([String::new(), code_block("uiua", snippet)]);
+    }
+    lines.extend(items.items.iter().flat_map(|item| generate_item(item, 4)));
+    lines
+}
+
+fn generate_module_page(summary: &DocumentationSummary, module: &ModuleDefinition) -> Vec<String> {
+    let mut page = vec![
+        format!("# {}", module.name),
+        String::new(),
+        format!("[Back to {}](../index.md)", summary.title),
+    ];
+    if let Some(comment) = module.comment() {
+        page.extend([String::new(), comment.to_owned()]);
+    }
+    if let Some(readme) = &module.readme {
+        page.extend([String::new(), readme.clone()]);
+    }
+    page.extend(module.items.iter().flat_map(|item| generate_item(item, 2)));
+    page.extend([String::new(), generator_note()]);
+    page
+}
+
+/// The lines of an item with a heading of the given level, starting with a blank line. Nested modules are listed
+/// with their items, a level deeper.
+fn generate_item(item: &ItemContent, level: usize) -> Vec<String> {
+    let heading = "#".repeat(level.min(6));
+    let (name, description, comment, code) = match item {
+        ItemContent::Binding(binding) if binding.is_documented() => (
+            qualified_name(&binding.module_path, &binding.name),
+            binding_description(binding),
+            binding.comment(),
+            Some(&binding.code).filter(|code| !code.is_empty()),
+        ),
+        ItemContent::Data(DataDefinition {
+            name,
+            module_path,
+            definition,
+            comment,
+            ..
+        }) => (
+            match name {
+                Some(name) => qualified_name(module_path, name),
+                None => module_path.join("~"),
+            },
+            data_description("data", definition.as_ref()),
+            comment.as_deref(),
+            None,
+        ),
+        ItemContent::Variant(VariantDefinition {
+            name,
+            module_path,
+            definition,
+            comment,
+            ..
+        }) => (
+            qualified_name(module_path, name),
+            data_description("variant", definition.as_ref()),
+            comment.as_deref(),
+            None,
+        ),
+        ItemContent::Module(module) if module.has_public_items() => {
+            let mut lines = vec![
+                String::new(),
+                format!("{} {}", heading, qualified_name(&module.module_path, &module.name)),
+            ];
+            if let Some(comment) = module.comment() {
+                lines.extend([String::new(), comment.to_owned()]);
+            }
+            lines.extend(module.items.iter().flat_map(|item| generate_item(item, level + 1)));
+            return lines;
+        }
+        _ => return Vec::new(),
+    };
+
+    // Unnamed data definitions at the top level define the library itself
+    let title = match name.is_empty() {
+        true => format!("{} Data definition", heading),
+        false => format!("{} `{}`", heading, name),
+    };
+    let mut lines = vec![String::new(), title, String::new(), description];
+    if let Some(comment) = comment {
+        lines.extend([String::new(), comment.to_owned()]);
+    }
+    if let Some(code) = code {
+        lines.extend([String::new(), code_block("uiua", code)]);
+    }
+    lines
+}
+
+fn binding_description(binding: &BindingDefinition) -> String {
+    let named = |signature: Option<&NamedSignature>| match signature {
+        Some(signature) => format!(", `{}`", signature.to_comment().trim_start_matches("# ")),
+        None => String::new(),
+    };
+
+    let description = match &binding.kind {
+        BindingType::Const(_) => "Constant".to_owned(),
+        BindingType::Function(function) => match &function.signature {
+            Some(signature) => format!("Function `{}`{}", signature, named(function.named_signature.as_ref())),
+            None => format!("Function{}", named(function.named_signature.as_ref())),
+        },
+        BindingType::IndexMacro(index_macro) => format!(
+            "Index macro with {} operands{}",
+            index_macro.arguments,
+            named(index_macro.named_signature.as_ref())
+        ),
+        BindingType::CodeMacro(code_macro) => format!("Code macro{}", named(code_macro.named_signature.as_ref())),
+    };
+    match binding.public {
+        true => format!("*{}*", description),
+        false => format!("*{}, private*", description),
+    }
+}
+
+fn data_description(kind: &str, definition: Option<&Definition>) -> String {
+    let fields = definition
+        .map(|definition| definition.fields.iter().map(|field| format!("`{}`", field.name)).collect::<Vec<_>>())
+        .unwrap_or_default();
+    match fields.is_empty() {
+        true => format!("*{}, {}*", kind, box_description(definition)),
+        false => format!("*{}, {}, fields: {}*", kind, box_description(definition), fields.join(" ")),
+    }
+}
+
+/// An example with the outputs of its code cells in blocks of their own.
+fn generate_example_page(summary: &DocumentationSummary, example: &ExampleNotebook) -> Vec<String> {
+    let mut page = vec![format!("[Back to {}](../index.md)", summary.title)];
+    // Examples usually start with a heading of their own
+    if !matches!(example.cells.first(), Some(NotebookCell::Prose(prose)) if prose.trim_start().starts_with("# ")) {
+        page.splice(0..0, [format!("# {}", example.heading()), String::new()]);
+    }
+    for cell in &example.cells {
+        match cell {
+            NotebookCell::Prose(prose) => page.extend([String::new(), prose.clone()]),
+            NotebookCell::Code {
+                code,
+                outputs,
+                error,
+                expect_error,
+            } => {
+                page.extend([String::new(), code_block("uiua", code)]);
+                if !outputs.is_empty() {
+                    page.extend([String::new(), code_block("", &outputs.join("\n"))]);
+                }
+                if let Some(error) = error {
+                    let label = match expect_error {
+                        true => "Fails with",
+                        false => "Error",
+                    };
+                    page.extend([String::new(), format!("> {}: {}", label, error.replace('\n', "\n> "))]);
+                }
+            }
+        }
+    }
+    page.extend([String::new(), generator_note()]);
+    page
+}
+
+fn generate_glossary_page(summary: &DocumentationSummary, glossary: &Glossary) -> Vec<String> {
+    let mut page = vec!["# Glossary".to_owned(), String::new(), format!("[Back to {}](index.md)", summary.title)];
+    for term in &glossary.terms {
+        page.extend([String::new(), format!("## {}", term.term), String::new(), term.definition.clone()]);
+    }
+    page.extend([String::new(), generator_note()]);
+    page
+}
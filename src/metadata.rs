@@ -0,0 +1,128 @@
+use crate::config::GroupConfig;
+use crate::extractor::LIBRARY_FILE;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+/// The file in the library's directory that describes the project.
+pub const PROJECT_FILE: &str = "uiua.toml";
+
+/// Information about the documented project that isn't part of the Uiua source itself.
+#[derive(Debug, Clone)]
+pub struct ProjectMetadata {
+    pub name: String,
+    /// The title of the docs, when it isn't the library's name.
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    /// The URL the docs are published at.
+    pub documentation: Option<String>,
+    /// The library's entry file, when it isn't `lib.ua`.
+    pub library_file: Option<String>,
+    /// Titles and descriptions of the sections of the front page, by the key of the section, like `monadic_functions`.
+    pub sections: HashMap<String, SectionConfig>,
+    /// Families of bindings listed in groups of their own, from `uiua-doc.toml`.
+    pub groups: Vec<GroupConfig>,
+}
+
+/// The contents of `uiua.toml`, where every field can be overridden from the command line.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectFile {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    pub documentation: Option<String>,
+    #[serde(default)]
+    pub sections: HashMap<String, SectionConfig>,
+    /// Modules left out of the docs, like `Internal` or `Geo~Experimental`.
+    #[serde(default)]
+    pub exclude_modules: Vec<String>,
+}
+
+/// A `[sections.<key>]` table of `uiua.toml`, replacing the generated title of a section.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SectionConfig {
+    pub title: Option<String>,
+    /// Markdown shown above the section's panels.
+    pub description: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ProjectFileError {
+    #[error("Unable to read {PROJECT_FILE}: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid {PROJECT_FILE}: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+/// Reads the project file of the library, if it has one.
+pub fn read_project_file(root: &Path) -> Result<Option<ProjectFile>, ProjectFileError> {
+    let path = root.join(PROJECT_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    Ok(Some(toml::from_str(&fs::read_to_string(path)?)?))
+}
+
+/// A name for a library that wasn't given one, taken from the repository its origin remote
+/// points to, or from its directory.
+pub fn infer_project_name(root: &Path) -> Option<String> {
+    git_remote_name(root).or_else(|| {
+        root.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .filter(|name| !name.is_empty())
+    })
+}
+
+fn git_remote_name(root: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    // Both `https://host/user/repo.git` and `git@host:user/repo.git` end with the name of the repository
+    let url = String::from_utf8(output.stdout).ok()?;
+    let url = url.trim().trim_end_matches('/').trim_end_matches(".git");
+    url.rsplit(['/', ':', '\\']).next().filter(|name| !name.is_empty()).map(str::to_owned)
+}
+
+impl ProjectMetadata {
+    /// What the docs are titled, the library's name unless they were given a title of their own.
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The path used in `~ "..."` imports to reference the library.
+    pub fn import_source(&self) -> String {
+        match &self.repository {
+            Some(repository) => {
+                let repository = repository
+                    .trim()
+                    .trim_start_matches("https://")
+                    .trim_start_matches("http://")
+                    .trim_end_matches('/')
+                    .trim_end_matches(".git");
+                format!("git: {}", repository)
+            }
+            None => self.library_file.clone().unwrap_or_else(|| LIBRARY_FILE.to_owned()),
+        }
+    }
+
+    /// An import statement for the library, optionally importing a single item from it.
+    pub fn import_snippet(&self, item: Option<&str>) -> String {
+        match item {
+            Some(item) => format!("~ \"{}\" ~ {}", self.import_source(), item),
+            None => format!("~ \"{}\"", self.import_source()),
+        }
+    }
+}
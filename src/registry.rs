@@ -0,0 +1,86 @@
+use crate::extractor::{BindingType, Documented, ItemContent};
+use crate::generator::anchor_id;
+use crate::metadata::ProjectMetadata;
+use serde::Serialize;
+
+/// A compact description of the library, published as `registry.json` for package indexes to ingest.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryPackage {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    /// Where the docs are published, if known.
+    pub documentation: Option<String>,
+    pub uiua_version: &'static str,
+    pub bindings: Vec<RegistryBinding>,
+}
+
+/// A top-level item of the library.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryBinding {
+    pub name: String,
+    pub kind: &'static str,
+    pub signature: Option<String>,
+    pub summary: Option<String>,
+    /// The item's URL, absolute when the docs URL is known and relative to the site otherwise.
+    pub url: String,
+}
+
+impl RegistryPackage {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Unable to serialize the registry entry")
+    }
+}
+
+/// Describes the library and the public items at its top level.
+pub fn build_registry_package(items: &[ItemContent], metadata: &ProjectMetadata) -> RegistryPackage {
+    let url = |name: &str| {
+        let page = format!("index.html#{}", anchor_id(&[], name));
+        match &metadata.documentation {
+            Some(documentation) => format!("{}/{}", documentation.trim_end_matches('/'), page),
+            None => page,
+        }
+    };
+
+    let bindings = items
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Binding(binding) if binding.is_documented() => Some(RegistryBinding {
+                name: binding.name.clone(),
+                kind: binding.kind.name(),
+                signature: match &binding.kind {
+                    BindingType::Function(function) => function.signature.as_ref().map(ToString::to_string),
+                    _ => None,
+                },
+                summary: binding.summary().map(str::to_owned),
+                url: url(&binding.name),
+            }),
+            ItemContent::Module(module) if module.has_public_items() => Some(RegistryBinding {
+                name: module.name.clone(),
+                kind: "module",
+                signature: None,
+                summary: module.summary().map(str::to_owned),
+                url: url(&module.name),
+            }),
+            ItemContent::Data(data) => data.name.as_ref().map(|name| RegistryBinding {
+                name: name.clone(),
+                kind: "data",
+                signature: None,
+                summary: data.summary().map(str::to_owned),
+                url: url(name),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    RegistryPackage {
+        name: metadata.name.clone(),
+        version: metadata.version.clone(),
+        description: metadata.description.clone(),
+        repository: metadata.repository.clone(),
+        documentation: metadata.documentation.clone(),
+        uiua_version: uiua::VERSION,
+        bindings,
+    }
+}
@@ -0,0 +1,62 @@
+use std::any::Any;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use uiua::SysBackend;
+
+/// A system backend for compiling and running libraries that aren't trusted.
+///
+/// Code can only read the files inside the library's directory, which is enough for its imports.
+/// Writing files, the network, git imports and everything else a backend could do are refused,
+/// and whatever the code prints is discarded.
+#[derive(Debug, Clone)]
+pub struct SandboxSys {
+    root: PathBuf,
+}
+
+impl SandboxSys {
+    pub fn new(root: &Path) -> io::Result<Self> {
+        Ok(Self { root: root.canonicalize()? })
+    }
+
+    /// The path a file would be read from, as long as it's inside the library's directory.
+    fn resolve(&self, path: &Path) -> Result<PathBuf, String> {
+        let outside = || format!("{} is outside of the library's directory", path.display());
+        let full_path = path.canonicalize().map_err(|_| outside())?;
+        if full_path.starts_with(&self.root) {
+            Ok(full_path)
+        } else {
+            Err(outside())
+        }
+    }
+}
+
+impl SysBackend for SandboxSys {
+    fn any(&self) -> &dyn Any {
+        self
+    }
+
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn print_str_stdout(&self, _: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn print_str_stderr(&self, _: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        self.resolve(Path::new(path)).is_ok()
+    }
+
+    fn is_file(&self, path: &str) -> Result<bool, String> {
+        self.resolve(Path::new(path)).map(|path| path.is_file())
+    }
+
+    fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
+        fs::read(self.resolve(path)?).map_err(|err| err.to_string())
+    }
+}
@@ -0,0 +1,255 @@
+use kuchiki::traits::TendrilSink;
+use kuchiki::NodeRef;
+
+/// How HTML written in doc comments and Markdown files ends up on the site.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RawHtmlPolicy {
+    /// Show the HTML as text, which is safe for docs of any library.
+    #[default]
+    Escape,
+    /// Keep the HTML elements and attributes that text needs, like links, images and tables, and remove everything
+    /// else, including scripts, styles, event handlers and `javascript:` links.
+    Sanitize,
+    /// Keep the HTML as written, for libraries whose authors are trusted.
+    Allow,
+}
+
+/// The elements that are kept. Others are replaced by their contents, unless they're in `DROPPED_ELEMENTS`.
+const ALLOWED_ELEMENTS: [&str; 56] = [
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "samp",
+    "section",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+    "var",
+    "wbr",
+];
+
+/// Elements whose contents aren't text to read, so they're removed along with everything in them.
+const DROPPED_ELEMENTS: [&str; 21] = [
+    "script", "style", "iframe", "frame", "frameset", "object", "embed", "applet", "noscript", "template", "svg", "math", "textarea", "select",
+    "option", "title", "head", "meta", "link", "base", "noembed",
+];
+
+/// Attributes kept on every allowed element.
+const GLOBAL_ATTRIBUTES: [&str; 3] = ["title", "lang", "dir"];
+
+/// Attributes kept on specific elements, as `(element, attribute)`.
+const ELEMENT_ATTRIBUTES: [(&str, &str); 18] = [
+    ("a", "href"),
+    ("img", "src"),
+    ("img", "alt"),
+    ("img", "width"),
+    ("img", "height"),
+    ("ol", "start"),
+    ("ol", "reversed"),
+    ("li", "value"),
+    ("td", "align"),
+    ("td", "colspan"),
+    ("td", "rowspan"),
+    ("th", "align"),
+    ("th", "colspan"),
+    ("th", "rowspan"),
+    ("details", "open"),
+    ("input", "type"),
+    ("input", "checked"),
+    ("input", "disabled"),
+];
+
+/// Attributes that hold URLs, which have to use one of `SAFE_SCHEMES` or be relative.
+const URL_ATTRIBUTES: [&str; 2] = ["href", "src"];
+
+const SAFE_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Keeps only the elements and attributes of a fragment of HTML that are known to be safe, so it can't run code,
+/// load other pages or cover the rest of the page.
+pub fn sanitize_html(html: &str) -> String {
+    let document = kuchiki::parse_html().one(html);
+    let body = document.select_first("body").unwrap();
+    clean_children(body.as_node());
+    body.as_node().children().map(|child| child.to_string()).collect()
+}
+
+fn clean_children(node: &NodeRef) {
+    for child in node.children().collect::<Vec<_>>() {
+        let Some(element) = child.as_element() else {
+            // Comments and the like are left out, text is kept
+            if child.as_text().is_none() {
+                child.detach();
+            }
+            continue;
+        };
+
+        let name = element.name.local.to_lowercase();
+        if DROPPED_ELEMENTS.contains(&name.as_str()) {
+            child.detach();
+            continue;
+        }
+
+        clean_children(&child);
+        if !ALLOWED_ELEMENTS.contains(&name.as_str()) {
+            for grandchild in child.children().collect::<Vec<_>>() {
+                child.insert_before(grandchild);
+            }
+            child.detach();
+            continue;
+        }
+
+        let mut attributes = element.attributes.borrow_mut();
+        attributes.map.retain(|attribute_name, attribute| {
+            let attribute_name = attribute_name.local.to_lowercase();
+            is_allowed_attribute(&name, &attribute_name, &attribute.value)
+        });
+        // Task lists are the only inputs Markdown writes, and they can't be changed
+        if name == "input" {
+            if attributes.get("type") != Some("checkbox") {
+                drop(attributes);
+                child.detach();
+                continue;
+            }
+            attributes.insert("disabled", String::new());
+        }
+    }
+}
+
+fn is_allowed_attribute(element: &str, attribute: &str, value: &str) -> bool {
+    let allowed = GLOBAL_ATTRIBUTES.contains(&attribute)
+        || ELEMENT_ATTRIBUTES.contains(&(element, attribute))
+        // Markdown names the language of code blocks in their class, and prefixes the IDs of footnotes
+        || (element == "code" && attribute == "class" && value.split_whitespace().all(|class| class.starts_with("language-")))
+        || (attribute == "id" && value.starts_with("user-content-"));
+    allowed && (!URL_ATTRIBUTES.contains(&attribute) || is_safe_url(value))
+}
+
+fn is_safe_url(url: &str) -> bool {
+    // Browsers ignore whitespace and control characters inside the scheme
+    let url = url.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect::<String>();
+    match url.find([':', '/', '?', '#']) {
+        Some(end) if url[end..].starts_with(':') => SAFE_SCHEMES.contains(&url[..end].to_ascii_lowercase().as_str()),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_svg_animations_that_set_links() {
+        let html = sanitize_html(r#"<svg><a><animate attributeName="href" values="javascript:alert(1)"/><text>x</text></a></svg>"#);
+        assert!(!html.contains("javascript"), "{}", html);
+        assert!(!html.contains("animate"), "{}", html);
+    }
+
+    #[test]
+    fn removes_styles() {
+        let html = sanitize_html(r#"<p style="position: fixed; inset: 0">Sign in again</p><style>body { display: none }</style>"#);
+        assert_eq!(html, "<p>Sign in again</p>");
+    }
+
+    #[test]
+    fn removes_event_handlers_and_scripts() {
+        let html = sanitize_html(r#"<img src="cat.png" alt="Cat" onerror="alert(1)"><script>alert(2)</script>"#);
+        assert_eq!(html, r#"<img alt="Cat" src="cat.png">"#);
+    }
+
+    #[test]
+    fn removes_links_with_unsafe_schemes() {
+        for url in [
+            "javascript:alert(1)",
+            "jav&#x09;ascript:alert(1)",
+            " JaVaScRiPt:alert(1)",
+            "data:text/html,x",
+            "vbscript:x",
+        ] {
+            let html = sanitize_html(&format!(r#"<a href="{}">x</a>"#, url));
+            assert_eq!(html, "<a>x</a>", "{}", url);
+        }
+    }
+
+    #[test]
+    fn keeps_safe_links() {
+        for url in [
+            "https://example.com/a:b",
+            "mailto:someone@example.com",
+            "#anchor",
+            "page.html?q=a:b",
+            "/docs/",
+        ] {
+            let html = sanitize_html(&format!(r#"<a href="{}">x</a>"#, url));
+            assert!(html.contains("href"), "{}", url);
+        }
+    }
+
+    #[test]
+    fn keeps_the_text_of_unknown_elements() {
+        let html = sanitize_html(r#"<form action="https://evil.example"><button formaction="javascript:x">Click</button></form>"#);
+        assert_eq!(html, "Click");
+    }
+
+    #[test]
+    fn removes_math_and_its_links() {
+        let html = sanitize_html(r#"<math><mi xlink:href="javascript:alert(1)">x</mi></math>"#);
+        assert_eq!(html, "");
+    }
+
+    #[test]
+    fn keeps_what_markdown_writes() {
+        let markdown = "| a |\n|:-:|\n| `b` |\n\n- [x] done\n\n```uiua\n+1\n```\n\nNote[^1]\n\n[^1]: A footnote";
+        let rendered = markdown::to_html_with_options(markdown, &markdown::Options::gfm()).unwrap();
+        let html = sanitize_html(&rendered);
+        assert!(html.contains(r#"<th align="center">"#), "{}", html);
+        assert!(html.contains(r#"<input checked="" disabled="" type="checkbox">"#), "{}", html);
+        assert!(html.contains(r#"<code class="language-uiua">"#), "{}", html);
+        assert!(html.contains(r##"href="#user-content-fn-1""##), "{}", html);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::examples::ExampleNotebook;
+use crate::extractor::{qualified_name, BindingType, Documented, GeneratedFunction, ItemContent, SignatureInfo};
+use crate::generator::anchor_id;
+use crate::summarizer::{DocumentationSummary, RenderingContent};
+use serde::Serialize;
+
+/// A documented item, as seen by the search on the generated site.
+///
+/// Module paths and arities are kept as separate fields, so queries like `Geo.Area` or `|2`
+/// can be matched against the right part of an item instead of a flat string.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchEntry {
+    pub name: String,
+    pub path: Vec<String>,
+    /// The name qualified by the modules it's in, like `Outer~Inner~Name`.
+    pub qualified_name: String,
+    pub kind: &'static str,
+    pub inputs: Option<usize>,
+    pub outputs: Option<usize>,
+    pub anchor: String,
+    pub summary: Option<String>,
+    /// Glyphs and names of the primitives a binding uses, so `≡` or `rows` finds the bindings using it.
+    pub glyphs: String,
+    pub primitives: Vec<&'static str>,
+    /// Labels from `!doc badge` directives, so `badge:WIP` finds the bindings marked with them.
+    pub badges: Vec<String>,
+    /// The page of an entry that isn't on the front page, relative to the root of the site.
+    pub url: Option<String>,
+}
+
+pub fn build_search_index(summary: &DocumentationSummary) -> Vec<SearchEntry> {
+    let mut entries = Vec::new();
+
+    // The modifier overview only repeats items listed in the other sections
+    for item in summary.sections.iter().flat_map(|section| &section.content) {
+        if let RenderingContent::Items(items) = &item.content {
+            for item in &items.items {
+                collect_entries(&[], item, &mut entries);
+            }
+        }
+    }
+
+    entries
+}
+
+/// A place in the generated docs that can be linked to, listed in `anchors.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Anchor {
+    /// The section title, or the item's name qualified by its modules, like `Module~Name`.
+    pub title: String,
+    pub kind: &'static str,
+    /// The URL relative to the root of the site.
+    pub url: String,
+}
+
+/// Lists every section and item of the docs with its URL, so other tools can deep-link into them.
+pub fn build_anchor_map(summary: &DocumentationSummary) -> Vec<Anchor> {
+    let mut anchors = Vec::new();
+
+    for item in summary.sections.iter().flat_map(|section| &section.content) {
+        anchors.extend(item.links.iter().map(|link| Anchor {
+            title: link.title.clone(),
+            kind: "section",
+            url: page_url(&link.url),
+        }));
+        if let RenderingContent::Items(items) | RenderingContent::ModifierTable(items) = &item.content {
+            anchors.push(Anchor {
+                title: items.title.title.clone(),
+                kind: "section",
+                url: page_url(&format!("#{}", items.title.link_id)),
+            });
+        }
+    }
+
+    anchors.extend(build_search_index(summary).into_iter().map(|entry| Anchor {
+        title: entry.qualified_name,
+        kind: entry.kind,
+        url: page_url(&format!("#{}", entry.anchor)),
+    }));
+
+    anchors
+}
+
+fn page_url(url: &str) -> String {
+    match url.strip_prefix('#') {
+        Some(_) => format!("index.html{}", url),
+        None => url.to_owned(),
+    }
+}
+
+/// Entries for the example notebooks, found by their titles, so readers can search for what an example shows.
+pub fn example_search_entries(examples: &[ExampleNotebook]) -> Vec<SearchEntry> {
+    examples
+        .iter()
+        .map(|example| SearchEntry {
+            name: example.heading().to_owned(),
+            path: Vec::new(),
+            qualified_name: example.heading().to_owned(),
+            kind: "example",
+            inputs: None,
+            outputs: None,
+            anchor: String::new(),
+            summary: example.summary().map(str::to_owned),
+            glyphs: String::new(),
+            primitives: Vec::new(),
+            badges: Vec::new(),
+            url: Some(example.page_path()),
+        })
+        .collect()
+}
+
+pub fn anchor_map_json(anchors: &[Anchor]) -> String {
+    serde_json::to_string_pretty(anchors).expect("Unable to serialize the anchor map")
+}
+
+/// Serializes the index so it can be embedded in a `<script>` element of the page.
+pub fn search_index_json(entries: &[SearchEntry]) -> String {
+    serde_json::to_string(entries)
+        .expect("Unable to serialize the search index")
+        .replace("</", "<\\/")
+}
+
+fn collect_entries(path: &[String], item: &ItemContent, entries: &mut Vec<SearchEntry>) {
+    let entry = |name: &str, kind, signature: Option<&SignatureInfo>, anchor: String, summary: Option<&str>| SearchEntry {
+        name: name.to_owned(),
+        path: path.to_vec(),
+        qualified_name: qualified_name(path, name),
+        kind,
+        inputs: signature.map(|signature| signature.inputs),
+        outputs: signature.map(|signature| signature.outputs),
+        anchor,
+        summary: summary.map(str::to_owned),
+        glyphs: String::new(),
+        primitives: Vec::new(),
+        badges: Vec::new(),
+        url: None,
+    };
+
+    match item {
+        ItemContent::Binding(binding) => {
+            let signature = match &binding.kind {
+                BindingType::Function(function) => function.signature.as_ref(),
+                _ => None,
+            };
+            let anchor = anchor_id(path, &binding.name);
+            entries.push(SearchEntry {
+                glyphs: binding.primitives.iter().filter_map(|primitive| primitive.glyph()).collect(),
+                primitives: binding.primitives.iter().map(|primitive| primitive.name()).collect(),
+                badges: binding
+                    .directives
+                    .badges()
+                    .into_iter()
+                    .map(|badge| badge.label)
+                    .chain((!binding.public).then(|| "private".to_owned()))
+                    .collect(),
+                ..entry(&binding.name, binding.kind.name(), signature, anchor, binding.summary())
+            });
+        }
+        ItemContent::Module(module) => {
+            let anchor = anchor_id(path, &module.name);
+            entries.push(entry(&module.name, "module", None, anchor, module.summary()));
+
+            let path = child_path(path, &module.name);
+            for item in &module.items {
+                collect_entries(&path, item, entries);
+            }
+        }
+        ItemContent::Data(data) => {
+            // Unnamed data definitions belong to their module, so their functions are qualified by it
+            let (path, anchor) = match &data.name {
+                Some(name) => {
+                    let anchor = anchor_id(path, name);
+                    entries.push(entry(name, "data", None, anchor.clone(), data.summary()));
+                    (child_path(path, name), anchor)
+                }
+                None => {
+                    let anchor = match path.split_last() {
+                        Some((parent, modules)) => anchor_id(modules, parent),
+                        None => String::new(),
+                    };
+                    (path.to_vec(), anchor)
+                }
+            };
+            collect_generated_functions(&path, &anchor, &data.generated_functions, entries);
+        }
+        ItemContent::Variant(variant) => {
+            let anchor = anchor_id(path, &variant.name);
+            entries.push(entry(&variant.name, "variant", None, anchor.clone(), variant.summary()));
+
+            let path = child_path(path, &variant.name);
+            collect_generated_functions(&path, &anchor, &variant.generated_functions, entries);
+        }
+        _ => {}
+    }
+}
+
+fn child_path(path: &[String], name: &str) -> Vec<String> {
+    path.iter().cloned().chain(std::iter::once(name.to_owned())).collect()
+}
+
+fn collect_generated_functions(path: &[String], anchor: &str, functions: &[GeneratedFunction], entries: &mut Vec<SearchEntry>) {
+    entries.extend(functions.iter().map(|function| SearchEntry {
+        name: function.name.clone(),
+        path: path.to_vec(),
+        qualified_name: qualified_name(path, &function.name),
+        kind: "function",
+        inputs: function.function.signature.as_ref().map(|signature| signature.inputs),
+        outputs: function.function.signature.as_ref().map(|signature| signature.outputs),
+        anchor: anchor.to_owned(),
+        summary: function.summary().map(str::to_owned),
+        glyphs: String::new(),
+        primitives: Vec::new(),
+        badges: Vec::new(),
+        url: None,
+    }));
+}
@@ -0,0 +1,484 @@
+use crate::formatter::{format_source_code, format_source_code_in};
+use ignore::WalkBuilder;
+use kuchiki::traits::TendrilSink;
+use kuchiki::NodeRef;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use uiua::{Compiler, SysBackend};
+
+/// The path pages poll for the number of the latest build and the panels it changed, which isn't a file of the site.
+const LIVE_RELOAD_PATH: &str = "/__live-reload";
+
+/// The path pages report the anchor they're opened at to, since browsers don't send it to the server.
+const ANCHOR_PATH: &str = "/__anchor";
+
+/// The path that returns the markup of the code in its `code` parameter, highlighted like the site's code.
+const HIGHLIGHT_PATH: &str = "/highlight";
+
+/// Added to the end of the body of every page the server sends, so it updates once the site is regenerated and
+/// reports the anchors it's opened at. Pages where only item panels changed get the new panels in place, which keeps
+/// where the reader is, and other pages reload.
+const LIVE_RELOAD_SCRIPT: &str = r"<script>
+(function() {
+    function reportAnchor() {
+        let anchor;
+        try {
+            anchor = decodeURIComponent(location.hash.slice(1));
+        } catch (error) {
+            return;
+        }
+        if (anchor === '') {
+            return;
+        }
+        fetch('/__anchor?page=' + encodeURIComponent(location.pathname) + '&anchor=' + encodeURIComponent(anchor)
+            + '&from=' + encodeURIComponent(document.referrer)).catch(function() {});
+    }
+    reportAnchor();
+    window.addEventListener('hashchange', reportAnchor);
+
+    function applyPatch(operation) {
+        const panel = document.getElementById(operation.path.slice(1));
+        if (operation.op !== 'replace' || !panel) {
+            return false;
+        }
+        const template = document.createElement('template');
+        template.innerHTML = operation.value;
+        panel.replaceWith(template.content);
+        return true;
+    }
+
+    let build = null;
+    setInterval(function() {
+        const since = build === null ? '' : '?since=' + build + '&page=' + encodeURIComponent(location.pathname);
+        fetch('/__live-reload' + since).then(function(response) {
+            return response.json();
+        }).then(function(update) {
+            if (build !== null && build !== update.build && !(update.patch && update.patch.every(applyPatch))) {
+                location.reload();
+            }
+            build = update.build;
+        }).catch(function() {});
+    }, 1000);
+})();
+</script>";
+
+/// Counts the builds of the site, so the pages know when to update, and keeps the pages of the last two builds, so
+/// a page of the one before can be sent the panels that changed.
+#[derive(Debug, Clone, Default)]
+pub struct Builds {
+    latest: Arc<AtomicUsize>,
+    /// The pages of the previous and the latest build, by their file names.
+    pages: Arc<Mutex<[BTreeMap<String, PageSnapshot>; 2]>>,
+}
+
+impl Builds {
+    /// Counts a build of the site in the output directory.
+    pub fn finished(&self, output_directory: &Path) {
+        let snapshots = snapshot_pages(output_directory);
+        let mut pages = self.pages.lock().unwrap();
+        pages.rotate_left(1);
+        pages[1] = snapshots;
+        self.latest.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn latest(&self) -> usize {
+        self.latest.load(Ordering::SeqCst)
+    }
+
+    /// What a page of a build needs to show the latest build: nothing if it's the latest, the panels that changed
+    /// as JSON Patch operations if it's the build before and nothing else changed, and only the latest build's number
+    /// otherwise, which makes the page reload.
+    fn update(&self, since: Option<usize>, page: &str) -> serde_json::Value {
+        let pages = self.pages.lock().unwrap();
+        let latest = self.latest();
+        if since.is_none_or(|since| since + 1 != latest) {
+            return json!({ "build": latest });
+        }
+        let (Some(previous), Some(current)) = (pages[0].get(page), pages[1].get(page)) else {
+            return json!({ "build": latest });
+        };
+        if previous.outline != current.outline {
+            return json!({ "build": latest });
+        }
+        let patch = current
+            .panels
+            .iter()
+            .filter(|(anchor, panel)| previous.panels.get(*anchor) != Some(panel))
+            .map(|(anchor, panel)| json!({ "op": "replace", "path": format!("#{}", anchor), "value": panel }))
+            .collect::<Vec<_>>();
+        json!({ "build": latest, "patch": patch })
+    }
+}
+
+/// A page split into the markup of its item panels and the rest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PageSnapshot {
+    /// The page without its panels and its head, whose links to the stylesheet and script change with every build.
+    outline: String,
+    /// The markup of the panels, by their anchors. The search index is kept with them, since it changes with them.
+    panels: BTreeMap<String, String>,
+}
+
+/// The pages at the root of the site, which are the ones with item panels.
+fn snapshot_pages(output_directory: &Path) -> BTreeMap<String, PageSnapshot> {
+    let Ok(entries) = fs::read_dir(output_directory) else {
+        return BTreeMap::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "html"))
+        .filter_map(|entry| {
+            let page = fs::read_to_string(entry.path()).ok()?;
+            Some((entry.file_name().to_string_lossy().into_owned(), snapshot_page(&page)))
+        })
+        .collect()
+}
+
+fn snapshot_page(page: &str) -> PageSnapshot {
+    let document = kuchiki::parse_html().one(page);
+    if let Ok(head) = document.select_first("head") {
+        head.as_node().detach();
+    }
+
+    let mut panels = BTreeMap::new();
+    for panel in document
+        .select("article.panel.feature[id], script#search-index")
+        .unwrap()
+        .collect::<Vec<_>>()
+    {
+        // Module panels hold other panels, which are replaced on their own
+        if panel.as_node().select(".feature").unwrap().count() > 1 {
+            continue;
+        }
+        let anchor = panel.attributes.borrow().get("id").unwrap().to_owned();
+        panels.insert(anchor.clone(), panel.as_node().to_string());
+        panel.as_node().insert_before(NodeRef::new_comment(anchor));
+        panel.as_node().detach();
+    }
+    PageSnapshot {
+        outline: document.to_string(),
+        panels,
+    }
+}
+
+/// The files and anchors that were requested but aren't on the site, with the pages that linked to them.
+#[derive(Debug, Clone, Default)]
+pub struct MissingLinks(Arc<Mutex<BTreeMap<String, BTreeSet<String>>>>);
+
+impl MissingLinks {
+    /// Remembers a missing file or anchor, returning whether it wasn't requested before.
+    fn record(&self, link: String, referrer: Option<String>) -> bool {
+        let mut links = self.0.lock().unwrap();
+        let first = !links.contains_key(&link);
+        links.entry(link).or_default().extend(referrer);
+        first
+    }
+
+    /// Lists the missing files and anchors, or returns `None` when nothing was missing.
+    pub fn report(&self) -> Option<String> {
+        let links = self.0.lock().unwrap();
+        if links.is_empty() {
+            return None;
+        }
+        let lines = links
+            .iter()
+            .map(|(link, referrers)| format!("  {}{}", link, linked_from(referrers.iter())));
+        Some(format!(
+            "These files and anchors were requested, but aren't on the site:\n{}",
+            lines.collect::<Vec<_>>().join("\n")
+        ))
+    }
+}
+
+fn linked_from<'a>(referrers: impl Iterator<Item = &'a String>) -> String {
+    let referrers = referrers.map(String::as_str).collect::<Vec<_>>();
+    match referrers.is_empty() {
+        true => String::new(),
+        false => format!(", linked from {}", referrers.join(", ")),
+    }
+}
+
+/// Highlights code for other tools in the context of the library, so the code can use its bindings.
+pub struct Highlighter {
+    /// The library's entry file, or `None` for code that's highlighted without compiling the library.
+    library_file: Option<PathBuf>,
+    backend: Arc<dyn SysBackend>,
+    wrap: Option<usize>,
+    /// The library compiled for a build, which is compiled again once the site is regenerated.
+    compiled: Mutex<Option<(usize, Compiler)>>,
+}
+
+impl Highlighter {
+    pub fn new(library_file: Option<PathBuf>, backend: Arc<dyn SysBackend>, wrap: Option<usize>) -> Self {
+        Highlighter {
+            library_file,
+            backend,
+            wrap,
+            compiled: Mutex::new(None),
+        }
+    }
+
+    fn highlight(&self, code: &str, build: usize) -> String {
+        let Some(library_file) = &self.library_file else {
+            return format_source_code(code, self.wrap);
+        };
+        let mut compiled = self.compiled.lock().unwrap();
+        if !matches!(*compiled, Some((compiled_build, _)) if compiled_build == build) {
+            let mut compiler = Compiler::with_backend(self.backend.clone());
+            // A library that doesn't compile still has the bindings before the error
+            let _ = compiler.load_file(library_file);
+            *compiled = Some((build, compiler));
+        }
+        let (_, compiler) = compiled.as_ref().unwrap();
+        format_source_code_in(code, self.wrap, compiler)
+    }
+}
+
+/// Serves the files of `root` on localhost until the process ends, one thread per connection.
+/// Missing files and anchors are logged the first time they're requested, and remembered in `missing`.
+pub fn serve(root: &Path, port: u16, builds: Builds, highlighter: Highlighter, missing: MissingLinks) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let highlighter = Arc::new(highlighter);
+    for stream in listener.incoming().flatten() {
+        let root = root.to_path_buf();
+        let builds = builds.clone();
+        let highlighter = highlighter.clone();
+        let missing = missing.clone();
+        thread::spawn(move || {
+            // Browsers drop connections all the time, which isn't worth reporting
+            let _ = handle_connection(stream, &root, &builds, &highlighter, &missing);
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, root: &Path, builds: &Builds, highlighter: &Highlighter, missing: &MissingLinks) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Only the page that linked to a missing file is of interest in the headers
+    let (mut host, mut referer) = (None, None);
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':') {
+            match name.to_ascii_lowercase().as_str() {
+                "host" => host = Some(value.trim().to_owned()),
+                "referer" => referer = Some(value.trim().to_owned()),
+                _ => {}
+            }
+        }
+        header.clear();
+    }
+    let referrer = |url: &str| referrer_name(url, host.as_deref());
+
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return respond(&stream, "400 Bad Request", "text/plain", b"Bad request");
+    };
+    if method != "GET" && method != "HEAD" {
+        return respond(&stream, "405 Method Not Allowed", "text/plain", b"Method not allowed");
+    }
+
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    if path == LIVE_RELOAD_PATH {
+        let since = query_parameter(target, "since").and_then(|since| since.parse().ok());
+        let page = page_file(&query_parameter(target, "page").unwrap_or_default());
+        let update = builds.update(since, &page);
+        return respond(&stream, "200 OK", "application/json", update.to_string().as_bytes());
+    }
+    if path == HIGHLIGHT_PATH {
+        let Some(code) = query_parameter(target, "code") else {
+            return respond(&stream, "400 Bad Request", "text/plain", b"Missing the code parameter");
+        };
+        let html = highlighter.highlight(&code, builds.latest());
+        return respond(&stream, "200 OK", "text/html; charset=utf-8", html.as_bytes());
+    }
+    if path == ANCHOR_PATH {
+        let page = query_parameter(target, "page").unwrap_or_default();
+        let anchor = query_parameter(target, "anchor").unwrap_or_default();
+        let page = page_file(&page);
+        if !has_anchor(root, &page, &anchor) {
+            let from = query_parameter(target, "from")
+                .filter(|from| !from.is_empty())
+                .map(|from| referrer(&from));
+            let link = format!("/{}#{}", page, anchor);
+            if missing.record(link.clone(), from.clone()) {
+                eprintln!("Warning: {} isn't an anchor on the site{}", link, linked_from(from.iter()));
+            }
+        }
+        return respond(&stream, "204 No Content", "text/plain", b"");
+    }
+
+    let file = resolve_path(root, &percent_decode(path));
+    let Some((file, content)) = file.and_then(|file| fs::read(&file).ok().map(|content| (file, content))) else {
+        // Browsers ask for an icon of their own accord, and the site doesn't have one
+        if path != "/favicon.ico" {
+            let from = referer.map(|url| referrer(&url));
+            if missing.record(path.to_owned(), from.clone()) {
+                eprintln!("Warning: {} isn't a file of the site{}", path, linked_from(from.iter()));
+            }
+        }
+        return respond(&stream, "404 Not Found", "text/plain", b"Not found");
+    };
+
+    let content_type = content_type(&file);
+    match content_type.starts_with("text/html") {
+        true => respond(&stream, "200 OK", content_type, &with_live_reload(content)),
+        false => respond(&stream, "200 OK", content_type, &content),
+    }
+}
+
+fn with_live_reload(page: Vec<u8>) -> Vec<u8> {
+    let page = String::from_utf8_lossy(&page);
+    let end = page.rfind("</body>").unwrap_or(page.len());
+    format!("{}{}{}", &page[..end], LIVE_RELOAD_SCRIPT, &page[end..]).into_bytes()
+}
+
+fn respond(mut stream: &TcpStream, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+/// The file a URL path points to, with `index.html` for directories. Paths that would leave the root aren't served.
+fn resolve_path(root: &Path, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path.trim_start_matches('/'));
+    if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+        return None;
+    }
+
+    let file = root.join(relative);
+    match file.is_dir() {
+        true => Some(file.join("index.html")),
+        false => Some(file),
+    }
+}
+
+/// The file of the site a page's path is, relative to the root of the site.
+fn page_file(path: &str) -> String {
+    match path.trim_start_matches('/') {
+        "" => "index.html".to_owned(),
+        page if page.ends_with('/') => format!("{}index.html", page),
+        page => page.to_owned(),
+    }
+}
+
+/// Whether the page has the anchor, either listed in `anchors.json` or as the ID of one of its elements.
+fn has_anchor(root: &Path, page: &str, anchor: &str) -> bool {
+    let url = format!("{}#{}", page, anchor);
+    let listed = fs::read_to_string(root.join("anchors.json"))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Vec<serde_json::Value>>(&json).ok())
+        .is_some_and(|anchors| anchors.iter().any(|listed| listed["url"] == url.as_str()));
+    // anchors.json only lists sections and items, not the headings of examples or the terms of the glossary
+    let id = format!("id=\"{}\"", anchor.replace('&', "&amp;").replace('"', "&quot;"));
+    listed
+        || resolve_path(root, page)
+            .and_then(|file| fs::read_to_string(file).ok())
+            .is_some_and(|html| html.contains(&id))
+}
+
+/// A page of the site that linked to another as its path, and other pages as their URL.
+fn referrer_name(url: &str, host: Option<&str>) -> String {
+    let local = host.and_then(|host| url.strip_prefix("http://")?.strip_prefix(host));
+    match local {
+        Some(path) if path.starts_with('/') => path.to_owned(),
+        _ => url.to_owned(),
+    }
+}
+
+/// The decoded value of a parameter in the query of a request target, where `+` is a space.
+fn query_parameter(target: &str, name: &str) -> Option<String> {
+    let (_, query) = target.split_once('?')?;
+    let query = query.split('#').next().unwrap_or_default();
+    query
+        .split('&')
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(parameter, _)| *parameter == name)
+        .map(|(_, value)| percent_decode(&value.replace('+', " ")))
+}
+
+/// Decodes `%XX` escapes, which browsers use for the non-ASCII names of pages like `embed/F‼.html`.
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ttf") => "font/ttf",
+        Some("woff2") => "font/woff2",
+        Some("tex") | Some("txt") | Some("gmi") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Calls `on_change` whenever a file of the library changes, checking twice a second. The output directory is left
+/// out, along with hidden files and the files ignored by a `.gitignore`.
+pub fn watch_library(root: &Path, output_directory: &Path, mut on_change: impl FnMut()) {
+    let mut previous = library_files(root, output_directory);
+    loop {
+        thread::sleep(Duration::from_millis(500));
+        let current = library_files(root, output_directory);
+        if current != previous {
+            previous = current;
+            on_change();
+        }
+    }
+}
+
+fn library_files(root: &Path, output_directory: &Path) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+    let output_directory = output_directory.to_path_buf();
+    let mut files = WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(move |entry| entry.path() != output_directory)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|file_type| file_type.is_file()))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((entry.into_path(), metadata.modified().ok(), metadata.len()))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
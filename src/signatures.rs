@@ -0,0 +1,46 @@
+use crate::extractor::{qualified_name, BindingType, ItemContent};
+use serde::Serialize;
+
+/// The signature comment of a documented binding, listed in `signatures.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureComment {
+    /// The name, qualified by the modules it's in, like `Module~Name`.
+    pub name: String,
+    /// The named signature in doc comment syntax, like `# Area ? Width Height`.
+    pub comment: String,
+}
+
+/// Lists the named signatures of the documented bindings, so they can be copied into the
+/// doc comments of functions wrapping them.
+pub fn build_signature_comments(items: &[ItemContent]) -> Vec<SignatureComment> {
+    let mut comments = Vec::new();
+    collect_signature_comments(items, &mut comments);
+    comments
+}
+
+pub fn signature_comments_json(comments: &[SignatureComment]) -> String {
+    serde_json::to_string_pretty(comments).expect("Unable to serialize the signature comments")
+}
+
+fn collect_signature_comments(items: &[ItemContent], comments: &mut Vec<SignatureComment>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.is_documented() => {
+                let named_signature = match &binding.kind {
+                    BindingType::Function(function) => function.named_signature.as_ref(),
+                    BindingType::IndexMacro(index_macro) => index_macro.named_signature.as_ref(),
+                    BindingType::CodeMacro(code_macro) => code_macro.named_signature.as_ref(),
+                    BindingType::Const(_) => None,
+                };
+                if let Some(named_signature) = named_signature {
+                    comments.push(SignatureComment {
+                        name: qualified_name(&binding.module_path, &binding.name),
+                        comment: named_signature.to_comment(),
+                    });
+                }
+            }
+            ItemContent::Module(module) => collect_signature_comments(&module.items, comments),
+            _ => {}
+        }
+    }
+}
@@ -1,71 +1,153 @@
-use crate::extractor::{BindingDefinition, BindingType, FileContent, ItemContent, ModuleDefinition};
+use crate::analysis::Permission;
+use crate::config::GroupConfig;
+use crate::examples::ExampleNotebook;
+use crate::extractor::{BindingType, Documented, FileContent, ItemContent, ModuleDefinition};
+use crate::glossary::{Glossary, GLOSSARY_PAGE};
+use crate::hooks::MarkdownHooks;
+use crate::metadata::{ProjectMetadata, SectionConfig};
 use kuchiki::traits::TendrilSink;
 use kuchiki::NodeRef;
 use markup5ever::namespace_url;
 use markup5ever::{local_name, ns, QualName};
+use serde::Serialize;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io;
 use std::option::Option;
+use std::path::Path;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Title {
     pub title: String,
     pub link_id: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ContentItems {
     pub title: Title,
+    /// The rendered intro text set in `uiua.toml`.
+    pub description: Option<String>,
     pub items: Vec<ItemContent>,
+    pub import_snippet: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum RenderingContent {
     RenderedDocumentation(String),
     Items(ContentItems),
+    ModifierTable(ContentItems),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ItemLink {
     pub title: String,
     pub url: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RenderingItem {
     pub links: Vec<ItemLink>,
     pub content: RenderingContent,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum SectionType {
     Documentation,
+    Examples,
+    Glossary,
     Modules,
     Bindings,
+    /// The items of an entry point other than `lib.ua`.
+    EntryPoint,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[allow(unused)]
 pub struct DocumentationSection {
     pub title: String,
+    /// The rendered intro text set in `uiua.toml`.
+    pub description: Option<String>,
     pub section_type: SectionType,
     pub content: Vec<RenderingItem>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+pub struct ArityBucket {
+    pub inputs: usize,
+    pub count: usize,
+    pub link_id: String,
+}
+
+/// A module on the front page, with figures that give an idea of its size before opening it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleCard {
+    pub name: String,
+    pub link_id: String,
+    pub summary: Option<String>,
+    /// The documented bindings of the module, including those of its nested modules.
+    pub binding_count: usize,
+    /// How many of those bindings are functions with each number of inputs.
+    pub arity_mix: Vec<ArityBucket>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct DocumentationSummary {
     pub title: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// The URL the docs are published at.
+    pub documentation: Option<String>,
+    pub import_snippet: String,
     pub sections: Vec<DocumentationSection>,
+    pub arity_histogram: Vec<ArityBucket>,
+    pub module_cards: Vec<ModuleCard>,
+    pub examples: Vec<ExampleNotebook>,
+    pub glossary: Option<Glossary>,
+    pub permissions: Vec<Permission>,
+}
+
+impl DocumentationSummary {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Unable to serialize the documentation summary")
+    }
 }
 
-pub fn summarize_content(content: &FileContent, title: String) -> DocumentationSummary {
+/// Writes the summary the site is rendered from to `model.json` in `output_directory`, instead of the site.
+pub fn generate_model_file(output_directory: &Path, summary: &DocumentationSummary) -> io::Result<()> {
+    if output_directory.exists() {
+        remove_dir_all(output_directory)?;
+    }
+    create_dir_all(output_directory)?;
+    write(output_directory.join("model.json"), summary.to_json())
+}
+
+/// Summarizes the items of `content`, with the `!doc` comments of `landing` on the front page.
+pub fn summarize_content(
+    content: &FileContent,
+    landing: &FileContent,
+    metadata: &ProjectMetadata,
+    examples: Vec<ExampleNotebook>,
+    glossary: Option<Glossary>,
+    permissions: Vec<Permission>,
+    hooks: &MarkdownHooks,
+) -> DocumentationSummary {
     let mut sections = Vec::new();
 
-    if let Some(documentation) = summarize_doc_comments(content) {
+    if let Some(documentation) = summarize_doc_comments(content, landing, metadata, hooks) {
         sections.push(documentation);
     }
 
+    if let Some(examples) = summarize_examples(&examples, hooks) {
+        sections.push(examples);
+    }
+
+    if let Some(glossary) = &glossary {
+        sections.push(summarize_glossary(glossary, hooks));
+    }
+
     if let Some(modules) = summarize_modules(&content.items) {
         sections.push(DocumentationSection {
             title: "Modules".to_owned(),
+            description: None,
             section_type: SectionType::Modules,
             content: modules
                 .iter()
@@ -78,7 +160,9 @@ pub fn summarize_content(content: &FileContent, title: String) -> DocumentationS
                                     title: module.name.clone(),
                                     link_id: module.name.clone(),
                                 },
+                                description: None,
                                 items: vec![item.clone()],
+                                import_snippet: Some(metadata.import_snippet(Some(&module.name))),
                             }),
                         }
                     } else {
@@ -89,28 +173,231 @@ pub fn summarize_content(content: &FileContent, title: String) -> DocumentationS
         });
     }
 
-    if let Some(bindings) = summarize_bindings(&content.items) {
+    if let Some(bindings) = summarize_bindings(&content.items, &metadata.groups, hooks) {
         sections.push(DocumentationSection {
             title: "Bindings".to_owned(),
+            description: None,
             section_type: SectionType::Bindings,
             content: bindings,
         });
     }
 
+    configure_sections(&mut sections, metadata, hooks);
+
     DocumentationSummary {
-        title: title.clone(),
+        title: metadata.title().to_owned(),
+        version: metadata.version.clone(),
+        description: metadata.description.clone(),
+        documentation: metadata.documentation.clone(),
+        import_snippet: metadata.import_snippet(None),
         sections,
+        arity_histogram: summarize_arity_histogram(&content.items),
+        module_cards: summarize_module_cards(&content.items),
+        examples,
+        glossary,
+        permissions,
+    }
+}
+
+/// The keys of the sections whose title and description can be set in `uiua.toml`.
+pub fn configurable_sections() -> Vec<&'static str> {
+    let mut keys = vec!["modules", "bindings", "modifiers_overview"];
+    keys.extend(BINDING_SECTIONS.iter().map(|section| section.link_id.trim_start_matches('_')));
+    keys
+}
+
+/// Applies the `[sections]` of `uiua.toml` to the Modules and Bindings sections and to the groups of bindings,
+/// which are keyed by their link id without the leading underscores.
+fn configure_sections(sections: &mut [DocumentationSection], metadata: &ProjectMetadata, hooks: &MarkdownHooks) {
+    for section in sections {
+        let key = match section.section_type {
+            SectionType::Modules => "modules",
+            SectionType::Bindings => "bindings",
+            _ => continue,
+        };
+        if let Some(config) = metadata.sections.get(key) {
+            configure_section(&mut section.title, &mut section.description, config, hooks);
+        }
+        if !matches!(section.section_type, SectionType::Bindings) {
+            continue;
+        }
+
+        for item in &mut section.content {
+            if let RenderingContent::Items(group) | RenderingContent::ModifierTable(group) = &mut item.content {
+                if let Some(config) = metadata.sections.get(group.title.link_id.trim_start_matches('_')) {
+                    configure_section(&mut group.title.title, &mut group.description, config, hooks);
+                }
+            }
+        }
+    }
+}
+
+fn configure_section(title: &mut String, description: &mut Option<String>, config: &SectionConfig, hooks: &MarkdownHooks) {
+    if let Some(custom_title) = &config.title {
+        *title = custom_title.clone();
+    }
+    *description = config.description.as_deref().map(|description| hooks.render(description));
+}
+
+/// A section for an entry point other than `lib.ua`, like the `main.ua` of a program, with its documented items.
+pub fn summarize_entry_point(file: &FileContent) -> Option<DocumentationSection> {
+    let mut items = file
+        .items
+        .iter()
+        .filter(|item| match item {
+            ItemContent::Binding(binding) => binding.is_documented(),
+            ItemContent::Module(module) => module.has_public_items(),
+            ItemContent::Data(_) | ItemContent::Variant(_) => true,
+            _ => false,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    sort_by_order(&mut items);
+    if items.is_empty() {
+        return None;
     }
+
+    Some(DocumentationSection {
+        title: file.relative_path.clone(),
+        description: None,
+        section_type: SectionType::EntryPoint,
+        content: vec![RenderingItem {
+            links: vec![],
+            content: RenderingContent::Items(ContentItems {
+                title: Title {
+                    title: format!("Defined in {}", file.relative_path),
+                    link_id: format!("__entry_{}", file.relative_path),
+                },
+                description: None,
+                items,
+                import_snippet: None,
+            }),
+        }],
+    })
 }
 
-fn summarize_doc_comments(content: &FileContent) -> Option<DocumentationSection> {
-    let doc_comments = extract_doc_comments(&content.items);
-    if doc_comments.is_empty() {
+/// Lists the example notebooks on the main page, with a sidebar link to each of them.
+fn summarize_examples(examples: &[ExampleNotebook], hooks: &MarkdownHooks) -> Option<DocumentationSection> {
+    if examples.is_empty() {
         return None;
     }
 
+    let mut markdown = "# Examples\n\n".to_owned();
+    for example in examples {
+        markdown.push_str(&format!("- [{}]({})", example.heading(), example.page_path()));
+        if let Some(summary) = example.summary() {
+            markdown.push_str(&format!(": {}", summary));
+        }
+        markdown.push('\n');
+    }
+
+    let mut item = summarize_doc_comment(&markdown, hooks);
+    item.links = examples
+        .iter()
+        .map(|example| ItemLink {
+            title: example.heading().to_owned(),
+            url: example.page_path(),
+        })
+        .collect();
+
+    Some(DocumentationSection {
+        title: "Examples".to_owned(),
+        description: None,
+        section_type: SectionType::Examples,
+        content: vec![item],
+    })
+}
+
+/// Points to the glossary page, which has its own table of contents.
+fn summarize_glossary(glossary: &Glossary, hooks: &MarkdownHooks) -> DocumentationSection {
+    let markdown = format!(
+        "# Glossary\n\nThe [glossary]({}) defines {} {} used throughout this documentation.\n",
+        GLOSSARY_PAGE,
+        glossary.terms.len(),
+        pluralize(glossary.terms.len(), "term", "terms")
+    );
+
+    let mut item = summarize_doc_comment(&markdown, hooks);
+    item.links = vec![ItemLink {
+        title: "Glossary".to_owned(),
+        url: GLOSSARY_PAGE.to_owned(),
+    }];
+
+    DocumentationSection {
+        title: "Glossary".to_owned(),
+        description: None,
+        section_type: SectionType::Glossary,
+        content: vec![item],
+    }
+}
+
+fn summarize_arity_histogram(items: &[ItemContent]) -> Vec<ArityBucket> {
+    BINDING_SECTIONS
+        .iter()
+        .filter_map(|section| match section.filter {
+            BindingFilter::Functions(inputs) => Some(ArityBucket {
+                inputs,
+                count: filter_items(items, section.filter).len(),
+                link_id: section.link_id.to_owned(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn summarize_module_cards(items: &[ItemContent]) -> Vec<ModuleCard> {
+    summarize_modules(items)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Module(module) => {
+                let mut bindings = Vec::new();
+                collect_module_bindings(&module.items, &mut bindings);
+                Some(ModuleCard {
+                    name: module.name.clone(),
+                    link_id: module.name.clone(),
+                    summary: module.summary().map(str::to_owned),
+                    binding_count: bindings.len(),
+                    arity_mix: summarize_arity_histogram(&bindings),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn collect_module_bindings(items: &[ItemContent], bindings: &mut Vec<ItemContent>) {
+    for item in items {
+        match item {
+            ItemContent::Binding(binding) if binding.is_documented() => bindings.push(item.clone()),
+            ItemContent::Module(module) => collect_module_bindings(&module.items, bindings),
+            _ => {}
+        }
+    }
+}
+
+/// The Markdown of the `!doc` comments of the landing file, with the metadata variables substituted.
+pub(crate) fn landing_doc_comments(landing: &FileContent, metadata: &ProjectMetadata) -> Vec<String> {
+    extract_doc_comments(&landing.items)
+        .iter()
+        .map(|comment| substitute_variables(comment, metadata))
+        .collect()
+}
+
+fn summarize_doc_comments(
+    content: &FileContent,
+    landing: &FileContent,
+    metadata: &ProjectMetadata,
+    hooks: &MarkdownHooks,
+) -> Option<DocumentationSection> {
+    let doc_comments = landing_doc_comments(landing, metadata);
+
     let mut items = Vec::new();
-    items.extend(doc_comments.iter().map(|comment| summarize_doc_comment(comment)));
+    items.extend(doc_comments.iter().map(|comment| summarize_doc_comment(comment, hooks)));
+
+    if !has_getting_started(&doc_comments) {
+        items.push(summarize_doc_comment(&generate_getting_started(&content.items, metadata), hooks));
+    }
 
     if items.is_empty() {
         return None;
@@ -118,15 +405,56 @@ fn summarize_doc_comments(content: &FileContent) -> Option<DocumentationSection>
 
     Some(DocumentationSection {
         title: "Documentation".to_owned(),
+        description: None,
         section_type: SectionType::Documentation,
         content: items,
     })
 }
 
-fn summarize_doc_comment(comment: &str) -> RenderingItem {
+/// Whether the author already wrote a section explaining how to start using the library.
+pub(crate) fn has_getting_started(doc_comments: &[String]) -> bool {
+    const HEADINGS: [&str; 5] = ["getting started", "quick start", "quickstart", "installation", "usage"];
+
+    doc_comments.iter().flat_map(|comment| comment.lines()).any(|line| {
+        let line = line.trim();
+        line.starts_with('#') && {
+            let heading = line.trim_start_matches('#').trim().to_lowercase();
+            HEADINGS.iter().any(|candidate| heading.starts_with(candidate))
+        }
+    })
+}
+
+pub(crate) fn generate_getting_started(items: &[ItemContent], metadata: &ProjectMetadata) -> String {
+    let example_bindings = items
+        .iter()
+        .filter_map(|item| match item {
+            ItemContent::Binding(binding) if binding.is_documented() => Some(binding.name.as_str()),
+            _ => None,
+        })
+        .take(3)
+        .collect::<Vec<_>>();
+
+    let installation = match &metadata.repository {
+        Some(_) => "Uiua fetches the library automatically when you import it:".to_owned(),
+        None => format!("Copy `{}` into your project and import it:", metadata.import_source()),
+    };
+
+    let mut markdown = format!("# Getting started\n\n{}\n\n```\n{}\n```\n", installation, metadata.import_snippet(None));
+
+    if !example_bindings.is_empty() {
+        markdown.push_str(&format!(
+            "\nImport only the bindings you need by listing them after the path:\n\n```\n{}\n```\n",
+            metadata.import_snippet(Some(&example_bindings.join(" ")))
+        ));
+    }
+
+    markdown
+}
+
+fn summarize_doc_comment(comment: &str, hooks: &MarkdownHooks) -> RenderingItem {
     let mut links = Vec::new();
 
-    let html = markdown::to_html_with_options(comment, &markdown::Options::gfm()).expect("Unable to convert markdown to HTML");
+    let html = hooks.render(comment);
 
     let document = kuchiki::parse_html().from_utf8().one(html.as_bytes());
     document
@@ -175,11 +503,32 @@ fn summarize_doc_comment(comment: &str) -> RenderingItem {
     }
 }
 
+/// Replaces variables like `{{version}}` in `!doc` comments with the project's metadata, so the
+/// prose stays accurate across releases. Variables without a value are left as written.
+fn substitute_variables(comment: &str, metadata: &ProjectMetadata) -> String {
+    let variables = [
+        ("name", Some(metadata.name.as_str())),
+        ("version", metadata.version.as_deref()),
+        ("description", metadata.description.as_deref()),
+        ("repository", metadata.repository.as_deref()),
+        ("uiua_version", Some(uiua::VERSION)),
+    ];
+
+    variables
+        .iter()
+        .filter_map(|(variable, value)| value.map(|value| (variable, value)))
+        .fold(comment.to_owned(), |comment, (variable, value)| {
+            comment.replace(&format!("{{{{{}}}}}", variable), value)
+        })
+}
+
 fn extract_doc_comments(items: &[ItemContent]) -> Vec<String> {
     items
         .iter()
         .filter_map(|item| {
             if let ItemContent::Words { code } = item {
+                // Chunks start with the indentation of their first line
+                let code = code.trim_start();
                 if code.starts_with("# !doc") {
                     let comment = code
                         .lines()
@@ -196,237 +545,240 @@ fn extract_doc_comments(items: &[ItemContent]) -> Vec<String> {
         .collect()
 }
 
-fn summarize_bindings(items: &[ItemContent]) -> Option<Vec<RenderingItem>> {
-    let mut results = Vec::new();
+#[derive(Debug, Clone, Copy)]
+enum BindingFilter {
+    Constants,
+    Data,
+    CodeMacros,
+    IndexMacros,
+    Functions(usize),
+    /// Functions whose signature isn't known, because the library wasn't compiled.
+    UnknownFunctions,
+}
 
-    if let Some(item) = summarize_constants(items) {
-        results.push(item);
+impl BindingFilter {
+    fn matches(&self, item: &ItemContent) -> bool {
+        match (self, item) {
+            (BindingFilter::Data, ItemContent::Data(_) | ItemContent::Variant(_)) => true,
+            (filter, ItemContent::Binding(binding)) => {
+                let matches_kind = match (filter, &binding.kind) {
+                    (BindingFilter::Constants, BindingType::Const(_)) => true,
+                    (BindingFilter::CodeMacros, BindingType::CodeMacro(_)) => true,
+                    (BindingFilter::IndexMacros, BindingType::IndexMacro(_)) => true,
+                    (BindingFilter::Functions(inputs), BindingType::Function(function)) => {
+                        function.signature.as_ref().is_some_and(|signature| signature.inputs == *inputs)
+                    }
+                    (BindingFilter::UnknownFunctions, BindingType::Function(function)) => function.signature.is_none(),
+                    _ => false,
+                };
+                matches_kind && binding.is_documented()
+            }
+            _ => false,
+        }
     }
+}
 
-    if let Some(data) = summarize_data(items) {
-        results.push(RenderingItem {
-            links: vec![],
-            content: RenderingContent::Items(ContentItems {
-                title: Title {
-                    title: "Data types".to_owned(),
-                    link_id: "__data".to_owned(),
-                },
-                items: data,
-            }),
-        });
-    }
+/// Describes one of the item groups rendered in the "Bindings" section.
+struct BindingSection {
+    filter: BindingFilter,
+    singular: &'static str,
+    plural: &'static str,
+    link_id: &'static str,
+}
 
-    if let Some(macros) = summarize_code_macros(items) {
-        results.push(RenderingItem {
-            links: vec![],
-            content: RenderingContent::Items(ContentItems {
-                title: Title {
-                    title: "Code macros".to_owned(),
-                    link_id: "__code_macros".to_owned(),
-                },
-                items: macros,
-            }),
-        });
+impl BindingSection {
+    fn title(&self, count: usize) -> String {
+        pluralize(count, self.singular, self.plural).to_owned()
     }
+}
 
-    if let Some(macros) = summarize_index_macros(items) {
-        results.push(RenderingItem {
-            links: vec![],
-            content: RenderingContent::Items(ContentItems {
-                title: Title {
-                    title: "Index macros".to_owned(),
-                    link_id: "__index_macros".to_owned(),
-                },
-                items: macros,
-            }),
-        });
+const BINDING_SECTIONS: [BindingSection; 12] = [
+    BindingSection {
+        filter: BindingFilter::Constants,
+        singular: "Constant",
+        plural: "Constants",
+        link_id: "__constants",
+    },
+    BindingSection {
+        filter: BindingFilter::Data,
+        singular: "Data type",
+        plural: "Data types",
+        link_id: "__data",
+    },
+    BindingSection {
+        filter: BindingFilter::CodeMacros,
+        singular: "Code macro",
+        plural: "Code macros",
+        link_id: "__code_macros",
+    },
+    BindingSection {
+        filter: BindingFilter::IndexMacros,
+        singular: "Index macro",
+        plural: "Index macros",
+        link_id: "__index_macros",
+    },
+    BindingSection {
+        filter: BindingFilter::Functions(0),
+        singular: "Noadic function",
+        plural: "Noadic functions",
+        link_id: "__noadic_functions",
+    },
+    BindingSection {
+        filter: BindingFilter::Functions(1),
+        singular: "Monadic function",
+        plural: "Monadic functions",
+        link_id: "__monadic_functions",
+    },
+    BindingSection {
+        filter: BindingFilter::Functions(2),
+        singular: "Dyadic function",
+        plural: "Dyadic functions",
+        link_id: "__dyadic_functions",
+    },
+    BindingSection {
+        filter: BindingFilter::Functions(3),
+        singular: "Triadic function",
+        plural: "Triadic functions",
+        link_id: "__triadic_functions",
+    },
+    BindingSection {
+        filter: BindingFilter::Functions(4),
+        singular: "Tetradic function",
+        plural: "Tetradic functions",
+        link_id: "__tetradic_functions",
+    },
+    BindingSection {
+        filter: BindingFilter::Functions(5),
+        singular: "Pentadic function",
+        plural: "Pentadic functions",
+        link_id: "__pentadic_functions",
+    },
+    BindingSection {
+        filter: BindingFilter::Functions(6),
+        singular: "Hexadic function",
+        plural: "Hexadic functions",
+        link_id: "__hexadic_functions",
+    },
+    BindingSection {
+        filter: BindingFilter::UnknownFunctions,
+        singular: "Function with unknown signature",
+        plural: "Functions with unknown signatures",
+        link_id: "__unknown_functions",
+    },
+];
+
+pub fn pluralize<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    if count == 1 {
+        singular
+    } else {
+        plural
     }
+}
 
-    if let Some(functions) = summarize_functions(items, 0) {
-        results.push(RenderingItem {
-            links: vec![],
-            content: RenderingContent::Items(ContentItems {
-                title: Title {
-                    title: "Noadic functions".to_owned(),
-                    link_id: "__noadic_functions".to_owned(),
-                },
-                items: functions,
-            }),
-        });
-    }
+fn filter_items(items: &[ItemContent], filter: BindingFilter) -> Vec<ItemContent> {
+    let mut items = items.iter().filter(|item| filter.matches(item)).cloned().collect::<Vec<_>>();
+    sort_by_order(&mut items);
+    items
+}
 
-    if let Some(functions) = summarize_functions(items, 1) {
-        results.push(RenderingItem {
-            links: vec![],
-            content: RenderingContent::Items(ContentItems {
-                title: Title {
-                    title: "Monadic functions".to_owned(),
-                    link_id: "__monadic_functions".to_owned(),
-                },
-                items: functions,
-            }),
-        });
+/// Moves the bindings with an `!doc order` directive to the front, including those of modules, keeping the source
+/// order of the rest.
+fn sort_by_order(items: &mut [ItemContent]) {
+    items.sort_by_key(|item| match item {
+        ItemContent::Binding(binding) => binding.order().map_or((1, 0), |order| (0, order)),
+        _ => (1, 0),
+    });
+    for item in items {
+        if let ItemContent::Module(module) = item {
+            sort_by_order(&mut module.items);
+        }
     }
+}
 
-    if let Some(functions) = summarize_functions(items, 2) {
-        results.push(RenderingItem {
-            links: vec![],
-            content: RenderingContent::Items(ContentItems {
-                title: Title {
-                    title: "Dyadic functions".to_owned(),
-                    link_id: "__dyadic_functions".to_owned(),
-                },
-                items: functions,
-            }),
-        });
-    }
+fn summarize_bindings(items: &[ItemContent], groups: &[GroupConfig], hooks: &MarkdownHooks) -> Option<Vec<RenderingItem>> {
+    let mut results = Vec::new();
 
-    if let Some(functions) = summarize_functions(items, 3) {
+    if let Some(modifiers) = summarize_modifiers(items) {
         results.push(RenderingItem {
             links: vec![],
-            content: RenderingContent::Items(ContentItems {
+            content: RenderingContent::ModifierTable(ContentItems {
                 title: Title {
-                    title: "Triadic functions".to_owned(),
-                    link_id: "__triadic_functions".to_owned(),
+                    title: "Modifiers overview".to_owned(),
+                    link_id: "__modifiers_overview".to_owned(),
                 },
-                items: functions,
+                description: None,
+                items: modifiers,
+                import_snippet: None,
             }),
         });
     }
 
-    if let Some(functions) = summarize_functions(items, 4) {
-        results.push(RenderingItem {
-            links: vec![],
-            content: RenderingContent::Items(ContentItems {
-                title: Title {
-                    title: "Tetradic functions".to_owned(),
-                    link_id: "__tetradic_functions".to_owned(),
-                },
-                items: functions,
-            }),
-        });
-    }
+    // A binding in more than one family is listed in the first one
+    let family = |item: &ItemContent| match item {
+        ItemContent::Binding(binding) if binding.is_documented() => groups
+            .iter()
+            .position(|group| !group.prefix.is_empty() && binding.name.starts_with(&group.prefix)),
+        _ => None,
+    };
+    for (index, group) in groups.iter().enumerate() {
+        let mut group_items = items.iter().filter(|item| family(item) == Some(index)).cloned().collect::<Vec<_>>();
+        if group_items.is_empty() {
+            continue;
+        }
+        sort_by_order(&mut group_items);
 
-    if let Some(functions) = summarize_functions(items, 5) {
         results.push(RenderingItem {
             links: vec![],
             content: RenderingContent::Items(ContentItems {
                 title: Title {
-                    title: "Pentadic functions".to_owned(),
-                    link_id: "__pentadic_functions".to_owned(),
+                    title: group.title.clone().unwrap_or_else(|| group.prefix.clone()),
+                    link_id: format!("__group_{}", group.prefix),
                 },
-                items: functions,
+                description: group.description.as_deref().map(|description| hooks.render(description)),
+                items: group_items,
+                import_snippet: None,
             }),
         });
     }
 
-    if let Some(functions) = summarize_functions(items, 6) {
+    let items = items.iter().filter(|item| family(item).is_none()).cloned().collect::<Vec<_>>();
+    for section in &BINDING_SECTIONS {
+        let section_items = filter_items(&items, section.filter);
+        if section_items.is_empty() {
+            continue;
+        }
+
         results.push(RenderingItem {
             links: vec![],
             content: RenderingContent::Items(ContentItems {
                 title: Title {
-                    title: "Hexadic functions".to_owned(),
-                    link_id: "__hexadic_functions".to_owned(),
+                    title: section.title(section_items.len()),
+                    link_id: section.link_id.to_owned(),
                 },
-                items: functions,
+                description: None,
+                items: section_items,
+                import_snippet: None,
             }),
         });
     }
 
-    Some(results)
-}
-
-fn summarize_constants(items: &[ItemContent]) -> Option<RenderingItem> {
-    let constants = items
-        .iter()
-        .filter(|item| {
-            if let ItemContent::Binding(binding) = item {
-                if let BindingType::Const(_) = &binding.kind {
-                    return binding.public;
-                }
-            }
-            false
-        })
-        .collect::<Vec<_>>();
-
-    if constants.is_empty() {
-        return None;
-    }
-
-    Some(RenderingItem {
-        links: vec![],
-        content: RenderingContent::Items(ContentItems {
-            title: Title {
-                title: "Constants".to_owned(),
-                link_id: "__constants".to_owned(),
-            },
-            items: constants.iter().map(|item| (*item).clone()).collect(),
-        }),
-    })
-}
-
-fn summarize_functions(items: &[ItemContent], num_inputs: usize) -> Option<Vec<ItemContent>> {
-    let functions = items
-        .iter()
-        .filter(|item| {
-            if let ItemContent::Binding(binding) = item {
-                if let BindingType::Function(function) = &binding.kind {
-                    if function.signature.inputs == num_inputs {
-                        return binding.public;
-                    }
-                }
-            }
-            false
-        })
-        .collect::<Vec<_>>();
-
-    if functions.is_empty() {
+    if results.is_empty() {
         return None;
     }
 
-    Some(functions.iter().map(|item| (*item).clone()).collect())
-}
-
-fn summarize_index_macros(items: &[ItemContent]) -> Option<Vec<ItemContent>> {
-    let macros = items
-        .iter()
-        .filter(|item| {
-            if let ItemContent::Binding(binding) = item {
-                if let BindingType::IndexMacro(_) = &binding.kind {
-                    return binding.public;
-                }
-            }
-            false
-        })
-        .collect::<Vec<_>>();
-
-    if macros.is_empty() {
-        return None;
-    }
-
-    Some(macros.iter().map(|item| (*item).clone()).collect())
+    Some(results)
 }
 
-fn summarize_code_macros(items: &[ItemContent]) -> Option<Vec<ItemContent>> {
-    let macros = items
+fn summarize_modifiers(items: &[ItemContent]) -> Option<Vec<ItemContent>> {
+    let mut modifiers = items
         .iter()
-        .filter(|item| {
-            if let ItemContent::Binding(BindingDefinition {
-                public,
-                kind: BindingType::CodeMacro(_),
-                ..
-            }) = item
-            {
-                *public
-            } else {
-                false
-            }
-        })
+        .filter(|item| BindingFilter::IndexMacros.matches(item) || BindingFilter::CodeMacros.matches(item))
         .cloned()
         .collect::<Vec<_>>();
+    sort_by_order(&mut modifiers);
 
-    if !macros.is_empty() {
-        Some(macros)
+    if !modifiers.is_empty() {
+        Some(modifiers)
     } else {
         None
     }
@@ -457,41 +809,44 @@ fn summarize_modules(items: &[ItemContent]) -> Option<Vec<ItemContent>> {
                         ItemContent::Module(module) => module.name.clone(),
                         _ => panic!("Expected module item"),
                     },
+                    module_path: Vec::new(),
                     items: match item {
-                        ItemContent::Module(module) => module
-                            .items
-                            .iter()
-                            .filter(|item| match item {
-                                ItemContent::Binding(binding) => binding.public,
-                                ItemContent::Module(module) => module.has_public_items(),
-                                ItemContent::Variant(_) => true,
-                                ItemContent::Data(_) => true,
-                                _ => false,
-                            })
-                            .cloned()
-                            .collect(),
+                        ItemContent::Module(module) => {
+                            let mut items = module
+                                .items
+                                .iter()
+                                .filter(|item| match item {
+                                    ItemContent::Binding(binding) => binding.is_documented(),
+                                    ItemContent::Module(module) => module.has_public_items(),
+                                    ItemContent::Variant(_) => true,
+                                    ItemContent::Data(_) => true,
+                                    _ => false,
+                                })
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            sort_by_order(&mut items);
+                            items
+                        }
                         _ => panic!("Expected module item"),
                     },
                     comment: match item {
                         ItemContent::Module(module) => module.comment.clone(),
                         _ => None,
                     },
+                    readme: match item {
+                        ItemContent::Module(module) => module.readme.clone(),
+                        _ => None,
+                    },
+                    dependencies: match item {
+                        ItemContent::Module(module) => module.dependencies.clone(),
+                        _ => Vec::new(),
+                    },
+                    location: match item {
+                        ItemContent::Module(module) => module.location.clone(),
+                        _ => panic!("Expected module item"),
+                    },
                 })
             })
             .collect(),
     )
 }
-
-fn summarize_data(items: &[ItemContent]) -> Option<Vec<ItemContent>> {
-    let data = items
-        .iter()
-        .filter(|item| matches!(item, ItemContent::Data(_) | ItemContent::Variant(_)))
-        .cloned()
-        .collect::<Vec<_>>();
-
-    if !data.is_empty() {
-        Some(data)
-    } else {
-        None
-    }
-}
@@ -0,0 +1,225 @@
+use semver::Version;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/ekgame/uiua-doc-gen/releases/latest";
+
+/// Set to anything to turn off the check for newer versions after generating docs.
+pub const NO_UPDATE_CHECK_VARIABLE: &str = "UIUA_DOC_GEN_NO_UPDATE_CHECK";
+
+/// How long to wait between checks for newer versions.
+const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    #[error("Unable to fetch the latest release: {0}")]
+    Http(#[from] ureq::Error),
+
+    #[error("The latest release has an invalid version: {0}")]
+    Version(#[from] semver::Error),
+
+    #[error("Release {0} has no binary for {1}-{2}")]
+    NoBinary(Version, &'static str, &'static str),
+
+    #[error("Release {0} has no {1}.sha256 checksum, so its binary can't be verified")]
+    NoChecksum(Version, String),
+
+    #[error("The checksum of {0} doesn't match the downloaded binary, so it wasn't installed")]
+    ChecksumMismatch(String),
+
+    #[error("Unable to replace the binary: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug)]
+pub enum UpdateOutcome {
+    UpToDate(Version),
+    Updated { from: Version, to: Version },
+}
+
+pub fn current_version() -> Version {
+    Version::parse(env!("CARGO_PKG_VERSION")).expect("The package version is valid")
+}
+
+fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder().timeout_global(Some(timeout)).build().into()
+}
+
+fn latest_release(agent: &ureq::Agent) -> Result<(Version, Release), UpdateError> {
+    let release: Release = agent
+        .get(LATEST_RELEASE_URL)
+        .header("User-Agent", concat!("uiua-doc-gen/", env!("CARGO_PKG_VERSION")))
+        .header("Accept", "application/vnd.github+json")
+        .call()?
+        .body_mut()
+        .read_json()?;
+    let version = Version::parse(release.tag_name.trim_start_matches('v'))?;
+    Ok((version, release))
+}
+
+/// The binary of a release built for this platform. Archives and checksums are skipped,
+/// since only plain executables can replace the running one.
+fn platform_asset(release: &Release) -> Option<&ReleaseAsset> {
+    let os_names: &[&str] = match env::consts::OS {
+        "macos" => &["macos", "darwin", "apple"],
+        os => &[os],
+    };
+    let arch_names: &[&str] = match env::consts::ARCH {
+        "x86_64" => &["x86_64", "amd64", "x64"],
+        "aarch64" => &["aarch64", "arm64"],
+        arch => &[arch],
+    };
+
+    release.assets.iter().find(|asset| {
+        let name = asset.name.to_lowercase();
+        let is_archive = [".zip", ".tar.gz", ".tgz", ".sha256", ".sig"]
+            .iter()
+            .any(|extension| name.ends_with(extension));
+        !is_archive && os_names.iter().any(|os| name.contains(os)) && arch_names.iter().any(|arch| name.contains(arch))
+    })
+}
+
+/// The `.sha256` file published next to a binary, in the format of `sha256sum`.
+fn checksum_asset<'a>(release: &'a Release, binary: &ReleaseAsset) -> Option<&'a ReleaseAsset> {
+    let name = format!("{}.sha256", binary.name);
+    release.assets.iter().find(|asset| asset.name.eq_ignore_ascii_case(&name))
+}
+
+/// The hash for the file in a checksum file, which lists `<hash>  <file>` lines or only the hash.
+fn expected_checksum(checksums: &str, file: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts
+            .next()
+            .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))?;
+        match parts.next() {
+            Some(name) if name.trim_start_matches('*') != file => None,
+            _ => Some(hash.to_ascii_lowercase()),
+        }
+    })
+}
+
+fn download(agent: &ureq::Agent, url: &str, limit: u64) -> Result<Vec<u8>, UpdateError> {
+    Ok(agent
+        .get(url)
+        .header("User-Agent", concat!("uiua-doc-gen/", env!("CARGO_PKG_VERSION")))
+        .call()?
+        .body_mut()
+        .with_config()
+        .limit(limit)
+        .read_to_vec()?)
+}
+
+/// Replaces the running binary with the latest release from GitHub, if it's newer. The binary is only installed
+/// when it matches the checksum published with it.
+pub fn self_update() -> Result<UpdateOutcome, UpdateError> {
+    let agent = agent(Duration::from_secs(120));
+    let current = current_version();
+    let (latest, release) = latest_release(&agent)?;
+    if latest <= current {
+        return Ok(UpdateOutcome::UpToDate(current));
+    }
+
+    let asset = platform_asset(&release).ok_or_else(|| UpdateError::NoBinary(latest.clone(), env::consts::OS, env::consts::ARCH))?;
+    let checksum = checksum_asset(&release, asset).ok_or_else(|| UpdateError::NoChecksum(latest.clone(), asset.name.clone()))?;
+    let checksums = download(&agent, &checksum.browser_download_url, 64 * 1024)?;
+    let expected = expected_checksum(&String::from_utf8_lossy(&checksums), &asset.name)
+        .ok_or_else(|| UpdateError::NoChecksum(latest.clone(), asset.name.clone()))?;
+
+    let binary = download(&agent, &asset.browser_download_url, 200 * 1024 * 1024)?;
+    let actual = Sha256::digest(&binary).iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    if actual != expected {
+        return Err(UpdateError::ChecksumMismatch(asset.name.clone()));
+    }
+
+    replace_executable(&binary)?;
+    Ok(UpdateOutcome::Updated { from: current, to: latest })
+}
+
+fn replace_executable(binary: &[u8]) -> Result<(), std::io::Error> {
+    let executable = env::current_exe()?.canonicalize()?;
+    let downloaded = executable.with_extension("download");
+    fs::write(&downloaded, binary)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&downloaded, fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Windows doesn't allow replacing a running executable, but it does allow renaming it
+    #[cfg(windows)]
+    {
+        let previous = executable.with_extension("old.exe");
+        let _ = fs::remove_file(&previous);
+        fs::rename(&executable, &previous)?;
+    }
+
+    fs::rename(&downloaded, &executable)
+}
+
+fn update_check_stamp() -> PathBuf {
+    env::temp_dir().join("uiua-doc-gen-update-check")
+}
+
+/// A notice about a newer release, checked at most once a day and quickly giving up when offline.
+pub fn update_notice() -> Option<String> {
+    if env::var_os(NO_UPDATE_CHECK_VARIABLE).is_some() {
+        return None;
+    }
+
+    let stamp = update_check_stamp();
+    let checked_recently = fs::metadata(&stamp)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|elapsed| elapsed < UPDATE_CHECK_INTERVAL);
+    if checked_recently {
+        return None;
+    }
+    let _ = fs::write(&stamp, "");
+
+    let (latest, _) = latest_release(&agent(Duration::from_secs(2))).ok()?;
+    (latest > current_version()).then(|| {
+        format!(
+            "uiua-doc-gen {} is available (you have {}), run `uiua-doc-gen self-update` to update.",
+            latest,
+            current_version()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_checksum_of_the_binary() {
+        let hash = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert_eq!(expected_checksum(hash, "uiua-doc-gen-linux-x86_64").as_deref(), Some(hash));
+        let listing = format!(
+            "{}  uiua-doc-gen-macos-arm64\n{} *uiua-doc-gen-linux-x86_64\n",
+            "0".repeat(64),
+            hash.to_uppercase()
+        );
+        assert_eq!(expected_checksum(&listing, "uiua-doc-gen-linux-x86_64").as_deref(), Some(hash));
+        assert_eq!(expected_checksum("not a checksum", "uiua-doc-gen-linux-x86_64"), None);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::analysis::{tokenize_code, CodeToken};
+use uiua::Primitive;
+
+fn type_name(code: f64) -> Option<&'static str> {
+    match code as i64 {
+        0 => Some("a number array"),
+        1 => Some("a character array"),
+        2 => Some("a box array"),
+        3 => Some("a complex array"),
+        _ => None,
+    }
+}
+
+fn rank_name(rank: f64) -> String {
+    match rank as i64 {
+        0 => "a scalar".to_owned(),
+        1 => "a list".to_owned(),
+        2 => "a table".to_owned(),
+        rank => format!("an array of rank {}", rank),
+    }
+}
+
+fn comparison_name(prim: Primitive) -> Option<&'static str> {
+    match prim {
+        Primitive::Gt => Some("greater than"),
+        Primitive::Ge => Some("at least"),
+        Primitive::Lt => Some("less than"),
+        Primitive::Le => Some("at most"),
+        Primitive::Eq => Some("equal to"),
+        Primitive::Ne => Some("different from"),
+        _ => None,
+    }
+}
+
+fn format_number(number: f64) -> String {
+    if number < 0.0 {
+        format!("¯{}", -number)
+    } else {
+        number.to_string()
+    }
+}
+
+/// Describes a field validator in plain words, if it follows one of the common validation idioms.
+pub fn explain_validator(code: &str) -> Option<String> {
+    use CodeToken::{Number, Primitive as Prim};
+    use Primitive::*;
+
+    let explanation = match tokenize_code(code).as_slice() {
+        [Prim(Un), Number(kind), Prim(Type)] => format!("must be {}", type_name(*kind)?),
+        [Prim(Un), Number(rank), Prim(Len), Prim(Shape)] => format!("must be {}", rank_name(*rank)),
+        [Prim(Un), Number(1.0), Prim(comparison), Number(value)] => {
+            format!("must be {} {}", comparison_name(*comparison)?, format_number(*value))
+        }
+        [Prim(Un), Number(1.0), Prim(Eq), Prim(Floor), Prim(Dup)] | [Prim(Un), Number(1.0), Prim(By), Prim(Floor), Prim(Eq)] => {
+            "must be an integer".to_owned()
+        }
+        [Prim(Un), Prim(Box)] => "must be boxed".to_owned(),
+        _ => return None,
+    };
+
+    Some(explanation)
+}